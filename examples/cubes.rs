@@ -21,9 +21,12 @@ fn main() {
                 ((i / map_size as i32) * 2) as f32,
             );
             let size = get_pos_perlin((pos.0, pos.1)) * 2f32;
+            // tint the taller cubes more green
+            let green = (size / 4f32).clamp(0f32, 1f32);
             TransformBuilder::default()
                 .pos((pos.0 * 5f32, size, pos.1 * 5f32))
                 .scale((4.2f32, size, 4.2f32))
+                .tint((1f32 - green, 0.6f32 + 0.4f32 * green, 1f32 - green, 1f32))
                 .build()
                 .unwrap()
         })
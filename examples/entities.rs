@@ -0,0 +1,75 @@
+extern crate caper;
+extern crate time;
+
+use caper::ecs::World;
+use caper::game::*;
+use caper::imgui::Ui;
+use caper::input::Key;
+use caper::mesh::gen_cube;
+use caper::types::{DefaultTag, RenderItemBuilder, Transform, TransformBuilder};
+use caper::utils::handle_fp_inputs;
+
+/// Component for entities that bob up and down
+struct Bob {
+    /// The offset of the bobbing
+    phase: f64,
+}
+
+fn main() {
+    // crate an instance of the game struct
+    let (mut game, event_loop) = Game::<DefaultTag>::new();
+
+    // define some entities with a render item, a transform and a user component
+    for i in 0..5 {
+        let entity = game.world.spawn();
+        let _ = game.world.insert(
+            entity,
+            RenderItemBuilder::<DefaultTag>::default()
+                .vertices(gen_cube())
+                .build()
+                .unwrap(),
+        );
+        let _ = game.world.insert(
+            entity,
+            TransformBuilder::default()
+                .pos((i as f32 * 2f32 - 4f32, 0.0, -5.0))
+                .build()
+                .unwrap(),
+        );
+        let _ = game.world.insert(
+            entity,
+            Bob {
+                phase: f64::from(i),
+            },
+        );
+    }
+
+    // add a system that bobs the entities
+    let start_time = game.renderer.start_time;
+    game.systems
+        .add_system("bob", 1, move |world: &mut World, _| {
+            let frame_time = time::precise_time_s() - start_time;
+            world.par_join_mut::<Bob, Transform, _>(|_, bob, transform| {
+                transform.pos.1 = (frame_time + bob.phase).sin() as f32;
+            });
+        });
+
+    start_loop(event_loop, move |events| {
+        // run the engine update
+        game.update(
+            |_: &Ui| {},
+            |g: &mut Game<DefaultTag>| -> UpdateStatus {
+                // update the first person inputs
+                handle_fp_inputs(&mut g.input, &mut g.cams[0]);
+
+                // quit
+                if g.input.keys_down.contains(&Key::Escape) {
+                    return UpdateStatus::Finish;
+                }
+
+                UpdateStatus::Continue
+            },
+            events,
+        )
+    });
+}
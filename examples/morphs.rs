@@ -0,0 +1,85 @@
+extern crate caper;
+
+use caper::game::*;
+use caper::imgui::Ui;
+use caper::input::Key;
+use caper::mesh::gen_cube;
+use caper::morph::{MorphTarget, MorphsBuilder};
+use caper::tween::{Easing, Repeat, Tween, TweenTarget, TweenValue};
+use caper::types::{DefaultTag, RenderItemBuilder, TransformBuilder};
+use caper::utils::handle_fp_inputs;
+
+fn main() {
+    // crate an instance of the game struct
+    let (mut game, event_loop) = Game::<DefaultTag>::new();
+
+    // a cube with a target that stretches the top upwards
+    let vertices = gen_cube();
+    let stretched = vertices
+        .iter()
+        .map(|v| {
+            let mut v = *v;
+            if v.position[1] > 0.0 {
+                v.position[1] += 2.0;
+            }
+            v
+        })
+        .collect::<Vec<_>>();
+    let stretch = MorphTarget::from_vertices("stretch", &vertices, &stretched).unwrap();
+
+    // each instance has its own weight, the second starts half stretched
+    let mut morphs = MorphsBuilder::default()
+        .targets(vec![stretch])
+        .build()
+        .unwrap();
+    morphs.set_weight_by_name(1, "stretch", 0.5);
+
+    game.add_render_item(
+        RenderItemBuilder::default()
+            .name("cubes")
+            .vertices(vertices)
+            .morphs(Some(morphs))
+            .instance_transforms(vec![
+                TransformBuilder::default()
+                    .pos((-2.0, -1.0, -8.0))
+                    .build()
+                    .unwrap(),
+                TransformBuilder::default()
+                    .pos((2.0, -1.0, -8.0))
+                    .build()
+                    .unwrap(),
+            ])
+            .build()
+            .unwrap(),
+    );
+
+    // stretch the first instance back and forth
+    let mut tween = Tween::new(
+        TweenTarget::MorphWeight(0, 0, 0),
+        TweenValue::Float(1.0),
+        1.0,
+        Easing::SineInOut,
+    );
+    tween.repeat = Repeat::Forever;
+    tween.yoyo = true;
+    game.tweens.play(tween);
+
+    start_loop(event_loop, move |events| {
+        // run the engine update
+        game.update(
+            |_: &Ui| {},
+            |g: &mut Game<DefaultTag>| -> UpdateStatus {
+                // update the first person inputs
+                handle_fp_inputs(&mut g.input, &mut g.cams[0]);
+
+                // quit
+                if g.input.keys_down.contains(&Key::Escape) {
+                    return UpdateStatus::Finish;
+                }
+
+                UpdateStatus::Continue
+            },
+            events,
+        )
+    });
+}
@@ -0,0 +1,89 @@
+extern crate caper;
+
+use caper::game::*;
+use caper::imgui::Ui;
+use caper::input::Key;
+use caper::mesh::gen_cube;
+use caper::particles::{ParticleBlend, ParticleEmitterBuilder};
+use caper::types::{DefaultTag, RenderItemBuilder, TransformBuilder};
+use caper::utils::handle_fp_inputs;
+
+fn main() {
+    // crate an instance of the game struct
+    let (mut game, event_loop) = Game::<DefaultTag>::new();
+
+    // define some items to be rendered
+    game.add_render_item(
+        RenderItemBuilder::default()
+            .vertices(gen_cube())
+            .instance_transforms(vec![TransformBuilder::default()
+                .pos((0.0, -1.0, -5.0))
+                .build()
+                .unwrap()])
+            .build()
+            .unwrap(),
+    );
+
+    // smoke rising from the cube
+    game.add_particle_emitter(
+        ParticleEmitterBuilder::default()
+            .name("smoke")
+            .pos((0.0, -0.5, -5.0))
+            .spawn_rate(40f32)
+            .lifetime(3f32)
+            .velocity((0.0, 1.0, 0.0))
+            .velocity_spread((0.3, 0.2, 0.3))
+            .end_speed(0.2f32)
+            .start_color((0.4, 0.4, 0.4, 0.8))
+            .end_color((0.8, 0.8, 0.8, 0.0))
+            .start_size(0.2f32)
+            .end_size(1f32)
+            .build()
+            .unwrap(),
+    );
+
+    // sparks falling from above
+    game.add_particle_emitter(
+        ParticleEmitterBuilder::default()
+            .name("sparks")
+            .pos((2.0, 2.0, -5.0))
+            .spawn_rate(100f32)
+            .lifetime(1f32)
+            .velocity((0.0, 2.0, 0.0))
+            .velocity_spread((2.0, 1.0, 2.0))
+            .gravity((0.0, -9.81, 0.0))
+            .start_color((1.0, 0.8, 0.2, 1.0))
+            .end_color((1.0, 0.2, 0.0, 0.0))
+            .start_size(0.05f32)
+            .end_size(0.02f32)
+            .blend(ParticleBlend::Additive)
+            .build()
+            .unwrap(),
+    );
+
+    // run the engine update
+    start_loop(event_loop, move |events| {
+        game.update(
+            |_: &Ui| {},
+            |g: &mut Game<DefaultTag>| -> UpdateStatus {
+                // update the first person inputs
+                handle_fp_inputs(&mut g.input, &mut g.cams[0]);
+
+                // burst of smoke
+                if g.input.keys_pressed.contains(&Key::Space) {
+                    if let Some(smoke) = g.get_particle_emitter_by_name("smoke") {
+                        smoke.burst(100);
+                    }
+                }
+
+                // quit
+                if g.input.keys_down.contains(&Key::Escape) {
+                    return UpdateStatus::Finish;
+                }
+
+                UpdateStatus::Continue
+            },
+            events,
+        )
+    });
+}
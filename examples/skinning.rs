@@ -0,0 +1,104 @@
+extern crate caper;
+
+use caper::animation::{AnimationClipBuilder, Interpolation, JointTrackBuilder, Track};
+use caper::game::*;
+use caper::imgui::Ui;
+use caper::input::Key;
+use caper::math::{Quat, Vec3};
+use caper::mesh::gen_cube;
+use caper::skinning::{JointBuilder, Skeleton, Skin, SkinVertex};
+use caper::types::{DefaultTag, RenderItemBuilder, TransformBuilder};
+use caper::utils::handle_fp_inputs;
+
+fn main() {
+    // crate an instance of the game struct
+    let (mut game, event_loop) = Game::<DefaultTag>::new();
+
+    // a bar from y 0 to 4 with a joint at the bottom and one in the middle
+    let mut vertices = gen_cube();
+    for v in vertices.iter_mut() {
+        v.position = [
+            v.position[0] * 0.5,
+            (v.position[1] + 1.0) * 2.0,
+            v.position[2] * 0.5,
+        ];
+    }
+    let skeleton = Skeleton::from_bind_pose(vec![
+        JointBuilder::default().name("base").build().unwrap(),
+        JointBuilder::default()
+            .name("middle")
+            .parent(Some(0))
+            .bind(
+                TransformBuilder::default()
+                    .pos((0.0, 2.0, 0.0))
+                    .rot((0.0, 0.0, 0.0, 1.0))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    ])
+    .unwrap();
+    // vertices at the top follow the middle joint
+    let skin_vertices = vertices
+        .iter()
+        .map(|v| {
+            let top = v.position[1] / 4.0;
+            SkinVertex::new(&[(0, 1.0 - top), (1, top)])
+        })
+        .collect();
+
+    let mut skin = Skin::new(skeleton, skin_vertices).unwrap();
+    skin.player.play("wave", true);
+
+    game.add_render_item(
+        RenderItemBuilder::default()
+            .name("bar")
+            .vertices(vertices)
+            .skin(Some(skin))
+            .instance_transforms(vec![TransformBuilder::default()
+                .pos((0.0, -2.0, -8.0))
+                .build()
+                .unwrap()])
+            .build()
+            .unwrap(),
+    );
+
+    // bend the middle joint back and forth
+    let bend = |angle: f32| -> (f32, f32, f32, f32) {
+        Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), angle.to_radians()).into()
+    };
+    game.animations.add_clip(
+        AnimationClipBuilder::default()
+            .name("wave")
+            .joints(vec![JointTrackBuilder::default()
+                .joint("middle")
+                .rot(Some(Track::new(
+                    vec![(0.0, bend(-45.0)), (1.0, bend(45.0)), (2.0, bend(-45.0))],
+                    Interpolation::Cubic,
+                )))
+                .build()
+                .unwrap()])
+            .build()
+            .unwrap(),
+    );
+
+    start_loop(event_loop, move |events| {
+        // run the engine update
+        game.update(
+            |_: &Ui| {},
+            |g: &mut Game<DefaultTag>| -> UpdateStatus {
+                // update the first person inputs
+                handle_fp_inputs(&mut g.input, &mut g.cams[0]);
+
+                // quit
+                if g.input.keys_down.contains(&Key::Escape) {
+                    return UpdateStatus::Finish;
+                }
+
+                UpdateStatus::Continue
+            },
+            events,
+        )
+    });
+}
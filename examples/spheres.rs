@@ -3,6 +3,7 @@ extern crate caper;
 use caper::game::*;
 use caper::imgui::Ui;
 use caper::input::Key;
+use caper::lod::{LodMetric, Lods};
 use caper::mesh::{gen_sphere, gen_sphere_segments};
 use caper::types::{DefaultTag, RenderItemBuilder, TransformBuilder};
 use caper::utils::handle_fp_inputs;
@@ -43,6 +44,28 @@ fn main() {
             .unwrap(),
     );
 
+    // a row of detailed spheres that are simplified further away
+    let detailed = gen_sphere_segments(32f32, 32f32);
+    let mut lods = Lods::generate(&detailed, &[(20.0, 8), (40.0, 4)], LodMetric::Distance);
+    lods.fade = 4.0;
+    game.add_render_item(
+        RenderItemBuilder::default()
+            .vertices(detailed)
+            .lods(Some(lods))
+            .instance_transforms(
+                (0..20)
+                    .map(|i| {
+                        TransformBuilder::default()
+                            .pos((0.0, 3.0, -5.0 - i as f32 * 4.0))
+                            .build()
+                            .unwrap()
+                    })
+                    .collect(),
+            )
+            .build()
+            .unwrap(),
+    );
+
     start_loop(event_loop, move |events| {
         // run the engine update
         game.update(
@@ -0,0 +1,69 @@
+extern crate caper;
+
+use caper::game::*;
+use caper::imgui::Ui;
+use caper::input::Key;
+use caper::types::{DefaultTag, SpriteAnimation, SpriteItemBuilder, TextItemBuilder};
+
+fn main() {
+    // crate an instance of the game struct
+    let (mut game, event_loop) = Game::<DefaultTag>::new();
+    game.input.hide_mouse = false;
+
+    // a static sprite
+    game.add_sprite_item(
+        SpriteItemBuilder::default()
+            .name("logo")
+            .pos((200f32, 200f32))
+            .size((128f32, 128f32))
+            .build()
+            .unwrap(),
+    );
+
+    // a sprite animated through the four quarters of the texture
+    game.add_sprite_item(
+        SpriteItemBuilder::default()
+            .name("animated")
+            .pos((400f32, 200f32))
+            .size((128f32, 128f32))
+            .z(1)
+            .animation(Some(SpriteAnimation {
+                fps: 2f32,
+                ..SpriteAnimation::from_grid(2, 2, 4)
+            }))
+            .build()
+            .unwrap(),
+    );
+
+    game.add_text_item(
+        TextItemBuilder::default()
+            .text("sprites")
+            .pos((-0.9, 0.9, 0f32))
+            .build()
+            .unwrap(),
+    );
+
+    // run the engine update
+    start_loop(event_loop, move |events| {
+        game.update(
+            |_: &Ui| {},
+            |g: &mut Game<DefaultTag>| -> UpdateStatus {
+                // spin and follow the mouse with the first sprite
+                let mouse_pos = g.input.mouse_pos;
+                let delta = g.delta;
+                if let Some(logo) = g.get_sprite_item_by_name("logo") {
+                    logo.pos = mouse_pos;
+                    logo.rotation += delta;
+                }
+
+                // quit
+                if g.input.keys_down.contains(&Key::Escape) {
+                    return UpdateStatus::Finish;
+                }
+
+                UpdateStatus::Continue
+            },
+            events,
+        )
+    });
+}
@@ -0,0 +1,110 @@
+extern crate caper;
+
+use caper::game::*;
+use caper::imgui::Ui;
+use caper::input::Key;
+use caper::mesh::gen_cube;
+use caper::state::State;
+use caper::types::{DefaultTag, RenderItemBuilder, TextItemBuilder, TransformBuilder};
+use caper::utils::handle_fp_inputs;
+
+/// The main menu, waits for enter to start playing
+struct Menu;
+
+impl State<DefaultTag> for Menu {
+    fn on_enter(&mut self, game: &mut Game<DefaultTag>) {
+        game.add_text_item(
+            TextItemBuilder::default()
+                .text("Press enter to play")
+                .pos((-0.4, 0.0, 0.0))
+                .build()
+                .unwrap(),
+        );
+    }
+
+    fn update(&mut self, game: &mut Game<DefaultTag>) -> UpdateStatus {
+        if game.input.keys_pressed.contains(&Key::Return) {
+            return UpdateStatus::Replace("play".into());
+        }
+        UpdateStatus::Continue
+    }
+
+    // the menu has its own items which are dropped when it is replaced
+    fn shares_items(&self) -> bool {
+        false
+    }
+}
+
+/// The gameplay, P pauses the game
+struct Play;
+
+impl State<DefaultTag> for Play {
+    fn on_enter(&mut self, game: &mut Game<DefaultTag>) {
+        game.add_render_item(
+            RenderItemBuilder::default()
+                .vertices(gen_cube())
+                .instance_transforms(vec![TransformBuilder::default()
+                    .pos((0.0, 0.0, -5.0))
+                    .build()
+                    .unwrap()])
+                .build()
+                .unwrap(),
+        );
+    }
+
+    fn update(&mut self, game: &mut Game<DefaultTag>) -> UpdateStatus {
+        handle_fp_inputs(&mut game.input, &mut game.cams[0]);
+
+        if game.input.keys_pressed.contains(&Key::P) {
+            return UpdateStatus::Push("pause".into());
+        }
+        UpdateStatus::Continue
+    }
+
+    fn shares_items(&self) -> bool {
+        false
+    }
+}
+
+/// The pause screen, shown over the gameplay until P is pressed again
+struct Pause;
+
+impl State<DefaultTag> for Pause {
+    fn update(&mut self, game: &mut Game<DefaultTag>) -> UpdateStatus {
+        if game.input.keys_pressed.contains(&Key::P) {
+            return UpdateStatus::Pop;
+        }
+        UpdateStatus::Continue
+    }
+
+    fn draw_ui(&mut self, ui: &Ui) {
+        ui.text("Paused");
+    }
+}
+
+fn main() {
+    // crate an instance of the game struct
+    let (mut game, event_loop) = Game::<DefaultTag>::new();
+
+    // register the states and start at the menu
+    game.states.register("menu", || Box::new(Menu));
+    game.states.register("play", || Box::new(Play));
+    game.states.register("pause", || Box::new(Pause));
+    game.push_state("menu").unwrap();
+
+    start_loop(event_loop, move |events| {
+        // run the engine update
+        game.update(
+            |_: &Ui| {},
+            |g: &mut Game<DefaultTag>| -> UpdateStatus {
+                // quit from any state
+                if g.input.keys_down.contains(&Key::Escape) {
+                    return UpdateStatus::Finish;
+                }
+
+                UpdateStatus::Continue
+            },
+            events,
+        )
+    });
+}
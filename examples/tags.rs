@@ -1,5 +1,4 @@
 extern crate caper;
-extern crate rayon;
 extern crate time;
 
 use caper::game::*;
@@ -9,9 +8,7 @@ use caper::mesh::gen_cube;
 use caper::types::{RenderItemBuilder, TransformBuilder};
 use caper::utils::handle_fp_inputs;
 
-use rayon::prelude::*;
-
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 enum Tags {
     One,
     Two,
@@ -62,16 +59,12 @@ fn main() {
                 let frame_time = time::precise_time_s() - g.renderer.start_time;
 
                 // update items by tag
-                g.render_items_iter_mut().for_each(|item| {
-                    match item.tag {
-                        Tags::One => {
-                            item.instance_transforms[0].pos.1 = frame_time.sin() as f32;
-                        }
-                        Tags::Two => {
-                            item.instance_transforms[0].pos.1 = frame_time.cos() as f32;
-                        }
-                    };
-                });
+                for item in g.items_with_tag(&Tags::One) {
+                    item.instance_transforms[0].pos.1 = frame_time.sin() as f32;
+                }
+                for item in g.items_with_tag(&Tags::Two) {
+                    item.instance_transforms[0].pos.1 = frame_time.cos() as f32;
+                }
 
                 // quit
                 if g.input.keys_down.contains(&Key::Escape) {
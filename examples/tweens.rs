@@ -0,0 +1,116 @@
+extern crate caper;
+
+use caper::events::EngineEvent;
+use caper::game::*;
+use caper::imgui::Ui;
+use caper::input::Key;
+use caper::mesh::gen_cube;
+use caper::tween::{
+    Easing, PostShaderField, Repeat, Timer, Tween, TweenBuilder, TweenNode, TweenTarget, TweenValue,
+};
+use caper::types::{DefaultTag, RenderItemBuilder, TextItemBuilder, TransformBuilder};
+use caper::utils::handle_fp_inputs;
+
+fn main() {
+    // crate an instance of the game struct
+    let (mut game, event_loop) = Game::<DefaultTag>::new();
+
+    // define some items to be rendered
+    game.add_render_item(
+        RenderItemBuilder::default()
+            .vertices(gen_cube())
+            .instance_transforms(vec![TransformBuilder::default()
+                .pos((0.0, 0.0, -5.0))
+                .build()
+                .unwrap()])
+            .build()
+            .unwrap(),
+    );
+    game.add_text_item(
+        TextItemBuilder::default()
+            .text("Tweens")
+            .pos((-0.9, 0.8, 0.0))
+            .build()
+            .unwrap(),
+    );
+
+    // bob the cube up and down forever
+    game.tweens.play(
+        TweenBuilder::default()
+            .target(TweenTarget::InstancePos(0, 0))
+            .to(TweenValue::Vec3((0.0, 1.0, -5.0)))
+            .duration(1.0)
+            .easing(Easing::SineInOut)
+            .repeat(Repeat::Forever)
+            .yoyo(true)
+            .build()
+            .unwrap(),
+    );
+
+    // grow the cube then fade out the text at the same time as scaling back
+    game.tweens.play(TweenNode::sequence(vec![
+        Tween::new(
+            TweenTarget::InstanceScale(0, 0),
+            TweenValue::Vec3((1.5, 1.5, 1.5)),
+            0.5,
+            Easing::BackOut,
+        )
+        .into(),
+        TweenNode::wait(1.0),
+        TweenNode::parallel(vec![
+            Tween::new(
+                TweenTarget::InstanceScale(0, 0),
+                TweenValue::Vec3((1.0, 1.0, 1.0)),
+                0.5,
+                Easing::BounceOut,
+            )
+            .into(),
+            Tween::new(
+                TweenTarget::TextColor(0),
+                TweenValue::Vec4((1.0, 1.0, 1.0, 0.0)),
+                2.0,
+                Easing::Linear,
+            )
+            .into(),
+        ]),
+    ]));
+
+    // pulse the chromatic aberration every couple of seconds
+    let pulse = game.tweens.start_timer(Timer::repeating(2.0));
+
+    start_loop(event_loop, move |events| {
+        // run the engine update
+        game.update(
+            |_: &Ui| {},
+            |g: &mut Game<DefaultTag>| -> UpdateStatus {
+                // update the first person inputs
+                handle_fp_inputs(&mut g.input, &mut g.cams[0]);
+
+                for event in g.events.read::<EngineEvent>().to_vec() {
+                    if event == EngineEvent::TimerFired(pulse) {
+                        g.tweens.play(
+                            TweenBuilder::default()
+                                .target(TweenTarget::PostShader(PostShaderField::ChromAmt))
+                                .from(Some(TweenValue::Float(0.0)))
+                                .to(TweenValue::Float(1.0))
+                                .duration(0.25)
+                                .easing(Easing::QuadOut)
+                                .yoyo(true)
+                                .repeat(Repeat::Times(2))
+                                .build()
+                                .unwrap(),
+                        );
+                    }
+                }
+
+                // quit
+                if g.input.keys_down.contains(&Key::Escape) {
+                    return UpdateStatus::Finish;
+                }
+
+                UpdateStatus::Continue
+            },
+            events,
+        )
+    });
+}
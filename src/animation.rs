@@ -0,0 +1,542 @@
+use std::collections::HashMap;
+
+use math::{Quat, Vec3};
+use skinning::{Pose, Skeleton};
+use types::{Quaternion, RenderItem, Transform, Vector3};
+
+/// How values are interpolated between keyframes
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub enum Interpolation {
+    /// Hold the value of the previous keyframe
+    Step,
+    /// Straight line between keyframes, rotations are slerped
+    #[default]
+    Linear,
+    /// Smooth curve through the keyframes, rotations are slerped
+    Cubic,
+}
+
+/// A value that can be keyframed
+pub trait Keyable: Copy {
+    /// Interpolate between a and b by t
+    fn lerp(a: Self, b: Self, t: f32) -> Self;
+    /// Interpolate between b and c by t using a and d to shape the curve
+    fn cubic(a: Self, b: Self, c: Self, d: Self, t: f32) -> Self;
+}
+
+impl Keyable for Vector3 {
+    fn lerp(a: Vector3, b: Vector3, t: f32) -> Vector3 {
+        Vec3::from(a).lerp(Vec3::from(b), t).into()
+    }
+
+    fn cubic(a: Vector3, b: Vector3, c: Vector3, d: Vector3, t: f32) -> Vector3 {
+        // catmull-rom spline
+        let (a, b, c, d) = (Vec3::from(a), Vec3::from(b), Vec3::from(c), Vec3::from(d));
+        let t2 = t * t;
+        let t3 = t2 * t;
+        let point = (b * 2f32
+            + (c - a) * t
+            + (a * 2f32 - b * 5f32 + c * 4f32 - d) * t2
+            + (b * 3f32 - a - c * 3f32 + d) * t3)
+            * 0.5f32;
+        point.into()
+    }
+}
+
+impl Keyable for Quaternion {
+    fn lerp(a: Quaternion, b: Quaternion, t: f32) -> Quaternion {
+        Quat::from(a).slerp(Quat::from(b), t).into()
+    }
+
+    fn cubic(_: Quaternion, b: Quaternion, c: Quaternion, _: Quaternion, t: f32) -> Quaternion {
+        Quaternion::lerp(b, c, t)
+    }
+}
+
+/// A value at a time in a track
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct Keyframe<V> {
+    /// The time of the keyframe in seconds
+    pub time: f32,
+    /// The value at the time
+    pub value: V,
+}
+
+/// Keyframes for a single value, sorted by time
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct Track<V> {
+    /// The keyframes sorted by time
+    pub keyframes: Vec<Keyframe<V>>,
+    /// How values are interpolated between the keyframes
+    pub interpolation: Interpolation,
+}
+
+impl<V: Keyable> Track<V> {
+    /// Create a track from (time, value) pairs, sorting them by time
+    pub fn new(keyframes: Vec<(f32, V)>, interpolation: Interpolation) -> Track<V> {
+        let mut keyframes = keyframes
+            .into_iter()
+            .map(|(time, value)| Keyframe { time, value })
+            .collect::<Vec<_>>();
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Track {
+            keyframes,
+            interpolation,
+        }
+    }
+
+    /// The time of the last keyframe
+    pub fn end_time(&self) -> f32 {
+        self.keyframes.last().map_or(0f32, |k| k.time)
+    }
+
+    /// The value at the time, values are held before the first and after the
+    /// last keyframe, None if there are no keyframes
+    pub fn sample(&self, time: f32) -> Option<V> {
+        let keys = &self.keyframes;
+        let first = keys.first()?;
+        if time <= first.time {
+            return Some(first.value);
+        }
+
+        // the index of the first keyframe after the time
+        let next = keys.partition_point(|k| k.time <= time);
+        if next >= keys.len() {
+            return keys.last().map(|k| k.value);
+        }
+
+        let (prev_key, next_key) = (&keys[next - 1], &keys[next]);
+        let span = next_key.time - prev_key.time;
+        let t = if span > 0f32 {
+            (time - prev_key.time) / span
+        } else {
+            1f32
+        };
+
+        Some(match self.interpolation {
+            Interpolation::Step => prev_key.value,
+            Interpolation::Linear => V::lerp(prev_key.value, next_key.value, t),
+            Interpolation::Cubic => {
+                let before = keys[next.saturating_sub(2)].value;
+                let after = keys[(next + 1).min(keys.len() - 1)].value;
+                V::cubic(before, prev_key.value, next_key.value, after, t)
+            }
+        })
+    }
+}
+
+/// The keyframed position, rotation and scale of a render item instance
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct TransformTrack {
+    /// The name of the render item
+    #[builder(setter(into))]
+    pub render_item: String,
+    /// The index of the instance of the render item
+    pub instance: usize,
+    /// The position keyframes
+    pub pos: Option<Track<Vector3>>,
+    /// The rotation keyframes
+    pub rot: Option<Track<Quaternion>>,
+    /// The scale keyframes
+    pub scale: Option<Track<Vector3>>,
+}
+
+impl Default for TransformTrack {
+    fn default() -> Self {
+        TransformTrack {
+            render_item: "ri".into(),
+            instance: 0,
+            pos: None,
+            rot: None,
+            scale: None,
+        }
+    }
+}
+
+impl TransformTrack {
+    /// The time of the last keyframe of any of the tracks
+    pub fn end_time(&self) -> f32 {
+        keys_end_time(&self.pos, &self.rot, &self.scale)
+    }
+
+    /// Write the values at the time to the transform, blending from the current
+    /// values by the weight
+    pub fn apply(&self, time: f32, weight: f32, transform: &mut Transform) {
+        apply_keys(&self.pos, &self.rot, &self.scale, time, weight, transform);
+    }
+}
+
+/// The keyframed local position, rotation and scale of a skeleton joint
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct JointTrack {
+    /// The name of the joint
+    #[builder(setter(into))]
+    pub joint: String,
+    /// The position keyframes
+    pub pos: Option<Track<Vector3>>,
+    /// The rotation keyframes
+    pub rot: Option<Track<Quaternion>>,
+    /// The scale keyframes
+    pub scale: Option<Track<Vector3>>,
+}
+
+impl Default for JointTrack {
+    fn default() -> Self {
+        JointTrack {
+            joint: "joint".into(),
+            pos: None,
+            rot: None,
+            scale: None,
+        }
+    }
+}
+
+impl JointTrack {
+    /// The time of the last keyframe of any of the tracks
+    pub fn end_time(&self) -> f32 {
+        keys_end_time(&self.pos, &self.rot, &self.scale)
+    }
+
+    /// Write the values at the time to the joint's local transform, blending
+    /// from the current values by the weight
+    pub fn apply(&self, time: f32, weight: f32, transform: &mut Transform) {
+        apply_keys(&self.pos, &self.rot, &self.scale, time, weight, transform);
+    }
+}
+
+/// The time of the last keyframe of any of the tracks
+fn keys_end_time(
+    pos: &Option<Track<Vector3>>,
+    rot: &Option<Track<Quaternion>>,
+    scale: &Option<Track<Vector3>>,
+) -> f32 {
+    let pos = pos.as_ref().map_or(0f32, |t| t.end_time());
+    let rot = rot.as_ref().map_or(0f32, |t| t.end_time());
+    let scale = scale.as_ref().map_or(0f32, |t| t.end_time());
+    pos.max(rot).max(scale)
+}
+
+/// Write the values of the tracks at the time to the transform, blending
+/// from the current values by the weight
+fn apply_keys(
+    pos: &Option<Track<Vector3>>,
+    rot: &Option<Track<Quaternion>>,
+    scale: &Option<Track<Vector3>>,
+    time: f32,
+    weight: f32,
+    transform: &mut Transform,
+) {
+    if let Some(pos) = pos.as_ref().and_then(|t| t.sample(time)) {
+        transform.pos = Vector3::lerp(transform.pos, pos, weight);
+    }
+    if let Some(rot) = rot.as_ref().and_then(|t| t.sample(time)) {
+        transform.rot = Quaternion::lerp(transform.rot, rot, weight);
+    }
+    if let Some(scale) = scale.as_ref().and_then(|t| t.sample(time)) {
+        transform.scale = Vector3::lerp(transform.scale, scale, weight);
+    }
+}
+
+/// An authored animation of render item instance transforms and skeleton joints
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct AnimationClip {
+    /// The name of the clip used to play it
+    #[builder(setter(into))]
+    pub name: String,
+    /// The tracks of each animated instance
+    pub tracks: Vec<TransformTrack>,
+    /// The tracks of each animated joint, applied to the skins playing the clip
+    #[serde(default)]
+    pub joints: Vec<JointTrack>,
+}
+
+impl Default for AnimationClip {
+    fn default() -> Self {
+        AnimationClip {
+            name: "clip".into(),
+            tracks: Vec::new(),
+            joints: Vec::new(),
+        }
+    }
+}
+
+impl AnimationClip {
+    /// The length of the clip, the time of its last keyframe
+    pub fn duration(&self) -> f32 {
+        self.tracks
+            .iter()
+            .map(|t| t.end_time())
+            .chain(self.joints.iter().map(|t| t.end_time()))
+            .fold(0f32, f32::max)
+    }
+
+    /// Write the joint tracks at the time to the pose, blending from its
+    /// current transforms by the weight
+    pub fn apply_pose(&self, time: f32, weight: f32, skeleton: &Skeleton, pose: &mut Pose) {
+        for track in self.joints.iter() {
+            let transform = skeleton
+                .joint_index(&track.joint)
+                .and_then(|i| pose.locals.get_mut(i));
+            if let Some(transform) = transform {
+                track.apply(time, weight, transform);
+            }
+        }
+    }
+
+    /// Write the clip at the time to the render items, blending from their
+    /// current transforms by the weight
+    pub fn apply<T: Default>(&self, time: f32, weight: f32, render_items: &mut [RenderItem<T>]) {
+        for track in self.tracks.iter() {
+            let transform = render_items
+                .iter_mut()
+                .find(|r| r.name == track.render_item)
+                .and_then(|r| r.instance_transforms.get_mut(track.instance));
+            if let Some(transform) = transform {
+                track.apply(time, weight, transform);
+            }
+        }
+    }
+}
+
+/// The time into a playing clip
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct ClipState {
+    /// The name of the clip
+    pub clip: String,
+    /// The time into the clip
+    pub time: f32,
+    /// Whether the clip loops
+    pub looping: bool,
+}
+
+impl ClipState {
+    /// Advance the time, wrapping or clamping to the duration
+    fn advance(&mut self, delta: f32, duration: f32) {
+        self.time += delta;
+        if duration <= 0f32 {
+            self.time = 0f32;
+        } else if self.looping {
+            self.time = self.time.rem_euclid(duration);
+        } else {
+            self.time = self.time.clamp(0f32, duration);
+        }
+    }
+}
+
+/// Plays clips with a speed, looping and crossfading between clips
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct AnimationPlayer {
+    /// The multiplier for the delta, negative plays backwards
+    pub speed: f32,
+    /// Whether the player is paused
+    pub paused: bool,
+    /// The clip being played
+    #[builder(setter(skip))]
+    current: Option<ClipState>,
+    /// The clip being faded out, the time into and the length of the fade
+    #[builder(setter(skip))]
+    fading: Option<(ClipState, f32, f32)>,
+}
+
+impl Default for AnimationPlayer {
+    fn default() -> Self {
+        AnimationPlayer {
+            speed: 1f32,
+            paused: false,
+            current: None,
+            fading: None,
+        }
+    }
+}
+
+impl AnimationPlayer {
+    /// Create a new player with nothing playing
+    pub fn new() -> AnimationPlayer {
+        Default::default()
+    }
+
+    /// Play a clip from the start
+    pub fn play(&mut self, clip: &str, looping: bool) {
+        self.current = Some(ClipState {
+            clip: clip.into(),
+            time: 0f32,
+            looping,
+        });
+        self.fading = None;
+    }
+
+    /// Play a clip from the start, blending from the current clip over the duration
+    pub fn crossfade(&mut self, clip: &str, duration: f32, looping: bool) {
+        let previous = self.current.take();
+        self.play(clip, looping);
+        self.fading = previous.map(|p| (p, 0f32, duration));
+    }
+
+    /// Stop playing, leaving the items where they are
+    pub fn stop(&mut self) {
+        self.current = None;
+        self.fading = None;
+    }
+
+    /// The clip being played
+    pub fn current(&self) -> Option<&ClipState> {
+        self.current.as_ref()
+    }
+
+    /// Whether the clip faded out from is still being blended
+    pub fn is_fading(&self) -> bool {
+        self.fading.is_some()
+    }
+
+    /// Jump to the time in the current clip
+    pub fn seek(&mut self, time: f32) {
+        if let Some(current) = self.current.as_mut() {
+            current.time = time;
+        }
+    }
+
+    /// Whether a clip that doesn't loop has reached its end
+    pub fn is_finished(&self, clips: &HashMap<String, AnimationClip>) -> bool {
+        self.current.as_ref().is_none_or(|c| {
+            !c.looping
+                && clips.get(&c.clip).is_none_or(|clip| {
+                    if self.speed < 0f32 {
+                        c.time <= 0f32
+                    } else {
+                        c.time >= clip.duration()
+                    }
+                })
+        })
+    }
+
+    /// Advance the clips by the delta, finishing the fade once it is complete
+    pub fn advance(&mut self, delta: f32, clips: &HashMap<String, AnimationClip>) {
+        let delta = if self.paused {
+            0f32
+        } else {
+            delta * self.speed
+        };
+
+        if let Some((ref mut state, ref mut elapsed, _)) = self.fading {
+            *elapsed += delta.abs();
+            if let Some(clip) = clips.get(&state.clip) {
+                state.advance(delta, clip.duration());
+            }
+        }
+        if self.fade_weight() >= 1f32 {
+            self.fading = None;
+        }
+
+        if let Some(ref mut state) = self.current {
+            if let Some(clip) = clips.get(&state.clip) {
+                state.advance(delta, clip.duration());
+            }
+        }
+    }
+
+    /// The clips being played and their weights, the clip being faded from is
+    /// written fully, then the current on top by the progress of the fade
+    pub fn layers(&self) -> Vec<(&ClipState, f32)> {
+        let fading = self.fading.as_ref().map(|(state, _, _)| (state, 1f32));
+        let current = self
+            .current
+            .as_ref()
+            .map(|state| (state, self.fade_weight()));
+        fading.into_iter().chain(current).collect()
+    }
+
+    /// Write the clips to the render items
+    pub fn apply<T: Default>(
+        &self,
+        clips: &HashMap<String, AnimationClip>,
+        render_items: &mut [RenderItem<T>],
+    ) {
+        for (state, weight) in self.layers() {
+            if let Some(clip) = clips.get(&state.clip) {
+                clip.apply(state.time, weight, render_items);
+            }
+        }
+    }
+
+    /// Write the joint tracks of the clips to the pose
+    pub fn apply_pose(
+        &self,
+        clips: &HashMap<String, AnimationClip>,
+        skeleton: &Skeleton,
+        pose: &mut Pose,
+    ) {
+        for (state, weight) in self.layers() {
+            if let Some(clip) = clips.get(&state.clip) {
+                clip.apply_pose(state.time, weight, skeleton, pose);
+            }
+        }
+    }
+
+    /// Advance the clips by the delta and write them to the render items
+    pub fn update<T: Default>(
+        &mut self,
+        delta: f32,
+        clips: &HashMap<String, AnimationClip>,
+        render_items: &mut [RenderItem<T>],
+    ) {
+        self.advance(delta, clips);
+        self.apply(clips, render_items);
+    }
+
+    /// The weight of the current clip over the one being faded from
+    fn fade_weight(&self) -> f32 {
+        match self.fading {
+            Some((_, elapsed, duration)) if duration > 0f32 => (elapsed / duration).min(1f32),
+            _ => 1f32,
+        }
+    }
+}
+
+/// The clips and the named players that play them
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Animations {
+    /// The clips by name
+    pub clips: HashMap<String, AnimationClip>,
+    /// The players by name
+    pub players: HashMap<String, AnimationPlayer>,
+}
+
+impl Animations {
+    /// Create an empty set of clips and players
+    pub fn new() -> Animations {
+        Default::default()
+    }
+
+    /// Add a clip, replacing any with the same name
+    pub fn add_clip(&mut self, clip: AnimationClip) {
+        self.clips.insert(clip.name.clone(), clip);
+    }
+
+    /// Get the player with the name, creating it if needed
+    pub fn player(&mut self, name: &str) -> &mut AnimationPlayer {
+        self.players.entry(name.into()).or_default()
+    }
+
+    /// Whether the player with the name has finished its clip
+    pub fn is_finished(&self, name: &str) -> bool {
+        self.players
+            .get(name)
+            .is_none_or(|p| p.is_finished(&self.clips))
+    }
+
+    /// Advance all of the players and write their clips to the render items,
+    /// then advance and pose the skins of the render items
+    pub fn update<T: Default>(&mut self, delta: f32, render_items: &mut [RenderItem<T>]) {
+        for player in self.players.values_mut() {
+            player.update(delta, &self.clips, render_items);
+        }
+
+        for skin in render_items.iter_mut().filter_map(|r| r.skin.as_mut()) {
+            skin.update(delta, &self.clips);
+        }
+    }
+}
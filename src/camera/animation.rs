@@ -0,0 +1,374 @@
+use noise::{NoiseFn, Perlin, Seedable};
+
+use std::f32::consts::PI;
+
+use super::{look_rotation, CameraController};
+use input::Input;
+use math::Vec3;
+use types::{Camera, Vector3};
+
+/// The time step used to find the direction of travel along a path
+const LOOK_AHEAD: f32 = 0.01f32;
+
+/// How the points of a camera path are joined
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum PathInterpolation {
+    /// A curve passing through every point
+    CatmullRom,
+    /// Cubic bezier segments, each key point is followed by two control points
+    /// e.g. key, control, control, key, control, control, key
+    Bezier,
+}
+
+/// Where the camera looks while following a path
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum PathLook {
+    /// Look in the direction of travel
+    Forward,
+    /// Look at a single point
+    Target(Vector3),
+    /// Look at a point for each key of the path, interpolated between keys
+    Targets(Vec<Vector3>),
+}
+
+/// A path for a camera to follow with timing for each key point
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct CameraPath {
+    /// The points of the path
+    pub points: Vec<Vector3>,
+    /// The time in seconds the camera reaches each key point,
+    /// if empty the keys are spaced evenly over the duration
+    pub key_times: Vec<f32>,
+    /// The time taken to follow the path when key_times is empty
+    pub duration: f32,
+    /// How the points are joined
+    pub interpolation: PathInterpolation,
+    /// Where the camera looks
+    pub look: PathLook,
+    /// Whether the path starts again once the end is reached
+    pub looping: bool,
+}
+
+impl Default for CameraPath {
+    fn default() -> Self {
+        CameraPath {
+            points: Vec::new(),
+            key_times: Vec::new(),
+            duration: 5f32,
+            interpolation: PathInterpolation::CatmullRom,
+            look: PathLook::Forward,
+            looping: false,
+        }
+    }
+}
+
+impl CameraPath {
+    /// The number of points the path passes through
+    pub fn key_count(&self) -> usize {
+        match self.interpolation {
+            PathInterpolation::CatmullRom => self.points.len(),
+            PathInterpolation::Bezier => (self.points.len().max(1) - 1) / 3 + 1,
+        }
+    }
+
+    /// The time the camera reaches the key point
+    pub fn key_time(&self, key: usize) -> f32 {
+        let count = self.key_count();
+        if self.key_times.len() == count {
+            self.key_times[key]
+        } else if count > 1 {
+            self.duration * key as f32 / (count - 1) as f32
+        } else {
+            0f32
+        }
+    }
+
+    /// The time taken to follow the whole path
+    pub fn end_time(&self) -> f32 {
+        self.key_time(self.key_count().max(1) - 1)
+    }
+
+    /// The position on the path at the time
+    pub fn position_at(&self, time: f32) -> Vector3 {
+        if self.key_count() < 2 {
+            return self.points.first().cloned().unwrap_or((0f32, 0f32, 0f32));
+        }
+
+        let (seg, t) = self.segment_at(time);
+        match self.interpolation {
+            PathInterpolation::CatmullRom => catmull_rom_segment(&self.points, seg, t),
+            PathInterpolation::Bezier => {
+                let i = seg * 3;
+                cubic_bezier(
+                    self.points[i],
+                    self.points[i + 1],
+                    self.points[i + 2],
+                    self.points[i + 3],
+                    t,
+                )
+            }
+        }
+    }
+
+    /// The camera on the path at the time, looking as set by look
+    pub fn camera_at(&self, time: f32) -> Camera {
+        let pos = self.position_at(time);
+        let at = |time: f32| Vec3::from(self.position_at(time));
+
+        let dir = match self.look {
+            PathLook::Forward => {
+                let ahead = at(time + LOOK_AHEAD) - Vec3::from(pos);
+                if ahead.length() > 0f32 {
+                    ahead
+                } else {
+                    Vec3::from(pos) - at(time - LOOK_AHEAD)
+                }
+            }
+            PathLook::Target(target) => Vec3::from(target) - Vec3::from(pos),
+            PathLook::Targets(ref targets) => {
+                let target = if targets.len() < 2 || self.key_count() < 2 {
+                    targets.first().cloned().unwrap_or(pos)
+                } else {
+                    let (seg, t) = self.segment_at(time);
+                    catmull_rom_segment(targets, seg.min(targets.len() - 2), t)
+                };
+                Vec3::from(target) - Vec3::from(pos)
+            }
+        };
+
+        let (pitch, yaw) = look_rotation(dir.into());
+        Camera {
+            pos,
+            euler_rot: (pitch, yaw, 0f32),
+        }
+    }
+
+    /// The segment between two keys at the time and how far along it
+    fn segment_at(&self, time: f32) -> (usize, f32) {
+        let end = self.end_time();
+        let time = if self.looping && end > 0f32 {
+            time.rem_euclid(end)
+        } else {
+            time.max(0f32).min(end)
+        };
+
+        let last = self.key_count() - 2;
+        let seg = (0..last)
+            .find(|&i| time < self.key_time(i + 1))
+            .unwrap_or(last);
+        let (start, finish) = (self.key_time(seg), self.key_time(seg + 1));
+        let t = if finish > start {
+            (time - start) / (finish - start)
+        } else {
+            1f32
+        };
+
+        (seg, t.clamp(0f32, 1f32))
+    }
+}
+
+/// Moves a camera along a path, when capturing with `Renderer::save_add_to_gif`
+/// update it with a fixed delta so the gif plays back at a steady speed
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct CameraPathPlayer {
+    /// The path being followed
+    pub path: CameraPath,
+    /// The time along the path in seconds
+    pub time: f32,
+    /// The rate time advances at, 1 is normal speed
+    pub speed: f32,
+    /// Whether the camera is moving along the path
+    pub playing: bool,
+}
+
+impl CameraPathPlayer {
+    /// Create a new player at the start of the path
+    pub fn new(path: CameraPath) -> CameraPathPlayer {
+        CameraPathPlayer {
+            path,
+            time: 0f32,
+            speed: 1f32,
+            playing: true,
+        }
+    }
+
+    /// Whether the end of a non looping path has been reached
+    pub fn finished(&self) -> bool {
+        !self.path.looping && self.time >= self.path.end_time()
+    }
+}
+
+impl CameraController for CameraPathPlayer {
+    fn update(&mut self, cam: &mut Camera, _input: &Input, delta: f32) {
+        if self.playing && !self.finished() {
+            self.time += delta * self.speed;
+        }
+
+        *cam = self.path.camera_at(self.time);
+    }
+}
+
+/// A smooth blend from one camera to another over a duration
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct CameraTransition {
+    /// The camera blended from
+    pub from: Camera,
+    /// The camera blended to
+    pub to: Camera,
+    /// The time taken to blend in seconds
+    pub duration: f32,
+    /// The time since the blend started
+    pub time: f32,
+}
+
+impl CameraTransition {
+    /// Create a new transition between two cameras
+    pub fn new(from: Camera, to: Camera, duration: f32) -> CameraTransition {
+        CameraTransition {
+            from,
+            to,
+            duration,
+            time: 0f32,
+        }
+    }
+
+    /// How far through the transition from 0 to 1
+    pub fn progress(&self) -> f32 {
+        if self.duration > 0f32 {
+            (self.time / self.duration).min(1f32)
+        } else {
+            1f32
+        }
+    }
+
+    /// Whether the transition has reached the to camera
+    pub fn finished(&self) -> bool {
+        self.progress() >= 1f32
+    }
+}
+
+impl CameraController for CameraTransition {
+    fn update(&mut self, cam: &mut Camera, _input: &Input, delta: f32) {
+        self.time += delta;
+
+        // ease in and out of the transition
+        let t = self.progress();
+        *cam = blend_cameras(&self.from, &self.to, t * t * (3f32 - 2f32 * t));
+    }
+}
+
+/// Trauma based camera shake, trauma is added by events and decays over time
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct CameraShake {
+    /// The current trauma from 0 to 1, the shake is trauma squared
+    pub trauma: f32,
+    /// The trauma removed per second
+    pub decay: f32,
+    /// The maximum offset of the position on each axis
+    pub max_offset: Vector3,
+    /// The maximum offset of the pitch, yaw and roll in radians
+    pub max_rotation: Vector3,
+    /// How fast the shake moves
+    pub frequency: f32,
+    /// The seed for the shake noise
+    pub seed: u32,
+    /// The time the shake has been running for
+    #[builder(setter(skip))]
+    time: f32,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        CameraShake {
+            trauma: 0f32,
+            decay: 1f32,
+            max_offset: (0.2f32, 0.2f32, 0.2f32),
+            max_rotation: (0.05f32, 0.05f32, 0.05f32),
+            frequency: 15f32,
+            seed: 0,
+            time: 0f32,
+        }
+    }
+}
+
+impl CameraShake {
+    /// Add trauma, the total is kept between 0 and 1
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0f32, 1f32);
+    }
+
+    /// The strength of the shake from 0 to 1
+    pub fn shake(&self) -> f32 {
+        self.trauma * self.trauma
+    }
+
+    /// Advance the shake and decay the trauma
+    pub fn update(&mut self, delta: f32) {
+        self.time += delta;
+        self.trauma = (self.trauma - self.decay * delta).max(0f32);
+    }
+
+    /// Get the camera with the shake applied, the camera passed in is left
+    /// untouched so it can be shaken again on the next frame
+    pub fn apply(&self, cam: &Camera) -> Camera {
+        let shake = self.shake();
+        if shake <= 0f32 {
+            return *cam;
+        }
+
+        let perlin = Perlin::new().set_seed(self.seed);
+        let t = f64::from(self.time * self.frequency);
+        let noise = |i: u32| perlin.get([f64::from(i) * 10.5f64, t]) as f32 * shake;
+
+        let offset = Vec3::new(noise(0), noise(1), noise(2)) * Vec3::from(self.max_offset);
+        let rotation = Vec3::new(noise(3), noise(4), noise(5)) * Vec3::from(self.max_rotation);
+
+        Camera {
+            pos: (Vec3::from(cam.pos) + offset).into(),
+            euler_rot: (Vec3::from(cam.euler_rot) + rotation).into(),
+        }
+    }
+}
+
+/// Blend between two cameras, taking the shortest way round for each angle
+pub fn blend_cameras(a: &Camera, b: &Camera, t: f32) -> Camera {
+    let angle = |a: f32, b: f32| a + ((b - a + PI).rem_euclid(PI * 2f32) - PI) * t;
+
+    Camera {
+        pos: Vec3::from(a.pos).lerp(Vec3::from(b.pos), t).into(),
+        euler_rot: (
+            angle(a.euler_rot.0, b.euler_rot.0),
+            angle(a.euler_rot.1, b.euler_rot.1),
+            angle(a.euler_rot.2, b.euler_rot.2),
+        ),
+    }
+}
+
+/// Catmull-Rom interpolation between points[seg] and points[seg + 1]
+fn catmull_rom_segment(points: &[Vector3], seg: usize, t: f32) -> Vector3 {
+    let last = points.len() - 1;
+    let p0 = Vec3::from(points[seg.max(1) - 1]);
+    let p1 = Vec3::from(points[seg]);
+    let p2 = Vec3::from(points[(seg + 1).min(last)]);
+    let p3 = Vec3::from(points[(seg + 2).min(last)]);
+
+    let (t2, t3) = (t * t, t * t * t);
+    let a = p1 * 2f32;
+    let b = (p2 - p0) * t;
+    let c = (p0 * 2f32 - p1 * 5f32 + p2 * 4f32 - p3) * t2;
+    let d = (p1 * 3f32 - p0 + p3 - p2 * 3f32) * t3;
+
+    ((a + b + c + d) * 0.5f32).into()
+}
+
+/// Cubic bezier interpolation from p0 to p3 with the control points p1 and p2
+fn cubic_bezier(p0: Vector3, p1: Vector3, p2: Vector3, p3: Vector3, t: f32) -> Vector3 {
+    let u = 1f32 - t;
+    (Vec3::from(p0) * (u * u * u)
+        + Vec3::from(p1) * (3f32 * u * u * t)
+        + Vec3::from(p2) * (3f32 * u * t * t)
+        + Vec3::from(p3) * (t * t * t))
+        .into()
+}
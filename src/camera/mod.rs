@@ -0,0 +1,538 @@
+/// Module for animating cameras along paths, between each other and with shake
+pub mod animation;
+
+use std::f32::consts::PI;
+
+use input::{Input, Key, MouseButton};
+use math::Vec3;
+use types::{Camera, Ray, Vector3};
+use utils::ray_aabb_test;
+
+/// The default pitch limit, just short of looking straight up or down
+const PITCH_LIMIT: f32 = PI / 2f32 - 0.01f32;
+
+/// Trait for types that move a camera each frame from the input
+pub trait CameraController {
+    /// Update the camera from the input, delta is the frame time in seconds
+    fn update(&mut self, cam: &mut Camera, input: &Input, delta: f32);
+}
+
+/// The keys used to move a camera
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct MovementKeys {
+    /// Move forward
+    pub forward: Key,
+    /// Move backwards
+    pub back: Key,
+    /// Move left
+    pub left: Key,
+    /// Move right
+    pub right: Key,
+    /// Move up
+    pub up: Key,
+    /// Move down
+    pub down: Key,
+}
+
+impl Default for MovementKeys {
+    fn default() -> Self {
+        MovementKeys {
+            forward: Key::W,
+            back: Key::S,
+            left: Key::A,
+            right: Key::D,
+            up: Key::Space,
+            down: Key::LShift,
+        }
+    }
+}
+
+impl MovementKeys {
+    /// The movement on each axis (right, up, forward) from the keys held down
+    pub fn axes(&self, input: &Input) -> Vector3 {
+        let axis = |pos: Key, neg: Key| {
+            let held = |k: Key| input.keys_down.contains(&k) as i32 as f32;
+            held(pos) - held(neg)
+        };
+
+        (
+            axis(self.right, self.left),
+            axis(self.up, self.down),
+            axis(self.forward, self.back),
+        )
+    }
+}
+
+/// Camera that walks on the xz plane and looks around with the mouse
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct FirstPersonController {
+    /// The movement speed in units per second
+    pub move_speed: f32,
+    /// The rotation in radians per unit of mouse movement
+    pub look_speed: f32,
+    /// The time in seconds taken to catch up with the input, 0 for none
+    pub smoothing: f32,
+    /// The min and max pitch in radians
+    pub pitch_limits: (f32, f32),
+    /// Whether moving the mouse up looks down
+    pub invert_y: bool,
+    /// The keys used to move
+    pub keys: MovementKeys,
+    /// The pitch and yaw the camera is turning towards
+    #[builder(setter(skip))]
+    #[serde(skip)]
+    rotation: Option<(f32, f32)>,
+    /// The current velocity of the camera
+    #[builder(setter(skip))]
+    #[serde(skip)]
+    velocity: Vec3,
+}
+
+impl Default for FirstPersonController {
+    fn default() -> Self {
+        FirstPersonController {
+            move_speed: 12f32,
+            look_speed: 1f32,
+            smoothing: 0f32,
+            pitch_limits: (-PITCH_LIMIT, PITCH_LIMIT),
+            invert_y: false,
+            keys: Default::default(),
+            rotation: None,
+            velocity: Vec3::default(),
+        }
+    }
+}
+
+impl CameraController for FirstPersonController {
+    fn update(&mut self, cam: &mut Camera, input: &Input, delta: f32) {
+        let t = smooth_factor(self.smoothing, delta);
+
+        let rotation = mouse_look(
+            self.rotation.unwrap_or_else(|| cam_rotation(cam)),
+            input.mouse_delta,
+            self.look_speed,
+            self.invert_y,
+            self.pitch_limits,
+        );
+        self.rotation = Some(rotation);
+        turn_towards(cam, rotation, t);
+
+        // walk along the ground in the direction the camera faces
+        let axes = self.keys.axes(input);
+        let (right, forward) = (
+            Vec3::from(right_dir(cam.euler_rot.1)),
+            flat_forward_dir(cam.euler_rot.1),
+        );
+        let target = (right * axes.0 + forward * axes.2).normalize() * self.move_speed;
+
+        self.velocity = self.velocity.lerp(target, t);
+        cam.pos = (Vec3::from(cam.pos) + self.velocity * delta).into();
+    }
+}
+
+/// Camera that flies in the direction it is looking
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct FreeFlyController {
+    /// The movement speed in units per second
+    pub move_speed: f32,
+    /// The speed is multiplied by this while the boost key is held
+    pub boost_multiplier: f32,
+    /// The key held to move faster
+    pub boost_key: Key,
+    /// The rotation in radians per unit of mouse movement
+    pub look_speed: f32,
+    /// The time in seconds taken to catch up with the input, 0 for none
+    pub smoothing: f32,
+    /// The min and max pitch in radians
+    pub pitch_limits: (f32, f32),
+    /// Whether moving the mouse up looks down
+    pub invert_y: bool,
+    /// The keys used to move, up and down move along the world y axis
+    pub keys: MovementKeys,
+    /// The pitch and yaw the camera is turning towards
+    #[builder(setter(skip))]
+    #[serde(skip)]
+    rotation: Option<(f32, f32)>,
+    /// The current velocity of the camera
+    #[builder(setter(skip))]
+    #[serde(skip)]
+    velocity: Vec3,
+}
+
+impl Default for FreeFlyController {
+    fn default() -> Self {
+        FreeFlyController {
+            move_speed: 12f32,
+            boost_multiplier: 4f32,
+            boost_key: Key::LControl,
+            look_speed: 1f32,
+            smoothing: 0.1f32,
+            pitch_limits: (-PITCH_LIMIT, PITCH_LIMIT),
+            invert_y: false,
+            keys: Default::default(),
+            rotation: None,
+            velocity: Vec3::default(),
+        }
+    }
+}
+
+impl CameraController for FreeFlyController {
+    fn update(&mut self, cam: &mut Camera, input: &Input, delta: f32) {
+        let t = smooth_factor(self.smoothing, delta);
+
+        let rotation = mouse_look(
+            self.rotation.unwrap_or_else(|| cam_rotation(cam)),
+            input.mouse_delta,
+            self.look_speed,
+            self.invert_y,
+            self.pitch_limits,
+        );
+        self.rotation = Some(rotation);
+        turn_towards(cam, rotation, t);
+
+        let axes = self.keys.axes(input);
+        let dir = Vec3::from(right_dir(cam.euler_rot.1)) * axes.0
+            + Vec3::from(forward_dir(cam.euler_rot.0, cam.euler_rot.1)) * axes.2
+            + Vec3::new(0f32, axes.1, 0f32);
+        let speed = if input.keys_down.contains(&self.boost_key) {
+            self.move_speed * self.boost_multiplier
+        } else {
+            self.move_speed
+        };
+
+        self.velocity = self.velocity.lerp(dir.normalize() * speed, t);
+        cam.pos = (Vec3::from(cam.pos) + self.velocity * delta).into();
+    }
+}
+
+/// Camera that orbits around a target point, rotated by dragging the mouse
+/// and zoomed with the mouse wheel
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct OrbitController {
+    /// The point the camera orbits around
+    pub target: Vector3,
+    /// The distance from the target
+    pub distance: f32,
+    /// The min and max distance from the target
+    pub distance_limits: (f32, f32),
+    /// The change in distance per unit of mouse wheel movement
+    pub zoom_speed: f32,
+    /// The rotation around the y axis in radians
+    pub yaw: f32,
+    /// The rotation above/below the target in radians
+    pub pitch: f32,
+    /// The min and max pitch in radians
+    pub pitch_limits: (f32, f32),
+    /// The rotation in radians per pixel dragged
+    pub drag_speed: f32,
+    /// The rotation in radians per second while a key is held
+    pub key_speed: f32,
+    /// The mouse button held to rotate, the mouse always rotates if None
+    pub drag_button: Option<MouseButton>,
+    /// The keys used to rotate, left/right for yaw, forward/back for pitch
+    /// and up/down to zoom
+    pub keys: MovementKeys,
+    /// The time in seconds taken to catch up with the input, 0 for none
+    pub smoothing: f32,
+    /// The mouse position on the previous frame
+    #[builder(setter(skip))]
+    #[serde(skip)]
+    last_mouse_pos: Option<(f32, f32)>,
+}
+
+impl Default for OrbitController {
+    fn default() -> Self {
+        OrbitController {
+            target: (0f32, 0f32, 0f32),
+            distance: 10f32,
+            distance_limits: (1f32, 100f32),
+            zoom_speed: 1f32,
+            yaw: 0f32,
+            pitch: -0.4f32,
+            pitch_limits: (-PITCH_LIMIT, PITCH_LIMIT),
+            drag_speed: 0.01f32,
+            key_speed: 2f32,
+            drag_button: Some(MouseButton::Left),
+            keys: MovementKeys {
+                forward: Key::Up,
+                back: Key::Down,
+                left: Key::Left,
+                right: Key::Right,
+                up: Key::PageUp,
+                down: Key::PageDown,
+            },
+            smoothing: 0.05f32,
+            last_mouse_pos: None,
+        }
+    }
+}
+
+impl CameraController for OrbitController {
+    fn update(&mut self, cam: &mut Camera, input: &Input, delta: f32) {
+        let dragging = self
+            .drag_button
+            .is_none_or(|b| input.mouse_btns_down.contains(&b));
+        let mouse_move = match self.last_mouse_pos {
+            Some(last) if dragging => (input.mouse_pos.0 - last.0, input.mouse_pos.1 - last.1),
+            _ => (0f32, 0f32),
+        };
+        self.last_mouse_pos = Some(input.mouse_pos);
+
+        let axes = self.keys.axes(input);
+        self.yaw -= mouse_move.0 * self.drag_speed + axes.0 * self.key_speed * delta;
+        self.pitch = clamp(
+            self.pitch + mouse_move.1 * self.drag_speed + axes.2 * self.key_speed * delta,
+            self.pitch_limits,
+        );
+        self.distance = clamp(
+            self.distance - (input.mouse_wheel_delta.1 + axes.1 * delta * 10f32) * self.zoom_speed,
+            self.distance_limits,
+        );
+
+        let target = Vec3::from(self.target);
+        let pos = target - Vec3::from(forward_dir(self.pitch, self.yaw)) * self.distance;
+
+        let t = smooth_factor(self.smoothing, delta);
+        let cam_pos = Vec3::from(cam.pos).lerp(pos, t);
+        cam.pos = cam_pos.into();
+        let (pitch, yaw) = look_rotation((target - cam_pos).into());
+        cam.euler_rot = (pitch, yaw, 0f32);
+    }
+}
+
+/// Camera that follows behind a target, pulling in to avoid obstacles
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct FollowController {
+    /// The position being followed, update this each frame
+    pub target: Vector3,
+    /// The height above the target that the camera looks at
+    pub height: f32,
+    /// The distance behind the target
+    pub distance: f32,
+    /// The closest the camera will be pulled in by obstacles
+    pub min_distance: f32,
+    /// The rotation in radians per unit of mouse movement
+    pub look_speed: f32,
+    /// The rotation around the target in radians
+    pub yaw: f32,
+    /// The rotation above/below the target in radians
+    pub pitch: f32,
+    /// The min and max pitch in radians
+    pub pitch_limits: (f32, f32),
+    /// Whether moving the mouse up looks down
+    pub invert_y: bool,
+    /// The time in seconds taken to catch up with the target, 0 for none
+    pub smoothing: f32,
+    /// Axis aligned boxes (min, max) that the camera is kept in front of
+    pub obstacles: Vec<(Vector3, Vector3)>,
+    /// The gap kept between the camera and an obstacle
+    pub collision_padding: f32,
+}
+
+impl Default for FollowController {
+    fn default() -> Self {
+        FollowController {
+            target: (0f32, 0f32, 0f32),
+            height: 1.5f32,
+            distance: 6f32,
+            min_distance: 0.5f32,
+            look_speed: 1f32,
+            yaw: 0f32,
+            pitch: -0.3f32,
+            pitch_limits: (-1.2f32, 0.8f32),
+            invert_y: false,
+            smoothing: 0.1f32,
+            obstacles: Vec::new(),
+            collision_padding: 0.2f32,
+        }
+    }
+}
+
+impl CameraController for FollowController {
+    fn update(&mut self, cam: &mut Camera, input: &Input, delta: f32) {
+        let (pitch, yaw) = mouse_look(
+            (self.pitch, self.yaw),
+            input.mouse_delta,
+            self.look_speed,
+            self.invert_y,
+            self.pitch_limits,
+        );
+        self.pitch = pitch;
+        self.yaw = yaw;
+
+        let focus = Vec3::from(self.target) + Vec3::new(0f32, self.height, 0f32);
+        let back = -Vec3::from(forward_dir(pitch, yaw));
+
+        // pull the camera in front of anything between it and the target
+        let ray = Ray {
+            origin: focus.into(),
+            dir: back.into(),
+        };
+        let distance = self
+            .obstacles
+            .iter()
+            .filter_map(|&(min, max)| ray_aabb_test(&ray, min, max))
+            .fold(self.distance, |d, hit| d.min(hit - self.collision_padding))
+            .max(self.min_distance);
+        let pos = focus + back * distance;
+
+        cam.pos = if distance < self.distance {
+            pos.into()
+        } else {
+            Vec3::from(cam.pos)
+                .lerp(pos, smooth_factor(self.smoothing, delta))
+                .into()
+        };
+        cam.euler_rot = (pitch, yaw, 0f32);
+    }
+}
+
+/// Camera that looks down on the xz plane, panned with the movement keys
+/// and zoomed with the mouse wheel
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct TopDownController {
+    /// The point on the ground the camera is centered on
+    pub center: Vector3,
+    /// The height of the camera above the center
+    pub height: f32,
+    /// The min and max height
+    pub height_limits: (f32, f32),
+    /// The change in height per unit of mouse wheel movement
+    pub zoom_speed: f32,
+    /// The angle the camera looks down at in radians, -PI/2 is straight down
+    pub pitch: f32,
+    /// The rotation of the view around the y axis in radians
+    pub yaw: f32,
+    /// The pan speed in units per second, scaled by the height
+    pub pan_speed: f32,
+    /// The keys used to pan the camera
+    pub keys: MovementKeys,
+    /// The time in seconds taken to catch up with the input, 0 for none
+    pub smoothing: f32,
+}
+
+impl Default for TopDownController {
+    fn default() -> Self {
+        TopDownController {
+            center: (0f32, 0f32, 0f32),
+            height: 20f32,
+            height_limits: (2f32, 100f32),
+            zoom_speed: 2f32,
+            pitch: -1.2f32,
+            yaw: 0f32,
+            pan_speed: 1f32,
+            keys: Default::default(),
+            smoothing: 0.1f32,
+        }
+    }
+}
+
+impl CameraController for TopDownController {
+    fn update(&mut self, cam: &mut Camera, input: &Input, delta: f32) {
+        let axes = self.keys.axes(input);
+        let pan = Vec3::from(right_dir(self.yaw)) * axes.0 + flat_forward_dir(self.yaw) * axes.2;
+        self.center = (Vec3::from(self.center)
+            + pan.normalize() * (self.pan_speed * self.height * delta))
+            .into();
+        self.height = clamp(
+            self.height - input.mouse_wheel_delta.1 * self.zoom_speed,
+            self.height_limits,
+        );
+
+        // back away from the center along the view direction to reach the height
+        let forward = Vec3::from(forward_dir(self.pitch, self.yaw));
+        let distance = self.height / (-forward.y).max(0.01f32);
+        let pos = Vec3::from(self.center) - forward * distance;
+
+        cam.pos = Vec3::from(cam.pos)
+            .lerp(pos, smooth_factor(self.smoothing, delta))
+            .into();
+        cam.euler_rot = (self.pitch, self.yaw, 0f32);
+    }
+}
+
+/// The direction the camera looks in for the pitch and yaw
+pub fn forward_dir(pitch: f32, yaw: f32) -> Vector3 {
+    (
+        -yaw.sin() * pitch.cos(),
+        pitch.sin(),
+        -yaw.cos() * pitch.cos(),
+    )
+}
+
+/// The direction to the right of the camera for the yaw
+pub fn right_dir(yaw: f32) -> Vector3 {
+    (yaw.cos(), 0f32, -yaw.sin())
+}
+
+/// The pitch and yaw that looks along the direction
+pub fn look_rotation(dir: Vector3) -> (f32, f32) {
+    let dir = Vec3::from(dir).normalize();
+    (dir.y.asin(), (-dir.x).atan2(-dir.z))
+}
+
+/// Point the camera at the target
+pub fn look_at(cam: &mut Camera, target: Vector3) {
+    let (pitch, yaw) = look_rotation((Vec3::from(target) - Vec3::from(cam.pos)).into());
+    cam.euler_rot = (pitch, yaw, 0f32);
+}
+
+/// The forward direction on the xz plane for the yaw
+fn flat_forward_dir(yaw: f32) -> Vec3 {
+    Vec3::new(-yaw.sin(), 0f32, -yaw.cos())
+}
+
+/// The pitch of the camera between -PI and PI along with the yaw
+fn cam_rotation(cam: &Camera) -> (f32, f32) {
+    let pitch = (cam.euler_rot.0 + PI).rem_euclid(PI * 2f32) - PI;
+    (pitch, cam.euler_rot.1)
+}
+
+/// Apply the mouse movement to the pitch and yaw
+fn mouse_look(
+    rotation: (f32, f32),
+    mouse_delta: (f32, f32),
+    look_speed: f32,
+    invert_y: bool,
+    pitch_limits: (f32, f32),
+) -> (f32, f32) {
+    let dy = if invert_y {
+        -mouse_delta.1
+    } else {
+        mouse_delta.1
+    };
+
+    (
+        clamp(rotation.0 + dy * look_speed, pitch_limits),
+        rotation.1 + mouse_delta.0 * look_speed,
+    )
+}
+
+/// Turn the camera towards the pitch and yaw
+fn turn_towards(cam: &mut Camera, rotation: (f32, f32), t: f32) {
+    let (pitch, yaw) = cam_rotation(cam);
+    cam.euler_rot = (
+        pitch + (rotation.0 - pitch) * t,
+        yaw + (rotation.1 - yaw) * t,
+        cam.euler_rot.2,
+    );
+}
+
+/// How far to move towards a target this frame for the smoothing time
+fn smooth_factor(smoothing: f32, delta: f32) -> f32 {
+    if smoothing <= 0f32 {
+        1f32
+    } else {
+        1f32 - (-delta / smoothing).exp()
+    }
+}
+
+/// Clamp the value between the limits
+fn clamp(v: f32, limits: (f32, f32)) -> f32 {
+    v.max(limits.0).min(limits.1)
+}
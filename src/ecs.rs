@@ -0,0 +1,501 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use types::{PhysicsType, Transform, Vector3};
+
+/// The name the physics body system is added to the schedule with
+pub const PHYSICS_BODY_SYSTEM: &str = "physics_bodies";
+
+/// A handle to an entity in a world, handles of despawned entities are
+/// never valid again even when their index is reused
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Entity {
+    /// The index of the entity's components in each storage
+    index: u32,
+    /// The number of times the index has been reused
+    generation: u32,
+}
+
+impl Entity {
+    /// The index of the entity's components in each storage
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    /// The number of times the index has been reused
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/// The storage for one type of component, indexed by entity
+struct Storage<C> {
+    /// The component of each entity index, if it has one
+    components: Vec<Option<C>>,
+}
+
+/// Operations on a storage without knowing the component type
+trait AnyStorage: Send {
+    /// Remove the component at the index if there is one
+    fn remove_index(&mut self, index: usize);
+    /// Get the storage as Any to downcast
+    fn as_any(&self) -> &dyn Any;
+    /// Get the storage as mutable Any to downcast
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<C: Any + Send> AnyStorage for Storage<C> {
+    fn remove_index(&mut self, index: usize) {
+        if let Some(c) = self.components.get_mut(index) {
+            *c = None;
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A collection of entities and the components they own, any type that is
+/// Send and 'static can be used as a component, including `RenderItem`
+#[derive(Default)]
+pub struct World {
+    /// The current generation of each entity index
+    generations: Vec<u32>,
+    /// Whether each entity index is in use
+    alive: Vec<bool>,
+    /// The indices free to be reused
+    free: Vec<u32>,
+    /// The storage for each component type
+    storages: HashMap<TypeId, Box<dyn AnyStorage>>,
+}
+
+impl World {
+    /// Create a new empty world
+    pub fn new() -> World {
+        Default::default()
+    }
+
+    /// Create a new entity with no components
+    pub fn spawn(&mut self) -> Entity {
+        match self.free.pop() {
+            Some(index) => {
+                self.alive[index as usize] = true;
+                Entity {
+                    index,
+                    generation: self.generations[index as usize],
+                }
+            }
+            None => {
+                self.generations.push(0);
+                self.alive.push(true);
+                Entity {
+                    index: (self.generations.len() - 1) as u32,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    /// Remove the entity and all of its components, returns false if it was
+    /// already despawned
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+
+        for storage in self.storages.values_mut() {
+            storage.remove_index(entity.index());
+        }
+
+        self.alive[entity.index()] = false;
+        self.generations[entity.index()] += 1;
+        self.free.push(entity.index);
+        true
+    }
+
+    /// Whether the entity has been spawned and not despawned
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.alive.get(entity.index()).cloned().unwrap_or(false)
+            && self.generations[entity.index()] == entity.generation
+    }
+
+    /// The number of entities alive
+    pub fn len(&self) -> usize {
+        self.alive.iter().filter(|a| **a).count()
+    }
+
+    /// Whether there are no entities alive
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// All of the entities alive
+    pub fn entities(&self) -> Vec<Entity> {
+        (0..self.alive.len())
+            .filter(|i| self.alive[*i])
+            .map(|i| self.entity_at(i))
+            .collect()
+    }
+
+    /// Add the component to the entity, returning the component it replaced,
+    /// or giving the component back if the entity has been despawned
+    pub fn insert<C: Any + Send>(&mut self, entity: Entity, component: C) -> Result<Option<C>, C> {
+        if !self.is_alive(entity) {
+            return Err(component);
+        }
+
+        let components = &mut self.storage_mut::<C>().components;
+        if components.len() <= entity.index() {
+            components.resize_with(entity.index() + 1, || None);
+        }
+        Ok(components[entity.index()].replace(component))
+    }
+
+    /// Remove the component from the entity
+    pub fn remove<C: Any + Send>(&mut self, entity: Entity) -> Option<C> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        self.storage_mut::<C>()
+            .components
+            .get_mut(entity.index())
+            .and_then(|c| c.take())
+    }
+
+    /// Get a ref to the component of the entity
+    pub fn get<C: Any + Send>(&self, entity: Entity) -> Option<&C> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        self.storage::<C>()
+            .and_then(|s| s.components.get(entity.index()))
+            .and_then(|c| c.as_ref())
+    }
+
+    /// Get a mutable ref to the component of the entity
+    pub fn get_mut<C: Any + Send>(&mut self, entity: Entity) -> Option<&mut C> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+
+        self.storage_mut::<C>()
+            .components
+            .get_mut(entity.index())
+            .and_then(|c| c.as_mut())
+    }
+
+    /// Whether the entity has the component
+    pub fn has<C: Any + Send>(&self, entity: Entity) -> bool {
+        self.get::<C>(entity).is_some()
+    }
+
+    /// The entities that have the component
+    pub fn query<C: Any + Send>(&self) -> Vec<Entity> {
+        match self.storage::<C>() {
+            Some(s) => s
+                .components
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| c.is_some())
+                .map(|(i, _)| self.entity_at(i))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Call the function with each entity that has the component
+    pub fn for_each<C: Any + Send, F: FnMut(Entity, &C)>(&self, mut f: F) {
+        if let Some(s) = self.storage::<C>() {
+            for (i, c) in s.components.iter().enumerate() {
+                if let Some(c) = c {
+                    f(self.entity_at(i), c);
+                }
+            }
+        }
+    }
+
+    /// Call the function with each entity that has the component, mutably
+    pub fn for_each_mut<C: Any + Send, F: FnMut(Entity, &mut C)>(&mut self, mut f: F) {
+        let generations = &self.generations;
+        if let Some(s) = self.storages.get_mut(&TypeId::of::<C>()) {
+            let s = downcast_mut::<C>(s);
+            for (i, c) in s.components.iter_mut().enumerate() {
+                if let Some(c) = c {
+                    f(entity(generations, i), c);
+                }
+            }
+        }
+    }
+
+    /// Call the function with each entity that has the component in parallel
+    pub fn par_for_each_mut<C, F>(&mut self, f: F)
+    where
+        C: Any + Send,
+        F: Fn(Entity, &mut C) + Send + Sync,
+    {
+        let generations = &self.generations;
+        if let Some(s) = self.storages.get_mut(&TypeId::of::<C>()) {
+            downcast_mut::<C>(s)
+                .components
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(i, c)| {
+                    if let Some(c) = c {
+                        f(entity(generations, i), c);
+                    }
+                });
+        }
+    }
+
+    /// Call the function with each entity that has both components, mutably
+    pub fn join_mut<A, B, F>(&mut self, mut f: F)
+    where
+        A: Any + Send,
+        B: Any + Send,
+        F: FnMut(Entity, &mut A, &mut B),
+    {
+        self.with_pair::<A, B, _>(|generations, a, b| {
+            for (i, (a, b)) in a.iter_mut().zip(b.iter_mut()).enumerate() {
+                if let (Some(a), Some(b)) = (a, b) {
+                    f(entity(generations, i), a, b);
+                }
+            }
+        });
+    }
+
+    /// Call the function with each entity that has both components in parallel
+    pub fn par_join_mut<A, B, F>(&mut self, f: F)
+    where
+        A: Any + Send,
+        B: Any + Send,
+        F: Fn(Entity, &mut A, &mut B) + Send + Sync,
+    {
+        self.with_pair::<A, B, _>(|generations, a, b| {
+            a.par_iter_mut()
+                .zip(b.par_iter_mut())
+                .enumerate()
+                .for_each(|(i, (a, b))| {
+                    if let (Some(a), Some(b)) = (a, b) {
+                        f(entity(generations, i), a, b);
+                    }
+                });
+        });
+    }
+
+    /// Remove the component from every entity that has it, returning them
+    /// so they can be put back with insert
+    pub fn take_all<C: Any + Send>(&mut self) -> Vec<(Entity, C)> {
+        let generations = &self.generations;
+        match self.storages.get_mut(&TypeId::of::<C>()) {
+            Some(s) => downcast_mut::<C>(s)
+                .components
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(i, c)| c.take().map(|c| (entity(generations, i), c)))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// The entity currently at the index
+    fn entity_at(&self, index: usize) -> Entity {
+        entity(&self.generations, index)
+    }
+
+    /// Get the storage for the component if any have been added
+    fn storage<C: Any + Send>(&self) -> Option<&Storage<C>> {
+        self.storages
+            .get(&TypeId::of::<C>())
+            .and_then(|s| s.as_any().downcast_ref::<Storage<C>>())
+    }
+
+    /// Get the storage for the component, creating it if needed
+    fn storage_mut<C: Any + Send>(&mut self) -> &mut Storage<C> {
+        let s = self.storages.entry(TypeId::of::<C>()).or_insert_with(|| {
+            Box::new(Storage::<C> {
+                components: Vec::new(),
+            })
+        });
+        downcast_mut::<C>(s)
+    }
+
+    /// Borrow the storages of two different components at once
+    fn with_pair<A, B, F>(&mut self, f: F)
+    where
+        A: Any + Send,
+        B: Any + Send,
+        F: FnOnce(&[u32], &mut Vec<Option<A>>, &mut Vec<Option<B>>),
+    {
+        assert!(
+            TypeId::of::<A>() != TypeId::of::<B>(),
+            "Can't join a component with itself"
+        );
+
+        // take one storage out so both can be borrowed mutably
+        let mut b = match self.storages.remove(&TypeId::of::<B>()) {
+            Some(b) => b,
+            None => return,
+        };
+        if let Some(a) = self.storages.get_mut(&TypeId::of::<A>()) {
+            f(
+                &self.generations,
+                &mut downcast_mut::<A>(a).components,
+                &mut downcast_mut::<B>(&mut b).components,
+            );
+        }
+        self.storages.insert(TypeId::of::<B>(), b);
+    }
+}
+
+/// The entity at the index with its current generation
+fn entity(generations: &[u32], index: usize) -> Entity {
+    Entity {
+        index: index as u32,
+        generation: generations[index],
+    }
+}
+
+/// Downcast a storage to the storage of the component type
+fn downcast_mut<C: Any + Send>(storage: &mut Box<dyn AnyStorage>) -> &mut Storage<C> {
+    storage
+        .as_any_mut()
+        .downcast_mut::<Storage<C>>()
+        .expect("Storage type mismatch")
+}
+
+/// A system that is run on the world each frame
+pub trait System: Send {
+    /// Run the system on the world
+    fn run(&mut self, world: &mut World, delta: f32);
+}
+
+impl<F: FnMut(&mut World, f32) + Send> System for F {
+    fn run(&mut self, world: &mut World, delta: f32) {
+        self(world, delta)
+    }
+}
+
+/// A system in a schedule
+struct ScheduledSystem {
+    /// The name used to look up the system
+    name: String,
+    /// Systems with a lower order are run first
+    order: i32,
+    /// The system itself
+    system: Box<dyn System>,
+}
+
+/// The systems to run on a world in order
+#[derive(Default)]
+pub struct Schedule {
+    /// The systems sorted by order, then by when they were added
+    systems: Vec<ScheduledSystem>,
+}
+
+impl Schedule {
+    /// Create a new empty schedule
+    pub fn new() -> Schedule {
+        Default::default()
+    }
+
+    /// Add a system, systems with a lower order are run first and those with
+    /// the same order are run in the order they were added
+    pub fn add_system<S: System + 'static>(&mut self, name: &str, order: i32, system: S) {
+        let index = self
+            .systems
+            .iter()
+            .position(|s| s.order > order)
+            .unwrap_or(self.systems.len());
+        self.systems.insert(
+            index,
+            ScheduledSystem {
+                name: name.into(),
+                order,
+                system: Box::new(system),
+            },
+        );
+    }
+
+    /// Remove the systems with the name, returns whether any were removed
+    pub fn remove_system(&mut self, name: &str) -> bool {
+        let len = self.systems.len();
+        self.systems.retain(|s| s.name != name);
+        self.systems.len() != len
+    }
+
+    /// The names of the systems in the order they are run
+    pub fn system_names(&self) -> Vec<&str> {
+        self.systems.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    /// Run all of the systems on the world in order
+    pub fn run(&mut self, world: &mut World, delta: f32) {
+        for s in self.systems.iter_mut() {
+            s.system.run(world, delta);
+        }
+    }
+}
+
+/// Component for a simple physics body that moves the entity's transform
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct PhysicsBody {
+    /// Only dynamic bodies are moved
+    pub physics_type: PhysicsType,
+    /// The velocity in units per second
+    pub velocity: Vector3,
+    /// The acceleration applied to the velocity
+    pub gravity: Vector3,
+}
+
+impl Default for PhysicsBody {
+    fn default() -> Self {
+        PhysicsBody {
+            physics_type: PhysicsType::Dynamic,
+            velocity: (0f32, 0f32, 0f32),
+            gravity: (0f32, -9.81f32, 0f32),
+        }
+    }
+}
+
+/// Component for a sound played by the game's audio system
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct AudioSource {
+    /// The name the sound was added to the audio system with
+    pub sound: &'static str,
+    /// Set to play the sound on the next update, cleared once played
+    pub play: bool,
+}
+
+impl AudioSource {
+    /// Create a new audio source that plays the sound on the next update
+    pub fn new(sound: &'static str) -> AudioSource {
+        AudioSource { sound, play: true }
+    }
+}
+
+/// Move the transforms of dynamic physics bodies by their velocity
+pub fn physics_body_system(world: &mut World, delta: f32) {
+    world.par_join_mut::<PhysicsBody, Transform, _>(|_, body, transform| {
+        if body.physics_type != PhysicsType::Dynamic {
+            return;
+        }
+
+        body.velocity.0 += body.gravity.0 * delta;
+        body.velocity.1 += body.gravity.1 * delta;
+        body.velocity.2 += body.gravity.2 * delta;
+
+        transform.pos.0 += body.velocity.0 * delta;
+        transform.pos.1 += body.velocity.1 * delta;
+        transform.pos.2 += body.velocity.2 * delta;
+    });
+}
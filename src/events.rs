@@ -0,0 +1,225 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::mem;
+
+use glium::glutin::event::{Event, WindowEvent};
+
+use tween::{TimerId, TweenId};
+
+/// Events published by the engine, there is no event for audio finishing as
+/// the audio systems don't report when a sound has ended
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum EngineEvent {
+    /// The window was resized to the width and height in pixels
+    Resized(u32, u32),
+    /// The window gained or lost focus
+    Focused(bool),
+    /// The window was asked to close
+    CloseRequested,
+    /// Two physics items started or stopped touching, the items are the
+    /// indices of the render items and their instances
+    Contact {
+        /// The first item in contact
+        a: (usize, usize),
+        /// The second item in contact
+        b: (usize, usize),
+        /// Whether the contact started or stopped
+        started: bool,
+    },
+    /// A tween finished playing
+    TweenFinished(TweenId),
+    /// A timer fired
+    TimerFired(TimerId),
+    /// A state transition requested by an update status failed, with the reason
+    TransitionFailed(String),
+}
+
+/// The handle to a subscription used to unsubscribe
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct SubscriptionId(usize);
+
+/// A function called with each delivered event
+type Subscriber<E> = Box<dyn FnMut(&E)>;
+
+/// A function called with each raw window event
+type RawSubscriber = Box<dyn FnMut(&Event<()>)>;
+
+/// The events of one type and their subscribers
+struct Channel<E> {
+    /// The events published since the last update
+    pending: Vec<E>,
+    /// The events delivered by the last update
+    current: Vec<E>,
+    /// The functions called with each event when delivered
+    subscribers: Vec<(SubscriptionId, Subscriber<E>)>,
+}
+
+/// Operations on a channel without knowing the event type
+trait AnyChannel {
+    /// Deliver the pending events, replacing the current ones
+    fn flush(&mut self);
+    /// Remove the subscriber, returning whether it was found
+    fn unsubscribe(&mut self, id: SubscriptionId) -> bool;
+    /// Remove all of the pending and current events
+    fn clear(&mut self);
+    /// Get the channel as Any to downcast
+    fn as_any(&self) -> &dyn Any;
+    /// Get the channel as mutable Any to downcast
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<E: Any> AnyChannel for Channel<E> {
+    fn flush(&mut self) {
+        self.current = mem::take(&mut self.pending);
+        for (_, subscriber) in self.subscribers.iter_mut() {
+            for event in self.current.iter() {
+                subscriber(event);
+            }
+        }
+    }
+
+    fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let len = self.subscribers.len();
+        self.subscribers.retain(|(s, _)| *s != id);
+        self.subscribers.len() != len
+    }
+
+    fn clear(&mut self) {
+        self.pending.clear();
+        self.current.clear();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Typed publish/subscribe channels, events published during a frame are
+/// delivered to subscribers and readable for the whole of the next frame
+#[derive(Default)]
+pub struct EventBus {
+    /// The channel for each event type
+    channels: HashMap<TypeId, Box<dyn AnyChannel>>,
+    /// The functions called with each raw window event
+    raw_subscribers: Vec<(SubscriptionId, RawSubscriber)>,
+    /// The id given to the next subscription
+    next_id: usize,
+}
+
+impl EventBus {
+    /// Create a new event bus with no channels
+    pub fn new() -> EventBus {
+        Default::default()
+    }
+
+    /// Publish an event to be delivered on the next update
+    pub fn publish<E: Any>(&mut self, event: E) {
+        self.channel_mut::<E>().pending.push(event);
+    }
+
+    /// Call the function with each event of the type when it is delivered
+    pub fn subscribe<E: Any, F: FnMut(&E) + 'static>(&mut self, f: F) -> SubscriptionId {
+        let id = self.next_subscription_id();
+        self.channel_mut::<E>().subscribers.push((id, Box::new(f)));
+        id
+    }
+
+    /// Call the function with each raw window event buffered by `start_loop`
+    pub fn subscribe_raw<F: FnMut(&Event<()>) + 'static>(&mut self, f: F) -> SubscriptionId {
+        let id = self.next_subscription_id();
+        self.raw_subscribers.push((id, Box::new(f)));
+        id
+    }
+
+    /// Remove a subscription, returning whether it was found
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let len = self.raw_subscribers.len();
+        self.raw_subscribers.retain(|(s, _)| *s != id);
+
+        self.raw_subscribers.len() != len
+            || self
+                .channels
+                .values_mut()
+                .any(|channel| channel.unsubscribe(id))
+    }
+
+    /// The events of the type delivered by the last update
+    pub fn read<E: Any>(&self) -> &[E] {
+        self.channels
+            .get(&TypeId::of::<E>())
+            .and_then(|c| c.as_any().downcast_ref::<Channel<E>>())
+            .map(|c| c.current.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Take the events of the type delivered by the last update
+    pub fn drain<E: Any>(&mut self) -> Vec<E> {
+        mem::take(&mut self.channel_mut::<E>().current)
+    }
+
+    /// Deliver the events published since the last update to their
+    /// subscribers, dropping the events delivered by the last update
+    pub fn update(&mut self) {
+        for channel in self.channels.values_mut() {
+            channel.flush();
+        }
+    }
+
+    /// Call the raw subscribers with each of the window events
+    pub fn dispatch_raw(&mut self, events: &[Event<()>]) {
+        for (_, subscriber) in self.raw_subscribers.iter_mut() {
+            for event in events {
+                subscriber(event);
+            }
+        }
+    }
+
+    /// Remove all of the events that haven't been drained, keeping subscribers
+    pub fn clear(&mut self) {
+        for channel in self.channels.values_mut() {
+            channel.clear();
+        }
+    }
+
+    /// Get the channel for the event type, creating it if needed
+    fn channel_mut<E: Any>(&mut self) -> &mut Channel<E> {
+        self.channels
+            .entry(TypeId::of::<E>())
+            .or_insert_with(|| {
+                Box::new(Channel::<E> {
+                    pending: Vec::new(),
+                    current: Vec::new(),
+                    subscribers: Vec::new(),
+                })
+            })
+            .as_any_mut()
+            .downcast_mut::<Channel<E>>()
+            .expect("Channel type mismatch")
+    }
+
+    /// Get a new unique subscription id
+    fn next_subscription_id(&mut self) -> SubscriptionId {
+        self.next_id += 1;
+        SubscriptionId(self.next_id)
+    }
+}
+
+/// The engine events for the raw window events
+pub fn window_engine_events(events: &[Event<()>]) -> Vec<EngineEvent> {
+    events
+        .iter()
+        .filter_map(|event| match *event {
+            Event::WindowEvent { ref event, .. } => match *event {
+                WindowEvent::Resized(size) => Some(EngineEvent::Resized(size.width, size.height)),
+                WindowEvent::Focused(focused) => Some(EngineEvent::Focused(focused)),
+                WindowEvent::CloseRequested => Some(EngineEvent::CloseRequested),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
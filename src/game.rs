@@ -1,20 +1,37 @@
+use animation::Animations;
 #[cfg(feature = "default")]
 #[cfg(not(feature = "3d-audio"))]
 use audio::Audio;
 #[cfg(feature = "3d-audio")]
 use audio::{Ambisonic, AmbisonicBuilder};
+#[cfg(feature = "default")]
+#[cfg(not(feature = "3d-audio"))]
+use ecs::AudioSource;
+use ecs::{self, physics_body_system, Entity, Schedule, PHYSICS_BODY_SYSTEM};
+use events::{window_engine_events, EngineEvent, EventBus};
 use imgui::Ui;
+use index::{select_mut, ItemIndex};
+use input::actions::ActionMap;
+use input::record::InputRecorder;
 use input::Input;
+use localization::Localization;
+use particles::ParticleEmitter;
 use renderer::{Draw, Renderer};
+use scene::{combine_transforms, SceneGraph, SceneItems, SceneLink, SceneNode};
+use state::{StashedItems, State, StateEntry, StateStack};
+use tween::{TweenItems, Tweener};
 #[cfg(feature = "nphysics")]
 use types::PhysicsType;
-use types::{Camera, RenderItem, TextItem};
+use types::{Camera, RenderItem, SpriteItem, TextItem, Transform, Vector3};
+use utils::{ray_render_item_test, unproject};
 
 #[cfg(feature = "nphysics")]
 use nalgebra::Translation3;
 #[cfg(feature = "nphysics")]
 use nalgebra::Vector3 as nVector3;
 #[cfg(feature = "nphysics")]
+use ncollide::events::ContactEvent;
+#[cfg(feature = "nphysics")]
 use ncollide::shape::{Cuboid, ShapeHandle};
 #[cfg(feature = "nphysics")]
 use nphysics3d::object::{BodyHandle, BodyStatus, ColliderDesc, RigidBodyDesc};
@@ -25,6 +42,8 @@ use glium::glutin::event::{Event, StartCause};
 use glium::glutin::event_loop::{ControlFlow, EventLoop};
 
 //use std::slice::IterMut;
+use std::hash::Hash;
+use std::mem;
 use std::time::{Duration, Instant};
 
 use rayon::prelude::*;
@@ -40,6 +59,12 @@ pub enum UpdateStatus {
     Continue,
     /// Finish/Exit the game
     Finish,
+    /// Push the registered state with the name on top of the current state
+    Push(String),
+    /// Pop the current state, returning to the one below
+    Pop,
+    /// Replace the current state with the registered state with the name
+    Replace(String),
 }
 
 /// Struct for storing physics handles and associated RenderItem
@@ -53,6 +78,10 @@ struct PhysicsHandle {
 pub struct Game<T: Default> {
     /// The input system for the game
     pub input: Input,
+    /// The named actions and axes mapped from the input
+    pub actions: ActionMap,
+    /// Records the input each frame or replays it in place of the live input
+    pub recorder: InputRecorder,
     /// The render system for the game
     pub renderer: Renderer,
     /// The physics system
@@ -69,11 +98,35 @@ pub struct Game<T: Default> {
     pub cams: Vec<Camera>,
     /// All of the mesh items to be rendered in the game
     render_items: Vec<RenderItem<T>>,
+    /// The lookups of render items by tag and name
+    render_item_index: ItemIndex<T>,
     /// All the text items to be rendered in the game
     text_items: Vec<TextItem>,
+    /// All the particle emitters to be simulated and rendered in the game
+    particle_emitters: Vec<ParticleEmitter>,
+    /// All the sprite items to be rendered in the game
+    sprite_items: Vec<SpriteItem>,
     /// All the body handles for physics items
     #[cfg(feature = "nphysics")]
     physics_items: Vec<PhysicsHandle>,
+    /// The localized strings used by text items
+    pub localization: Localization,
+    /// The parent/child relationships between items
+    pub scene: SceneGraph,
+    /// The entities and their components, entities with a RenderItem are
+    /// drawn relative to their Transform if they have one
+    pub world: ecs::World,
+    /// The systems run on the world each frame after the user update
+    pub systems: Schedule,
+    /// The registered states and the stack of active ones
+    pub states: StateStack<T>,
+    /// The typed event channels, engine events are published as EngineEvent
+    /// and raw window events are passed to the raw subscribers
+    pub events: EventBus,
+    /// The playing tweens and running timers, advanced by the delta each frame
+    pub tweens: Tweener,
+    /// The keyframe animation clips and the players writing them to the render items
+    pub animations: Animations,
     /// The delta time for each frame
     pub delta: f32,
 }
@@ -93,6 +146,9 @@ impl<T: Default> Game<T> {
             euler_rot: (0.0f32, 0.0, 0.0),
         };
 
+        let mut systems = Schedule::new();
+        systems.add_system(PHYSICS_BODY_SYSTEM, 0, physics_body_system);
+
         let event_loop = EventLoop::new();
 
         let renderer = Renderer::new("caper window".to_string(), &event_loop);
@@ -106,6 +162,8 @@ impl<T: Default> Game<T> {
         (
             Game {
                 input: Input::new(),
+                actions: ActionMap::new(),
+                recorder: InputRecorder::new(),
                 renderer,
                 #[cfg(feature = "nphysics")]
                 physics,
@@ -116,9 +174,20 @@ impl<T: Default> Game<T> {
                 audio: audio,
                 cams: vec![cam],
                 render_items: Vec::new(),
+                render_item_index: ItemIndex::new(),
                 text_items: Vec::new(),
+                particle_emitters: Vec::new(),
+                sprite_items: Vec::new(),
                 #[cfg(feature = "nphysics")]
                 physics_items: Vec::new(),
+                localization: Localization::default(),
+                scene: SceneGraph::new(),
+                world: ecs::World::new(),
+                systems,
+                states: StateStack::new(),
+                events: EventBus::new(),
+                tweens: Tweener::new(),
+                animations: Animations::new(),
                 delta: 0.016_666_667f32,
             },
             event_loop,
@@ -126,6 +195,89 @@ impl<T: Default> Game<T> {
     }
 }
 
+impl<T: Default + 'static> Game<T> {
+    /// Move the render items of entities to the end of render_items so they are
+    /// drawn, returning the entities and the local instances to restore
+    fn push_entity_render_items(&mut self) -> Vec<(Entity, Option<Vec<Transform>>)> {
+        let items = self.world.take_all::<RenderItem<T>>();
+        let mut entities = Vec::with_capacity(items.len());
+
+        for (entity, mut item) in items {
+            let local = self.world.get::<Transform>(entity).map(|transform| {
+                // the instances are relative to the entity's transform
+                let world = if item.instance_transforms.is_empty() {
+                    vec![*transform]
+                } else {
+                    item.instance_transforms
+                        .iter()
+                        .map(|t| combine_transforms(transform, t))
+                        .collect()
+                };
+                mem::replace(&mut item.instance_transforms, world)
+            });
+
+            entities.push((entity, local));
+            self.render_items.push(item);
+        }
+
+        entities
+    }
+
+    /// Move the render items of entities from render_items back to the entities
+    fn restore_entity_render_items(
+        &mut self,
+        item_count: usize,
+        entities: Vec<(Entity, Option<Vec<Transform>>)>,
+    ) {
+        let items = self.render_items.drain(item_count..);
+        for ((entity, local), mut item) in entities.into_iter().zip(items) {
+            if let Some(local) = local {
+                item.instance_transforms = local;
+            }
+            // entities despawned while their item was out are dropped with it
+            let _ = self.world.insert(entity, item);
+        }
+    }
+
+    /// Play the sounds of the entities' audio sources that are set to play
+    #[cfg(feature = "default")]
+    #[cfg(not(feature = "3d-audio"))]
+    fn play_entity_audio(&mut self) {
+        let audio = &mut self.audio;
+        self.world.for_each_mut::<AudioSource, _>(|_, source| {
+            if source.play {
+                audio.play(source.sound);
+                source.play = false;
+            }
+        });
+    }
+
+    /// Advance the tweens and timers, publishing the ones that finished or fired
+    fn update_tweens(&mut self) {
+        let renderer = &mut self.renderer;
+        let mut items = TweenItems {
+            render_items: &mut self.render_items,
+            text_items: &mut self.text_items,
+            cams: &mut self.cams,
+            lights: renderer.lighting.directional_lights_mut(),
+            post_shader_options: &mut renderer.post_effect.post_shader_options,
+            lights_changed: false,
+        };
+        let update = self.tweens.update(self.delta, &mut items);
+
+        if items.lights_changed {
+            self.renderer.lighting.regenerate_lighting_tex();
+        }
+
+        for id in update.finished {
+            self.events.publish(EngineEvent::TweenFinished(id));
+        }
+        for id in update.fired {
+            self.events.publish(EngineEvent::TimerFired(id));
+        }
+    }
+}
+
 /// Default trait implementation from Game
 impl<T: Default> Default for Game<T> {
     /// Returns a default instance of Game
@@ -148,6 +300,8 @@ pub trait RenderItems {
     fn get_render_item_by_name(&mut self, name: &str) -> Option<&mut RenderItem<Self::T>>;
     /// Add a render item to the game
     fn add_render_item(&mut self, render_item: RenderItem<Self::T>);
+    /// Remove a render item from the game, dropping the scene links of its instances
+    fn remove_render_item(&mut self, index: usize) -> RenderItem<Self::T>;
 }
 
 impl<T: Default> RenderItems for Game<T> {
@@ -177,6 +331,7 @@ impl<T: Default> RenderItems for Game<T> {
     fn add_render_item(&mut self, render_item: RenderItem<T>) {
         // add the render item
         self.render_items.push(render_item);
+        self.render_item_index.invalidate();
 
         // the index of the newly added item
         let i = self.render_items.len() - 1;
@@ -185,6 +340,153 @@ impl<T: Default> RenderItems for Game<T> {
         #[cfg(feature = "nphysics")]
         self.add_physics(i);
     }
+
+    /// Remove a render item from the game, dropping the scene links of its instances
+    fn remove_render_item(&mut self, index: usize) -> RenderItem<T> {
+        let render_item = self.render_items.remove(index);
+        self.render_item_index.invalidate();
+        self.scene.remove_render_item(index);
+
+        // remove the bodies of the item and move the later items down
+        #[cfg(feature = "nphysics")]
+        {
+            let removed = self
+                .physics_items
+                .iter()
+                .filter(|ph| ph.render_item.0 == index)
+                .map(|ph| ph.body_handle)
+                .collect::<Vec<_>>();
+            self.physics.remove_bodies(&removed);
+            self.physics_items.retain(|ph| ph.render_item.0 != index);
+            for ph in self.physics_items.iter_mut() {
+                if ph.render_item.0 > index {
+                    ph.render_item.0 -= 1;
+                }
+            }
+        }
+
+        render_item
+    }
+}
+
+/// Trait for looking up render items by their tags and names, the lookups are
+/// updated as items are added and retagged, if the tag, tags or name of an
+/// item are changed directly `reindex_render_items` must be called
+pub trait TaggedRenderItems {
+    /// RenderItem utype associated type
+    type T: Default;
+    /// Get refs to the render items with the tag as their tag or one of their tags
+    fn items_with_tag(&mut self, tag: &Self::T) -> Vec<&mut RenderItem<Self::T>>;
+    /// Get refs to the render items with any tag that matches the predicate
+    fn items_matching<P: Fn(&Self::T) -> bool>(
+        &mut self,
+        predicate: P,
+    ) -> Vec<&mut RenderItem<Self::T>>;
+    /// Get refs to the render items with the name
+    fn items_with_name(&mut self, name: &str) -> Vec<&mut RenderItem<Self::T>>;
+    /// Get the indices of the render items with the tag
+    fn item_indices_with_tag(&mut self, tag: &Self::T) -> Vec<usize>;
+    /// Set the tag and additional tags of a render item
+    fn retag_render_item(&mut self, index: usize, tag: Self::T, tags: Vec<Self::T>);
+    /// Rebuild the lookups after tags or names have been changed directly
+    fn reindex_render_items(&mut self);
+}
+
+impl<T: Default + Hash + Eq + Clone> TaggedRenderItems for Game<T> {
+    /// Associated type for RenderItems
+    type T = T;
+
+    /// Get refs to the render items with the tag as their tag or one of their tags
+    fn items_with_tag(&mut self, tag: &T) -> Vec<&mut RenderItem<T>> {
+        self.render_item_index.ensure_built(&self.render_items);
+        select_mut(&mut self.render_items, self.render_item_index.with_tag(tag))
+    }
+
+    /// Get refs to the render items with any tag that matches the predicate
+    fn items_matching<P: Fn(&T) -> bool>(&mut self, predicate: P) -> Vec<&mut RenderItem<T>> {
+        self.render_item_index.ensure_built(&self.render_items);
+        let indices = self.render_item_index.matching(predicate);
+        select_mut(&mut self.render_items, &indices)
+    }
+
+    /// Get refs to the render items with the name
+    fn items_with_name(&mut self, name: &str) -> Vec<&mut RenderItem<T>> {
+        self.render_item_index.ensure_built(&self.render_items);
+        select_mut(
+            &mut self.render_items,
+            self.render_item_index.with_name(name),
+        )
+    }
+
+    /// Get the indices of the render items with the tag
+    fn item_indices_with_tag(&mut self, tag: &T) -> Vec<usize> {
+        self.render_item_index.ensure_built(&self.render_items);
+        self.render_item_index.with_tag(tag).to_vec()
+    }
+
+    /// Set the tag and additional tags of a render item
+    fn retag_render_item(&mut self, index: usize, tag: T, tags: Vec<T>) {
+        self.render_item_index.ensure_built(&self.render_items);
+
+        let item = &mut self.render_items[index];
+        self.render_item_index.remove_tags(index, item);
+        item.tag = tag;
+        item.tags = tags;
+        self.render_item_index.insert_tags(index, item);
+    }
+
+    /// Rebuild the lookups after tags or names have been changed directly
+    fn reindex_render_items(&mut self) {
+        self.render_item_index.rebuild(&self.render_items);
+    }
+}
+
+/// The render item instance found when picking from the screen
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PickResult {
+    /// The index of the render item that was hit
+    pub render_item: usize,
+    /// The index of the instance transform that was hit
+    pub instance: usize,
+    /// The point in world space where the hit occurred
+    pub point: Vector3,
+    /// The distance from the camera to the hit point
+    pub distance: f32,
+}
+
+/// Trait for picking render items from screen positions
+pub trait Pick {
+    /// Get the closest render item instance under the screen position using the first camera,
+    /// None if nothing is hit or there are no cameras
+    fn pick(&self, screen_pos: (f32, f32)) -> Option<PickResult>;
+}
+
+impl<T: Default> Pick for Game<T> {
+    /// Get the closest render item instance under the screen position using the first camera,
+    /// None if nothing is hit or there are no cameras
+    fn pick(&self, screen_pos: (f32, f32)) -> Option<PickResult> {
+        let cam = self.cams.first()?;
+        let dimensions = self.renderer.display.get_framebuffer_dimensions();
+        let ray = unproject(screen_pos, dimensions, cam);
+
+        self.render_items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.active)
+            .filter_map(|(i, item)| {
+                ray_render_item_test(&ray, item).map(|(instance, distance)| PickResult {
+                    render_item: i,
+                    instance,
+                    point: (
+                        ray.origin.0 + ray.dir.0 * distance,
+                        ray.origin.1 + ray.dir.1 * distance,
+                        ray.origin.2 + ray.dir.2 * distance,
+                    ),
+                    distance,
+                })
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
 }
 
 /// Trait for physics operations
@@ -319,6 +621,39 @@ impl<T: Default> Physics for Game<T> {
                 }
             }
         }
+
+        // publish the contacts between physics items
+        {
+            let physics = &self.physics;
+            let physics_items = &self.physics_items;
+            let render_item = |collider| {
+                physics.collider(collider).and_then(|c| {
+                    physics_items
+                        .iter()
+                        .find(|ph| ph.body_handle == c.body())
+                        .map(|ph| ph.render_item)
+                })
+            };
+
+            let contacts = physics
+                .contact_events()
+                .iter()
+                .filter_map(|event| {
+                    let (c1, c2, started) = match *event {
+                        ContactEvent::Started(c1, c2) => (c1, c2, true),
+                        ContactEvent::Stopped(c1, c2) => (c1, c2, false),
+                    };
+                    match (render_item(c1), render_item(c2)) {
+                        (Some(a), Some(b)) => Some(EngineEvent::Contact { a, b, started }),
+                        _ => None,
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            for contact in contacts {
+                self.events.publish(contact);
+            }
+        }
     }
 }
 
@@ -334,6 +669,8 @@ pub trait TextItems {
     fn get_text_item_by_name(&mut self, name: String) -> Option<&mut TextItem>;
     /// Add a text item to the game
     fn add_text_item(&mut self, text_item: TextItem);
+    /// Remove a text item from the game, dropping its scene links
+    fn remove_text_item(&mut self, index: usize) -> TextItem;
 }
 
 impl<T: Default> TextItems for Game<T> {
@@ -366,6 +703,351 @@ impl<T: Default> TextItems for Game<T> {
     fn add_text_item(&mut self, text_item: TextItem) {
         self.text_items.push(text_item);
     }
+
+    /// Remove a text item from the game, dropping its scene links
+    fn remove_text_item(&mut self, index: usize) -> TextItem {
+        let text_item = self.text_items.remove(index);
+        self.scene.remove(SceneNode::Text(index));
+        text_item
+    }
+}
+
+/// Trait for parenting items to each other
+pub trait Hierarchy {
+    /// Attach the child to the parent, keeping the child where it currently is,
+    /// a child that is already attached is moved to the new parent
+    fn attach(&mut self, child: SceneNode, parent: SceneNode) -> Result<(), String>;
+    /// Detach the child from its parent, keeping the child where it currently is
+    fn detach(&mut self, child: SceneNode) -> Option<SceneLink>;
+    /// Write the world transforms of all the children to their items
+    fn update_transforms(&mut self);
+}
+
+impl<T: Default> Hierarchy for Game<T> {
+    /// Attach the child to the parent, keeping the child where it currently is
+    fn attach(&mut self, child: SceneNode, parent: SceneNode) -> Result<(), String> {
+        let items = SceneItems {
+            render_items: &mut self.render_items,
+            text_items: &mut self.text_items,
+            cams: &mut self.cams,
+            lights: self.renderer.lighting.directional_lights_mut(),
+        };
+        self.scene.attach(child, parent, &items)
+    }
+
+    /// Detach the child from its parent, keeping the child where it currently is
+    fn detach(&mut self, child: SceneNode) -> Option<SceneLink> {
+        let mut items = SceneItems {
+            render_items: &mut self.render_items,
+            text_items: &mut self.text_items,
+            cams: &mut self.cams,
+            lights: self.renderer.lighting.directional_lights_mut(),
+        };
+        let link = self.scene.detach(child, &mut items);
+
+        if let SceneNode::Light(_) = child {
+            self.renderer.lighting.regenerate_lighting_tex();
+        }
+
+        link
+    }
+
+    /// Write the world transforms of all the children to their items
+    fn update_transforms(&mut self) {
+        let mut items = SceneItems {
+            render_items: &mut self.render_items,
+            text_items: &mut self.text_items,
+            cams: &mut self.cams,
+            lights: self.renderer.lighting.directional_lights_mut(),
+        };
+
+        if self.scene.update(&mut items) {
+            self.renderer.lighting.regenerate_lighting_tex();
+        }
+    }
+}
+
+/// Trait for managing the stack of game states, transitions requested from
+/// inside a state's own callbacks are applied once the callback returns
+pub trait States {
+    /// Push the registered state with the name on top of the current state
+    fn push_state(&mut self, name: &str) -> Result<(), String>;
+    /// Pop the current state, returning to the one below
+    fn pop_state(&mut self) -> bool;
+    /// Replace the current state with the registered state with the name
+    fn replace_state(&mut self, name: &str) -> Result<(), String>;
+    /// Update the current state, returning the status it requested
+    fn update_states(&mut self) -> UpdateStatus;
+    /// Apply the transition requested by the status, leaving Continue or Finish,
+    /// failing if the state to push or replace with isn't registered
+    fn apply_transition(&mut self, status: UpdateStatus) -> Result<UpdateStatus, String>;
+}
+
+impl<T: Default> States for Game<T> {
+    /// Push the registered state with the name on top of the current state
+    fn push_state(&mut self, name: &str) -> Result<(), String> {
+        if self.states.in_callback() {
+            self.states.check_registered(name)?;
+            self.states.deferred.push(UpdateStatus::Push(name.into()));
+            return Ok(());
+        }
+
+        let state = self.states.create(name)?;
+        if let Some(top) = self.states.len().checked_sub(1) {
+            self.with_state(top, |s, g| s.on_pause(g));
+        }
+        self.enter_state(name, state);
+        self.apply_deferred();
+        Ok(())
+    }
+
+    /// Pop the current state, returning to the one below
+    fn pop_state(&mut self) -> bool {
+        if self.states.in_callback() {
+            if self.states.is_empty() {
+                return false;
+            }
+            self.states.deferred.push(UpdateStatus::Pop);
+            return true;
+        }
+
+        if !self.exit_state() {
+            self.apply_deferred();
+            return false;
+        }
+        if let Some(top) = self.states.len().checked_sub(1) {
+            self.with_state(top, |s, g| s.on_resume(g));
+        }
+        self.apply_deferred();
+        true
+    }
+
+    /// Replace the current state with the registered state with the name
+    fn replace_state(&mut self, name: &str) -> Result<(), String> {
+        if self.states.in_callback() {
+            self.states.check_registered(name)?;
+            self.states
+                .deferred
+                .push(UpdateStatus::Replace(name.into()));
+            return Ok(());
+        }
+
+        let state = self.states.create(name)?;
+        self.exit_state();
+        self.enter_state(name, state);
+        self.apply_deferred();
+        Ok(())
+    }
+
+    /// Update the current state, returning the status it requested
+    fn update_states(&mut self) -> UpdateStatus {
+        let status = match self.states.len().checked_sub(1) {
+            Some(top) => self
+                .with_state(top, |s, g| s.update(g))
+                .unwrap_or(UpdateStatus::Continue),
+            None => UpdateStatus::Continue,
+        };
+        self.apply_deferred();
+        status
+    }
+
+    /// Apply the transition requested by the status, leaving Continue or Finish,
+    /// failing if the state to push or replace with isn't registered
+    fn apply_transition(&mut self, status: UpdateStatus) -> Result<UpdateStatus, String> {
+        match status {
+            UpdateStatus::Push(name) => self.push_state(&name)?,
+            UpdateStatus::Replace(name) => self.replace_state(&name)?,
+            UpdateStatus::Pop => {
+                self.pop_state();
+            }
+            status => return Ok(status),
+        }
+
+        Ok(UpdateStatus::Continue)
+    }
+}
+
+impl<T: Default> Game<T> {
+    /// Call the function with the state at the index taken out of the stack
+    fn with_state<R, F>(&mut self, index: usize, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut Box<dyn State<T>>, &mut Game<T>) -> R,
+    {
+        let mut state = self.states.entries.get_mut(index)?.state.take()?;
+        let result = f(&mut state, self);
+
+        // the state may have been removed while it was taken
+        if let Some(entry) = self.states.entries.get_mut(index) {
+            if entry.state.is_none() {
+                entry.state = Some(state);
+            }
+        }
+
+        Some(result)
+    }
+
+    /// Apply the transitions states requested from inside their callbacks
+    fn apply_deferred(&mut self) {
+        for status in mem::take(&mut self.states.deferred) {
+            // the states were checked to be registered when requested
+            let _ = self.apply_transition(status);
+        }
+    }
+
+    /// Push the state onto the stack and enter it
+    fn enter_state(&mut self, name: &str, state: Box<dyn State<T>>) {
+        let stash = if state.shares_items() {
+            None
+        } else {
+            Some(StashedItems {
+                render_items: mem::take(&mut self.render_items),
+                text_items: mem::take(&mut self.text_items),
+                particle_emitters: mem::take(&mut self.particle_emitters),
+                sprite_items: mem::take(&mut self.sprite_items),
+                scene: mem::take(&mut self.scene),
+            })
+        };
+        self.render_item_index.invalidate();
+
+        self.states.entries.push(StateEntry {
+            name: name.into(),
+            state: Some(state),
+            stash,
+        });
+        let top = self.states.len() - 1;
+        self.with_state(top, |s, g| s.on_enter(g));
+    }
+
+    /// Exit the top state and pop it off of the stack
+    fn exit_state(&mut self) -> bool {
+        let top = match self.states.len().checked_sub(1) {
+            Some(top) => top,
+            None => return false,
+        };
+        self.with_state(top, |s, g| s.on_exit(g));
+
+        if let Some(entry) = self.states.entries.pop() {
+            if let Some(stash) = entry.stash {
+                self.render_items = stash.render_items;
+                self.text_items = stash.text_items;
+                self.particle_emitters = stash.particle_emitters;
+                self.sprite_items = stash.sprite_items;
+                self.scene = stash.scene;
+                self.render_item_index.invalidate();
+            }
+        }
+        true
+    }
+}
+
+/// Trait for operations on ParticleEmitter
+pub trait ParticleEmitters {
+    /// Get the len of particle_emitters
+    fn particle_emitters_len(&self) -> usize;
+    /// Get an IterMut of the ParticleEmitter
+    fn particle_emitters_iter_mut(&mut self) -> IterMut<'_, ParticleEmitter>;
+    /// Get a ref to a particle emitter
+    fn get_particle_emitter(&mut self, index: usize) -> &mut ParticleEmitter;
+    /// Get a ref to a particle emitter from its name, returning the first found
+    fn get_particle_emitter_by_name(&mut self, name: &str) -> Option<&mut ParticleEmitter>;
+    /// Add a particle emitter to the game
+    fn add_particle_emitter(&mut self, particle_emitter: ParticleEmitter);
+    /// Simulate all of the active particle emitters
+    fn update_particles(&mut self);
+}
+
+impl<T: Default> ParticleEmitters for Game<T> {
+    /// Get the len of particle_emitters
+    fn particle_emitters_len(&self) -> usize {
+        self.particle_emitters.len()
+    }
+
+    /// Get an IterMut of the ParticleEmitter
+    fn particle_emitters_iter_mut(&mut self) -> IterMut<'_, ParticleEmitter> {
+        self.particle_emitters.par_iter_mut()
+    }
+
+    /// Get a ref to a particle emitter
+    fn get_particle_emitter(&mut self, index: usize) -> &mut ParticleEmitter {
+        &mut self.particle_emitters[index]
+    }
+
+    /// Get a ref to a particle emitter from its name, returning the first found
+    fn get_particle_emitter_by_name(&mut self, name: &str) -> Option<&mut ParticleEmitter> {
+        self.particle_emitters
+            .iter_mut()
+            .find(|emitter| emitter.name == name)
+    }
+
+    /// Add a particle emitter to the game
+    fn add_particle_emitter(&mut self, particle_emitter: ParticleEmitter) {
+        self.particle_emitters.push(particle_emitter);
+    }
+
+    /// Simulate all of the active particle emitters
+    fn update_particles(&mut self) {
+        let delta = self.delta;
+        self.particle_emitters
+            .par_iter_mut()
+            .filter(|e| e.active)
+            .for_each(|e| e.update(delta));
+    }
+}
+
+/// Trait for operations on SpriteItem
+pub trait SpriteItems {
+    /// Get the len of sprite_items
+    fn sprite_items_len(&self) -> usize;
+    /// Get an IterMut of the SpriteItem
+    fn sprite_items_iter_mut(&mut self) -> IterMut<'_, SpriteItem>;
+    /// Get a ref to a sprite item
+    fn get_sprite_item(&mut self, index: usize) -> &mut SpriteItem;
+    /// Get a ref to a sprite item from its name, returning the first found
+    fn get_sprite_item_by_name(&mut self, name: &str) -> Option<&mut SpriteItem>;
+    /// Add a sprite item to the game
+    fn add_sprite_item(&mut self, sprite_item: SpriteItem);
+    /// Advance the animations of all the active sprite items
+    fn update_sprites(&mut self);
+}
+
+impl<T: Default> SpriteItems for Game<T> {
+    /// Get the len of sprite_items
+    fn sprite_items_len(&self) -> usize {
+        self.sprite_items.len()
+    }
+
+    /// Get an IterMut of the SpriteItem
+    fn sprite_items_iter_mut(&mut self) -> IterMut<'_, SpriteItem> {
+        self.sprite_items.par_iter_mut()
+    }
+
+    /// Get a ref to a sprite item
+    fn get_sprite_item(&mut self, index: usize) -> &mut SpriteItem {
+        &mut self.sprite_items[index]
+    }
+
+    /// Get a ref to a sprite item from its name, returning the first found
+    fn get_sprite_item_by_name(&mut self, name: &str) -> Option<&mut SpriteItem> {
+        self.sprite_items.iter_mut().find(|item| item.name == name)
+    }
+
+    /// Add a sprite item to the game
+    fn add_sprite_item(&mut self, sprite_item: SpriteItem) {
+        self.sprite_items.push(sprite_item);
+    }
+
+    /// Advance the animations of all the active sprite items
+    fn update_sprites(&mut self) {
+        let delta = self.delta;
+        self.sprite_items
+            .par_iter_mut()
+            .filter(|s| s.active)
+            .for_each(|s| {
+                if let Some(ref mut anim) = s.animation {
+                    anim.update(delta);
+                }
+            });
+    }
 }
 
 /// Trait with default update definition
@@ -384,7 +1066,7 @@ pub trait Update {
 }
 
 /// Impl for Update on Game
-impl<T: Default> Update for Game<T> {
+impl<T: Default + 'static> Update for Game<T> {
     /// Associated type for RenderItems
     type T = T;
     /// Default Game implementation to update the engine state
@@ -400,31 +1082,84 @@ impl<T: Default> Update for Game<T> {
         #[cfg(feature = "nphysics")]
         self.update_physics();
 
-        let status = update(self);
+        // deliver the events published last frame along with this frame's engine events
+        for event in window_engine_events(events) {
+            self.events.publish(event);
+        }
+        self.events.update();
+        self.events.dispatch_raw(events);
+
+        let mut status = update(self);
+        if let UpdateStatus::Continue = status {
+            status = self.update_states();
+        }
+        let status = match self.apply_transition(status) {
+            Ok(status) => status,
+            Err(e) => {
+                self.events.publish(EngineEvent::TransitionFailed(e));
+                UpdateStatus::Continue
+            }
+        };
+
+        self.systems.run(&mut self.world, self.delta);
+        self.update_tweens();
+        self.animations.update(self.delta, &mut self.render_items);
+        #[cfg(feature = "default")]
+        #[cfg(not(feature = "3d-audio"))]
+        self.play_entity_audio();
+
+        self.update_particles();
+        self.update_sprites();
+        self.localization.localize_text_items(&mut self.text_items);
+        self.update_transforms();
 
-        // render the frame
+        let item_count = self.render_items.len();
+        let entities = self.push_entity_render_items();
+
+        // render the frame along with the ui of the current state
         {
+            let mut states = mem::take(&mut self.states);
             self.renderer.draw(
                 &mut self.cams,
                 &mut self.render_items,
                 &mut self.text_items,
-                &mut render_imgui,
+                &mut self.particle_emitters,
+                &mut self.sprite_items,
+                |ui: &Ui| {
+                    render_imgui(ui);
+                    states.draw_ui(ui);
+                },
             );
+            self.states = states;
         }
 
+        self.restore_entity_render_items(item_count, entities);
+
         self.delta = 0.000_000_001f32 * frame_start.elapsed().subsec_nanos() as f32;
 
+        // expire any debug primitives that have been drawn for their duration
+        self.renderer.debug_draw.update(self.delta);
+
         status
     }
 
     /// Default Game implementation to Update inputs
     fn update_inputs(&mut self, events: &Vec<Event<()>>) {
-        {
-            // updating and handling the inputs
-            let gl_window = self.renderer.display.gl_window();
-            let window = gl_window.window();
-            self.input.update_inputs(window, events);
+        match self.recorder.next_frame() {
+            // replaying the recorded inputs instead of the events
+            Some(frame) => {
+                frame.apply(&mut self.input);
+                self.delta = frame.delta;
+            }
+            None => {
+                // updating and handling the inputs
+                let gl_window = self.renderer.display.gl_window();
+                let window = gl_window.window();
+                self.input.update_inputs(window, events);
+                self.recorder.record_frame(&self.input, self.delta);
+            }
         }
+        self.actions.update(&self.input);
         {
             // update the inputs for imgui
             self.renderer.update_imgui_input(&self.input);
@@ -467,10 +1202,11 @@ where
         };
 
         match action {
-            UpdateStatus::Continue => {
+            UpdateStatus::Finish => *control_flow = ControlFlow::Exit,
+            // state transitions are applied by Game::update
+            _ => {
                 *control_flow = ControlFlow::WaitUntil(next_frame_time);
             }
-            UpdateStatus::Finish => *control_flow = ControlFlow::Exit,
         }
     })
 }
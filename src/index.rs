@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use types::RenderItem;
+
+/// Lookups from the tags and names of render items to their indices
+#[derive(Clone, Debug)]
+pub struct ItemIndex<T> {
+    /// The indices of the items with each tag, in ascending order
+    by_tag: HashMap<T, Vec<usize>>,
+    /// The indices of the items with each name, in ascending order
+    by_name: HashMap<String, Vec<usize>>,
+    /// Whether the items have changed since the index was built
+    dirty: bool,
+}
+
+impl<T> Default for ItemIndex<T> {
+    fn default() -> Self {
+        ItemIndex {
+            by_tag: HashMap::new(),
+            by_name: HashMap::new(),
+            dirty: true,
+        }
+    }
+}
+
+impl<T> ItemIndex<T> {
+    /// Create a new index that is built on first use
+    pub fn new() -> ItemIndex<T> {
+        Default::default()
+    }
+
+    /// Mark the index to be rebuilt before it is next used
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Whether the index needs to be rebuilt
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// The indices of the items with the name
+    pub fn with_name(&self, name: &str) -> &[usize] {
+        self.by_name.get(name).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+}
+
+impl<T: Default + Hash + Eq + Clone> ItemIndex<T> {
+    /// Build the index from all of the items
+    pub fn rebuild(&mut self, items: &[RenderItem<T>]) {
+        self.by_tag.clear();
+        self.by_name.clear();
+        for (i, item) in items.iter().enumerate() {
+            self.add_item(i, item);
+        }
+        self.dirty = false;
+    }
+
+    /// Build the index if the items have changed since it was last built
+    pub fn ensure_built(&mut self, items: &[RenderItem<T>]) {
+        if self.dirty {
+            self.rebuild(items);
+        }
+    }
+
+    /// Add the item at the index, items must be added in ascending order
+    pub fn add_item(&mut self, index: usize, item: &RenderItem<T>) {
+        let tags = item_tags(item);
+        for tag in tags {
+            let indices = self.by_tag.entry(tag).or_default();
+            if indices.last() != Some(&index) {
+                indices.push(index);
+            }
+        }
+        self.by_name
+            .entry(item.name.clone())
+            .or_default()
+            .push(index);
+    }
+
+    /// Remove the item at the index from the lookups of its tags
+    pub fn remove_tags(&mut self, index: usize, item: &RenderItem<T>) {
+        for tag in item_tags(item) {
+            if let Some(indices) = self.by_tag.get_mut(&tag) {
+                if let Ok(i) = indices.binary_search(&index) {
+                    indices.remove(i);
+                }
+                if indices.is_empty() {
+                    self.by_tag.remove(&tag);
+                }
+            }
+        }
+    }
+
+    /// Add the item at the index to the lookups of its tags
+    pub fn insert_tags(&mut self, index: usize, item: &RenderItem<T>) {
+        for tag in item_tags(item) {
+            let indices = self.by_tag.entry(tag).or_default();
+            if let Err(i) = indices.binary_search(&index) {
+                indices.insert(i, index);
+            }
+        }
+    }
+
+    /// The indices of the items with the tag
+    pub fn with_tag(&self, tag: &T) -> &[usize] {
+        self.by_tag.get(tag).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// The indices of the items with any tag that matches the predicate
+    pub fn matching<P: Fn(&T) -> bool>(&self, predicate: P) -> Vec<usize> {
+        let mut indices = self
+            .by_tag
+            .iter()
+            .filter(|(tag, _)| predicate(tag))
+            .flat_map(|(_, indices)| indices.iter().cloned())
+            .collect::<Vec<usize>>();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+    }
+
+    /// The distinct tags of all the items
+    pub fn tags(&self) -> Vec<&T> {
+        self.by_tag.keys().collect()
+    }
+}
+
+/// Get mutable refs to the items at the ascending and distinct indices
+pub fn select_mut<'a, I>(items: &'a mut [I], indices: &[usize]) -> Vec<&'a mut I> {
+    let mut selected = Vec::with_capacity(indices.len());
+    let mut rest = items;
+    let mut offset = 0;
+
+    for &index in indices {
+        if index < offset || index - offset >= rest.len() {
+            continue;
+        }
+
+        let (_, tail) = rest.split_at_mut(index - offset);
+        let (item, tail) = tail.split_first_mut().unwrap();
+        selected.push(item);
+        rest = tail;
+        offset = index + 1;
+    }
+
+    selected
+}
+
+/// The tag and additional tags of the item
+fn item_tags<T: Default + Clone>(item: &RenderItem<T>) -> Vec<T> {
+    let mut tags = Vec::with_capacity(item.tags.len() + 1);
+    tags.push(item.tag.clone());
+    tags.extend(item.tags.iter().cloned());
+    tags
+}
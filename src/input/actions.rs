@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use input::gamepad::{GamepadAxis, GamepadButton};
+use input::{Input, Key, MouseButton};
+use persist;
+
+/// The value an analog binding has to pass to count as pressed
+const PRESS_THRESHOLD: f32 = 0.5f32;
+
+/// The analog axes of the mouse
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub enum MouseAxis {
+    /// The horizontal movement of the mouse
+    X,
+    /// The vertical movement of the mouse
+    Y,
+    /// The horizontal movement of the mouse wheel
+    WheelX,
+    /// The vertical movement of the mouse wheel
+    WheelY,
+}
+
+/// A single control that can be bound to an action or axis
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum Binding {
+    /// A key on the keyboard
+    Key(Key),
+    /// A button on the mouse
+    MouseButton(MouseButton),
+    /// Movement of the mouse or mouse wheel
+    MouseAxis(MouseAxis),
+    /// A button on a gamepad
+    GamepadButton(GamepadButton),
+    /// A stick or trigger on a gamepad
+    GamepadAxis(GamepadAxis),
+}
+
+/// A binding for an action along with the modifier keys that must be held
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct ActionBinding {
+    /// The control that triggers the action
+    pub binding: Binding,
+    /// The keys that must also be held down e.g. LControl
+    pub modifiers: Vec<Key>,
+}
+
+/// A binding for an axis and how much it contributes to the axis value
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct AxisBinding {
+    /// The control that moves the axis
+    pub binding: Binding,
+    /// The value is multiplied by this, use a negative scale for the opposite direction
+    pub scale: f32,
+}
+
+/// The bindings and settings for a named axis
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct AxisConfig {
+    /// All of the bindings that move the axis, their values are summed
+    pub bindings: Vec<AxisBinding>,
+    /// Analog values with a magnitude below this are ignored
+    pub dead_zone: f32,
+}
+
+/// The state of an action for the current frame
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+struct ActionState {
+    /// Whether the action is held down
+    down: bool,
+    /// Whether the action was pressed on this frame
+    pressed: bool,
+    /// Whether the action was released on this frame
+    released: bool,
+}
+
+/// Maps inputs to named actions and axes so game code does not test raw keys
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct ActionMap {
+    /// The bindings for each action
+    actions: HashMap<String, Vec<ActionBinding>>,
+    /// The bindings for each axis
+    axes: HashMap<String, AxisConfig>,
+    /// The state of each action for the current frame
+    #[serde(skip)]
+    action_states: HashMap<String, ActionState>,
+    /// The value of each axis for the current frame
+    #[serde(skip)]
+    axis_values: HashMap<String, f32>,
+}
+
+impl ActionMap {
+    /// Create a new empty action map
+    pub fn new() -> ActionMap {
+        Default::default()
+    }
+
+    /// Add a binding to the action
+    pub fn bind_action(&mut self, name: &str, binding: Binding) {
+        self.bind_action_with_modifiers(name, binding, Vec::new());
+    }
+
+    /// Add a binding to the action that only triggers while the modifiers are held
+    pub fn bind_action_with_modifiers(
+        &mut self,
+        name: &str,
+        binding: Binding,
+        modifiers: Vec<Key>,
+    ) {
+        self.actions
+            .entry(name.to_string())
+            .or_default()
+            .push(ActionBinding { binding, modifiers });
+    }
+
+    /// Replace all of the bindings for the action
+    pub fn set_action_bindings(&mut self, name: &str, bindings: Vec<ActionBinding>) {
+        self.actions.insert(name.to_string(), bindings);
+    }
+
+    /// Get the bindings for the action
+    pub fn action_bindings(&self, name: &str) -> Option<&Vec<ActionBinding>> {
+        self.actions.get(name)
+    }
+
+    /// Add a binding to the axis
+    pub fn bind_axis(&mut self, name: &str, binding: Binding, scale: f32) {
+        self.axes
+            .entry(name.to_string())
+            .or_default()
+            .bindings
+            .push(AxisBinding { binding, scale });
+    }
+
+    /// Set the dead zone for the analog bindings of the axis
+    pub fn set_axis_dead_zone(&mut self, name: &str, dead_zone: f32) {
+        self.axes.entry(name.to_string()).or_default().dead_zone = dead_zone;
+    }
+
+    /// Replace all of the bindings and settings for the axis
+    pub fn set_axis_config(&mut self, name: &str, config: AxisConfig) {
+        self.axes.insert(name.to_string(), config);
+    }
+
+    /// Get the bindings and settings for the axis
+    pub fn axis_config(&self, name: &str) -> Option<&AxisConfig> {
+        self.axes.get(name)
+    }
+
+    /// Remove all of the bindings for the action or axis with the name
+    pub fn unbind(&mut self, name: &str) {
+        self.actions.remove(name);
+        self.axes.remove(name);
+        self.action_states.remove(name);
+        self.axis_values.remove(name);
+    }
+
+    /// Update the state of all the actions and axes from the input for this frame
+    pub fn update(&mut self, input: &Input) {
+        for (name, bindings) in self.actions.iter() {
+            let down = bindings.iter().any(|b| {
+                b.modifiers.iter().all(|m| input.keys_down.contains(m))
+                    && binding_value(&b.binding, input).abs() >= PRESS_THRESHOLD
+            });
+
+            let state = self.action_states.entry(name.clone()).or_default();
+            state.pressed = down && !state.down;
+            state.released = !down && state.down;
+            state.down = down;
+        }
+
+        for (name, config) in self.axes.iter() {
+            let value = config
+                .bindings
+                .iter()
+                .map(|b| {
+                    let value = binding_value(&b.binding, input);
+                    if value.abs() < config.dead_zone {
+                        0f32
+                    } else {
+                        value * b.scale
+                    }
+                })
+                .sum();
+
+            self.axis_values.insert(name.clone(), value);
+        }
+    }
+
+    /// Whether the action is held down
+    pub fn pressed(&self, name: &str) -> bool {
+        self.action_states.get(name).is_some_and(|s| s.down)
+    }
+
+    /// Whether the action was pressed on this frame
+    pub fn just_pressed(&self, name: &str) -> bool {
+        self.action_states.get(name).is_some_and(|s| s.pressed)
+    }
+
+    /// Whether the action was released on this frame
+    pub fn just_released(&self, name: &str) -> bool {
+        self.action_states.get(name).is_some_and(|s| s.released)
+    }
+
+    /// The value of the axis, the sum of all its bindings
+    pub fn axis_value(&self, name: &str) -> f32 {
+        self.axis_values.get(name).cloned().unwrap_or(0f32)
+    }
+
+    /// Save the bindings through persist at the key
+    pub fn save(&self, key: &'static str) {
+        persist::save(self, key);
+    }
+
+    /// Load bindings that were saved through persist at the key
+    pub fn load(key: &'static str) -> Result<ActionMap, String> {
+        persist::load(key)
+    }
+}
+
+/// Get the current value of the binding, buttons are 1 when held and 0 otherwise
+fn binding_value(binding: &Binding, input: &Input) -> f32 {
+    let button = |down: bool| if down { 1f32 } else { 0f32 };
+
+    match *binding {
+        Binding::Key(key) => button(input.keys_down.contains(&key)),
+        Binding::MouseButton(btn) => button(input.mouse_btns_down.contains(&btn)),
+        Binding::MouseAxis(axis) => match axis {
+            MouseAxis::X => input.mouse_delta.0,
+            MouseAxis::Y => input.mouse_delta.1,
+            MouseAxis::WheelX => input.mouse_wheel_delta.0,
+            MouseAxis::WheelY => input.mouse_wheel_delta.1,
+        },
+        Binding::GamepadButton(btn) => button(input.gamepads.button_down(btn)),
+        Binding::GamepadAxis(axis) => input.gamepads.axis(axis),
+    }
+}
@@ -0,0 +1,383 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// The buttons of a gamepad, named by their position on the pad
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub enum GamepadButton {
+    /// The bottom face button, A on Xbox style pads
+    South,
+    /// The right face button, B on Xbox style pads
+    East,
+    /// The left face button, X on Xbox style pads
+    West,
+    /// The top face button, Y on Xbox style pads
+    North,
+    /// The left shoulder bumper
+    LeftBumper,
+    /// The right shoulder bumper
+    RightBumper,
+    /// The select/back button
+    Select,
+    /// The start button
+    Start,
+    /// The mode/home button
+    Mode,
+    /// Pressing in the left stick
+    LeftStick,
+    /// Pressing in the right stick
+    RightStick,
+    /// Up on the directional pad
+    DPadUp,
+    /// Down on the directional pad
+    DPadDown,
+    /// Left on the directional pad
+    DPadLeft,
+    /// Right on the directional pad
+    DPadRight,
+}
+
+/// The analog axes of a gamepad
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub enum GamepadAxis {
+    /// The horizontal axis of the left stick from -1 to 1
+    LeftStickX,
+    /// The vertical axis of the left stick from -1 to 1
+    LeftStickY,
+    /// The horizontal axis of the right stick from -1 to 1
+    RightStickX,
+    /// The vertical axis of the right stick from -1 to 1
+    RightStickY,
+    /// The left trigger from 0 to 1
+    LeftTrigger,
+    /// The right trigger from 0 to 1
+    RightTrigger,
+}
+
+/// An event from a gamepad backend
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum GamepadEvent {
+    /// A gamepad with the id and name was connected
+    Connected(usize, String),
+    /// The gamepad with the id was disconnected
+    Disconnected(usize),
+    /// A button was pressed on the gamepad with the id
+    ButtonPressed(usize, GamepadButton),
+    /// A button was released on the gamepad with the id
+    ButtonReleased(usize, GamepadButton),
+    /// An axis on the gamepad with the id moved to the value
+    AxisMoved(usize, GamepadAxis, f32),
+}
+
+/// Trait for the source of gamepad events e.g. a platform gamepad library
+pub trait GamepadBackend {
+    /// Get all of the events that have happened since the last poll
+    fn poll_events(&mut self) -> Vec<GamepadEvent>;
+}
+
+/// A gamepad backend that is driven from code rather than hardware,
+/// used for testing and for scripting gamepad input
+#[derive(Clone, Default)]
+pub struct VirtualGamepadBackend {
+    /// The events waiting to be polled, shared between clones
+    queue: Arc<Mutex<Vec<GamepadEvent>>>,
+}
+
+impl VirtualGamepadBackend {
+    /// Create a new virtual backend with no gamepads connected
+    pub fn new() -> VirtualGamepadBackend {
+        Default::default()
+    }
+
+    /// Queue an event to be received on the next poll
+    pub fn push_event(&self, event: GamepadEvent) {
+        self.queue.lock().unwrap().push(event);
+    }
+
+    /// Connect a virtual gamepad with the id
+    pub fn connect(&self, id: usize, name: &str) {
+        self.push_event(GamepadEvent::Connected(id, name.to_string()));
+    }
+
+    /// Disconnect the virtual gamepad with the id
+    pub fn disconnect(&self, id: usize) {
+        self.push_event(GamepadEvent::Disconnected(id));
+    }
+
+    /// Press a button on the virtual gamepad with the id
+    pub fn press(&self, id: usize, button: GamepadButton) {
+        self.push_event(GamepadEvent::ButtonPressed(id, button));
+    }
+
+    /// Release a button on the virtual gamepad with the id
+    pub fn release(&self, id: usize, button: GamepadButton) {
+        self.push_event(GamepadEvent::ButtonReleased(id, button));
+    }
+
+    /// Move an axis on the virtual gamepad with the id
+    pub fn move_axis(&self, id: usize, axis: GamepadAxis, value: f32) {
+        self.push_event(GamepadEvent::AxisMoved(id, axis, value));
+    }
+}
+
+impl GamepadBackend for VirtualGamepadBackend {
+    fn poll_events(&mut self) -> Vec<GamepadEvent> {
+        self.queue.lock().unwrap().drain(..).collect()
+    }
+}
+
+/// A gamepad backend for hardware gamepads using gilrs,
+/// only available with the `gamepad` feature
+#[cfg(feature = "gamepad")]
+pub struct GilrsBackend {
+    gilrs: gilrs::Gilrs,
+}
+
+#[cfg(feature = "gamepad")]
+impl GilrsBackend {
+    /// Create a new backend, fails if the platform's gamepads can't be read
+    pub fn new() -> Result<GilrsBackend, String> {
+        let gilrs = gilrs::Gilrs::new().map_err(|e| e.to_string())?;
+        Ok(GilrsBackend { gilrs })
+    }
+
+    /// Convert a gilrs button, the triggers are sent as axes instead
+    fn button(button: gilrs::Button) -> Option<GamepadButton> {
+        use gilrs::Button;
+        match button {
+            Button::South => Some(GamepadButton::South),
+            Button::East => Some(GamepadButton::East),
+            Button::West => Some(GamepadButton::West),
+            Button::North => Some(GamepadButton::North),
+            Button::LeftTrigger => Some(GamepadButton::LeftBumper),
+            Button::RightTrigger => Some(GamepadButton::RightBumper),
+            Button::Select => Some(GamepadButton::Select),
+            Button::Start => Some(GamepadButton::Start),
+            Button::Mode => Some(GamepadButton::Mode),
+            Button::LeftThumb => Some(GamepadButton::LeftStick),
+            Button::RightThumb => Some(GamepadButton::RightStick),
+            Button::DPadUp => Some(GamepadButton::DPadUp),
+            Button::DPadDown => Some(GamepadButton::DPadDown),
+            Button::DPadLeft => Some(GamepadButton::DPadLeft),
+            Button::DPadRight => Some(GamepadButton::DPadRight),
+            _ => None,
+        }
+    }
+
+    /// Convert a gilrs axis
+    fn axis(axis: gilrs::Axis) -> Option<GamepadAxis> {
+        use gilrs::Axis;
+        match axis {
+            Axis::LeftStickX => Some(GamepadAxis::LeftStickX),
+            Axis::LeftStickY => Some(GamepadAxis::LeftStickY),
+            Axis::RightStickX => Some(GamepadAxis::RightStickX),
+            Axis::RightStickY => Some(GamepadAxis::RightStickY),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl GamepadBackend for GilrsBackend {
+    fn poll_events(&mut self) -> Vec<GamepadEvent> {
+        use gilrs::{Button, EventType};
+
+        let mut events = Vec::new();
+        while let Some(gilrs::Event { id, event, .. }) = self.gilrs.next_event() {
+            let pad = usize::from(id);
+            match event {
+                EventType::Connected => {
+                    let name = self.gilrs.gamepad(id).name().to_string();
+                    events.push(GamepadEvent::Connected(pad, name));
+                }
+                EventType::Disconnected => events.push(GamepadEvent::Disconnected(pad)),
+                EventType::ButtonPressed(button, _) => {
+                    if let Some(button) = Self::button(button) {
+                        events.push(GamepadEvent::ButtonPressed(pad, button));
+                    }
+                }
+                EventType::ButtonReleased(button, _) => {
+                    if let Some(button) = Self::button(button) {
+                        events.push(GamepadEvent::ButtonReleased(pad, button));
+                    }
+                }
+                EventType::ButtonChanged(Button::LeftTrigger2, value, _) => {
+                    events.push(GamepadEvent::AxisMoved(
+                        pad,
+                        GamepadAxis::LeftTrigger,
+                        value,
+                    ));
+                }
+                EventType::ButtonChanged(Button::RightTrigger2, value, _) => {
+                    events.push(GamepadEvent::AxisMoved(
+                        pad,
+                        GamepadAxis::RightTrigger,
+                        value,
+                    ));
+                }
+                EventType::AxisChanged(axis, value, _) => {
+                    if let Some(axis) = Self::axis(axis) {
+                        events.push(GamepadEvent::AxisMoved(pad, axis, value));
+                    }
+                }
+                _ => (),
+            }
+        }
+        events
+    }
+}
+
+/// The state of a single gamepad
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Gamepad {
+    /// The id the backend uses for the gamepad
+    pub id: usize,
+    /// The name of the gamepad
+    pub name: String,
+    /// Whether the gamepad is currently connected
+    pub connected: bool,
+    /// The buttons that are currently pressed down
+    pub buttons_down: Vec<GamepadButton>,
+    /// The buttons that have been pressed on this frame
+    pub buttons_pressed: Vec<GamepadButton>,
+    /// The buttons that have been released on this frame
+    pub buttons_released: Vec<GamepadButton>,
+    /// The current value of each axis that has moved
+    pub axes: HashMap<GamepadAxis, f32>,
+}
+
+impl Gamepad {
+    /// The value of the axis, 0 if it has not moved
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes.get(&axis).cloned().unwrap_or(0f32)
+    }
+}
+
+/// All of the gamepads and the backend they are updated from.
+///
+/// Hardware gamepads are only read with the `gamepad` feature, which uses
+/// `GilrsBackend` by default. Without it no hardware is supported and the
+/// default backend is a `VirtualGamepadBackend`, so events only come from
+/// code or a custom `GamepadBackend` set with `set_backend`.
+pub struct Gamepads {
+    /// The source of the gamepad events
+    backend: Box<dyn GamepadBackend>,
+    /// Every gamepad that has been connected
+    pads: Vec<Gamepad>,
+    /// The events received on this frame
+    pub events: Vec<GamepadEvent>,
+}
+
+impl Default for Gamepads {
+    #[cfg(feature = "gamepad")]
+    fn default() -> Self {
+        // fall back to a virtual backend when there are no gamepads to read
+        match GilrsBackend::new() {
+            Ok(backend) => Self::new(Box::new(backend)),
+            Err(_) => Self::new(Box::new(VirtualGamepadBackend::new())),
+        }
+    }
+
+    #[cfg(not(feature = "gamepad"))]
+    fn default() -> Self {
+        Self::new(Box::new(VirtualGamepadBackend::new()))
+    }
+}
+
+impl Gamepads {
+    /// Create a new gamepad system updated from the backend
+    pub fn new(backend: Box<dyn GamepadBackend>) -> Gamepads {
+        Gamepads {
+            backend,
+            pads: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Replace the backend the gamepads are updated from
+    pub fn set_backend(&mut self, backend: Box<dyn GamepadBackend>) {
+        self.backend = backend;
+    }
+
+    /// Poll the backend and update the state of the gamepads for this frame
+    pub fn update(&mut self) {
+        let events = self.backend.poll_events();
+        self.apply_events(events);
+    }
+
+    /// Update the state of the gamepads from the events for this frame
+    pub fn apply_events(&mut self, events: Vec<GamepadEvent>) {
+        for pad in self.pads.iter_mut() {
+            pad.buttons_pressed.clear();
+            pad.buttons_released.clear();
+        }
+
+        for event in events.iter() {
+            match *event {
+                GamepadEvent::Connected(id, ref name) => {
+                    let pad = self.pad_mut(id);
+                    pad.name = name.clone();
+                    pad.connected = true;
+                }
+                GamepadEvent::Disconnected(id) => {
+                    let pad = self.pad_mut(id);
+                    pad.connected = false;
+                    pad.buttons_down.clear();
+                    pad.axes.clear();
+                }
+                GamepadEvent::ButtonPressed(id, button) => {
+                    let pad = self.pad_mut(id);
+                    if !pad.buttons_down.contains(&button) {
+                        pad.buttons_down.push(button);
+                    }
+                    pad.buttons_pressed.push(button);
+                }
+                GamepadEvent::ButtonReleased(id, button) => {
+                    let pad = self.pad_mut(id);
+                    pad.buttons_down.retain(|&b| b != button);
+                    pad.buttons_released.push(button);
+                }
+                GamepadEvent::AxisMoved(id, axis, value) => {
+                    self.pad_mut(id).axes.insert(axis, value);
+                }
+            }
+        }
+
+        self.events = events;
+    }
+
+    /// Get the gamepad with the id
+    pub fn get(&self, id: usize) -> Option<&Gamepad> {
+        self.pads.iter().find(|p| p.id == id)
+    }
+
+    /// Iterate over the connected gamepads
+    pub fn connected(&self) -> impl Iterator<Item = &Gamepad> {
+        self.pads.iter().filter(|p| p.connected)
+    }
+
+    /// Whether the button is held down on any connected gamepad
+    pub fn button_down(&self, button: GamepadButton) -> bool {
+        self.connected().any(|p| p.buttons_down.contains(&button))
+    }
+
+    /// The value of the axis with the largest magnitude across the connected gamepads
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.connected()
+            .map(|p| p.axis(axis))
+            .fold(0f32, |a, v| if v.abs() > a.abs() { v } else { a })
+    }
+
+    /// Get the gamepad with the id, adding it if it has not been seen before
+    fn pad_mut(&mut self, id: usize) -> &mut Gamepad {
+        match self.pads.iter().position(|p| p.id == id) {
+            Some(i) => &mut self.pads[i],
+            None => {
+                self.pads.push(Gamepad {
+                    id,
+                    ..Default::default()
+                });
+                self.pads.last_mut().unwrap()
+            }
+        }
+    }
+}
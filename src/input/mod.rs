@@ -0,0 +1,56 @@
+// everything from volition is re-exported, its Input is wrapped by the one below
+pub use volition::*;
+
+/// Module for mapping inputs to named actions and axes
+pub mod actions;
+/// Module for gamepad controls
+pub mod gamepad;
+/// Module for recording and replaying input
+pub mod record;
+
+use glium::glutin::event::Event;
+use glium::glutin::window::Window;
+
+use std::ops::{Deref, DerefMut};
+
+use self::gamepad::Gamepads;
+
+/// struct for the state of all the inputs, the keyboard and mouse state
+/// from volition can be accessed directly e.g. `input.keys_down`
+#[derive(Default)]
+pub struct Input {
+    /// The keyboard and mouse state
+    devices: volition::Input,
+    /// The state of the connected gamepads
+    pub gamepads: Gamepads,
+}
+
+impl Input {
+    /// Creates a new Input instance
+    pub fn new() -> Input {
+        Input {
+            devices: volition::Input::new(),
+            gamepads: Gamepads::default(),
+        }
+    }
+
+    /// This method updates the state of the inputs
+    pub fn update_inputs(&mut self, window: &Window, events: &Vec<Event<()>>) {
+        self.devices.update_inputs(window, events);
+        self.gamepads.update();
+    }
+}
+
+impl Deref for Input {
+    type Target = volition::Input;
+
+    fn deref(&self) -> &volition::Input {
+        &self.devices
+    }
+}
+
+impl DerefMut for Input {
+    fn deref_mut(&mut self) -> &mut volition::Input {
+        &mut self.devices
+    }
+}
@@ -0,0 +1,182 @@
+use input::gamepad::GamepadEvent;
+use input::{Input, Key, MouseButton};
+use persist;
+
+/// The state of the inputs for a single recorded frame
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct InputFrame {
+    /// The frame delta the update was run with
+    pub delta: f32,
+    /// The position of the mouse
+    pub mouse_pos: (f32, f32),
+    /// The difference in mouse position from the last frame
+    pub mouse_delta: (f32, f32),
+    /// The raw difference in mouse position from the last frame
+    pub raw_mouse_delta: (f32, f32),
+    /// The mouse axis motion for the last frame
+    pub mouse_axis_motion: (f32, f32),
+    /// The movement of the mouse wheel
+    pub mouse_wheel_delta: (f32, f32),
+    /// The keys that are currently pressed down
+    pub keys_down: Vec<Key>,
+    /// The keys that have been pressed on this frame
+    pub keys_pressed: Vec<Key>,
+    /// The keys that have been released on this frame
+    pub keys_released: Vec<Key>,
+    /// Characters received on this frame
+    pub characters_down: Vec<char>,
+    /// The mouse buttons that are currently pressed down
+    pub mouse_btns_down: Vec<MouseButton>,
+    /// The mouse buttons that have been pressed down on this frame
+    pub mouse_btns_pressed: Vec<MouseButton>,
+    /// The mouse buttons that have been released on this frame
+    pub mouse_btns_released: Vec<MouseButton>,
+    /// The gamepad events received on this frame
+    pub gamepad_events: Vec<GamepadEvent>,
+}
+
+impl InputFrame {
+    /// Capture the current state of the input along with the frame delta
+    pub fn capture(input: &Input, delta: f32) -> InputFrame {
+        InputFrame {
+            delta,
+            mouse_pos: input.mouse_pos,
+            mouse_delta: input.mouse_delta,
+            raw_mouse_delta: input.raw_mouse_delta,
+            mouse_axis_motion: input.mouse_axis_motion,
+            mouse_wheel_delta: input.mouse_wheel_delta,
+            keys_down: input.keys_down.clone(),
+            keys_pressed: input.keys_pressed.clone(),
+            keys_released: input.keys_released.clone(),
+            characters_down: input.characters_down.clone(),
+            mouse_btns_down: input.mouse_btns_down.clone(),
+            mouse_btns_pressed: input.mouse_btns_pressed.clone(),
+            mouse_btns_released: input.mouse_btns_released.clone(),
+            gamepad_events: input.gamepads.events.clone(),
+        }
+    }
+
+    /// Set the state of the input to the recorded state
+    pub fn apply(&self, input: &mut Input) {
+        input.mouse_pos = self.mouse_pos;
+        input.mouse_delta = self.mouse_delta;
+        input.raw_mouse_delta = self.raw_mouse_delta;
+        input.mouse_axis_motion = self.mouse_axis_motion;
+        input.mouse_wheel_delta = self.mouse_wheel_delta;
+        input.keys_down = self.keys_down.clone();
+        input.keys_pressed = self.keys_pressed.clone();
+        input.keys_released = self.keys_released.clone();
+        input.characters_down = self.characters_down.clone();
+        input.mouse_btns_down = self.mouse_btns_down.clone();
+        input.mouse_btns_pressed = self.mouse_btns_pressed.clone();
+        input.mouse_btns_released = self.mouse_btns_released.clone();
+        input.gamepads.apply_events(self.gamepad_events.clone());
+    }
+}
+
+/// A recorded session of input frames
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct InputRecording {
+    /// The frames in the order they were recorded
+    pub frames: Vec<InputFrame>,
+}
+
+impl InputRecording {
+    /// Save the recording through persist at the key
+    pub fn save(&self, key: &'static str) {
+        persist::save(self, key);
+    }
+
+    /// Load a recording that was saved through persist at the key
+    pub fn load(key: &'static str) -> Result<InputRecording, String> {
+        persist::load(key)
+    }
+}
+
+/// Whether the recorder is recording, replaying or neither
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum RecorderState {
+    /// The live input is used and not recorded
+    Idle,
+    /// The live input is used and each frame is recorded
+    Recording,
+    /// The recorded frames are used instead of the live input
+    Replaying,
+}
+
+/// Records the input of each frame and replays it back
+pub struct InputRecorder {
+    /// What the recorder is currently doing
+    state: RecorderState,
+    /// The recording being made or replayed
+    recording: InputRecording,
+    /// The next frame to be replayed
+    frame: usize,
+}
+
+impl Default for InputRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputRecorder {
+    /// Create a new idle recorder
+    pub fn new() -> InputRecorder {
+        InputRecorder {
+            state: RecorderState::Idle,
+            recording: Default::default(),
+            frame: 0,
+        }
+    }
+
+    /// What the recorder is currently doing
+    pub fn state(&self) -> RecorderState {
+        self.state
+    }
+
+    /// Start a new recording, discarding any previous one
+    pub fn start_recording(&mut self) {
+        self.recording = Default::default();
+        self.state = RecorderState::Recording;
+    }
+
+    /// Stop recording or replaying, returning the recording
+    pub fn stop(&mut self) -> InputRecording {
+        self.state = RecorderState::Idle;
+        self.frame = 0;
+        ::std::mem::take(&mut self.recording)
+    }
+
+    /// Start replaying the recording from the first frame
+    pub fn start_replay(&mut self, recording: InputRecording) {
+        self.recording = recording;
+        self.frame = 0;
+        self.state = RecorderState::Replaying;
+    }
+
+    /// Record the state of the input if recording
+    pub fn record_frame(&mut self, input: &Input, delta: f32) {
+        if self.state == RecorderState::Recording {
+            self.recording
+                .frames
+                .push(InputFrame::capture(input, delta));
+        }
+    }
+
+    /// Get the next frame to replay if replaying, the recorder goes idle
+    /// once all of the frames have been replayed
+    pub fn next_frame(&mut self) -> Option<InputFrame> {
+        if self.state != RecorderState::Replaying {
+            return None;
+        }
+
+        let frame = self.recording.frames.get(self.frame).cloned();
+        self.frame += 1;
+        if frame.is_none() {
+            self.stop();
+        }
+
+        frame
+    }
+}
@@ -92,33 +92,66 @@ pub extern crate nalgebra;
 pub extern crate ncollide3d as ncollide;
 #[cfg(feature = "nphysics")]
 pub extern crate nphysics3d;
-pub extern crate volition as input;
 
 extern crate bincode;
 extern crate fps_counter;
 extern crate gif;
+#[cfg(feature = "gamepad")]
+extern crate gilrs;
 extern crate glium_text_rusttype as glium_text;
 extern crate imgui_glium_renderer;
 extern crate noise;
 extern crate rayon;
 extern crate serde;
 extern crate time;
+extern crate volition;
 
+/// Module for keyframe animation clips and their players
+pub mod animation;
+/// Module for camera controllers
+pub mod camera;
 /// Simple collision detection
 pub mod collision;
+/// Module for entities, their components and the systems run on them
+pub mod ecs;
+/// Module for publishing and subscribing to events
+pub mod events;
 /// Module represent another way of creating a game
 pub mod game;
+/// Module for looking up render items by tag and name
+pub mod index;
+/// Module for the input systems
+pub mod input;
+/// Module for localized text tables
+pub mod localization;
+/// Module for lower detail meshes chosen by distance
+pub mod lod;
+/// Module for vector, quaternion and matrix types
+pub mod math;
 /// Module for procedurally generated meshes
 pub mod mesh;
+/// Module for morph targets blended per instance
+pub mod morph;
+/// Module for particle emitters and their simulation
+pub mod particles;
 /// Module for saving and loading data
 pub mod persist;
 /// A module for rendering items
 pub mod renderer;
+/// Module for parent/child transform relationships
+pub mod scene;
+/// Module for skeletons and skinned meshes
+pub mod skinning;
+/// Module for the stack of game states
+pub mod state;
+/// Module for tweens, easing curves and timers
+pub mod tween;
 /// All of the caper types
 pub mod types;
 /// Utility functions and macros
 pub mod utils;
 
+pub use renderer::debug;
 pub use renderer::lighting;
 pub use renderer::posteffect;
 pub use renderer::shader;
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+
+use types::TextItem;
+
+/// The name of the argument used to pick the plural form of a string
+pub const COUNT_ARG: &str = "count";
+
+/// A table of localized strings for a single locale
+///
+/// Tables are parsed from a simple `key = value` format:
+/// ```text
+/// # comments start with a hash
+/// greeting = Hello {name}!
+/// apples.one = {count} apple
+/// apples.other = {count} apples
+/// ```
+/// Plural forms are keys ending in `.zero`, `.one` or `.other`.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct LocaleTable {
+    /// The locale the table is for e.g. "en" or "fr"
+    pub locale: String,
+    /// The localized strings by key
+    pub strings: HashMap<String, String>,
+}
+
+impl LocaleTable {
+    /// Parse a table from the source text
+    pub fn parse(locale: &str, source: &str) -> Result<LocaleTable, String> {
+        let mut strings = HashMap::new();
+
+        for (i, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap_or("").trim();
+            let value = match parts.next() {
+                Some(v) => v.trim(),
+                None => return Err(format!("Missing '=' on line {}", i + 1)),
+            };
+            if key.is_empty() {
+                return Err(format!("Missing key on line {}", i + 1));
+            }
+
+            strings.insert(key.to_string(), value.replace("\\n", "\n"));
+        }
+
+        Ok(LocaleTable {
+            locale: locale.to_string(),
+            strings,
+        })
+    }
+
+    /// Load and parse a table from the file at path
+    pub fn load_path(locale: &str, path: &str) -> Result<LocaleTable, String> {
+        let mut source = String::new();
+        File::open(path)
+            .and_then(|mut f| f.read_to_string(&mut source))
+            .map_err(|e| format!("{}", e))?;
+
+        LocaleTable::parse(locale, source.as_str())
+    }
+
+    /// Get the string for the key, picking the plural form if count is given
+    pub fn get(&self, key: &str, count: Option<i64>) -> Option<&String> {
+        if let Some(count) = count {
+            let form = match count {
+                0 => "zero",
+                1 | -1 => "one",
+                _ => "other",
+            };
+
+            let plural = self
+                .strings
+                .get(&format!("{}.{}", key, form))
+                .or_else(|| self.strings.get(&format!("{}.other", key)));
+            if plural.is_some() {
+                return plural;
+            }
+        }
+
+        self.strings.get(key)
+    }
+}
+
+/// The localization tables for all locales and which one is active
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct Localization {
+    /// The tables by locale
+    tables: HashMap<String, LocaleTable>,
+    /// The locale strings are looked up in
+    locale: String,
+    /// The locale used when a key is missing from the active locale
+    pub fallback_locale: String,
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Localization {
+            tables: HashMap::new(),
+            locale: "en".into(),
+            fallback_locale: "en".into(),
+        }
+    }
+}
+
+impl Localization {
+    /// Add a table, replacing any existing table for the same locale
+    pub fn add_table(&mut self, table: LocaleTable) {
+        self.tables.insert(table.locale.clone(), table);
+    }
+
+    /// Load a table for the locale from the file at path
+    pub fn load_path(&mut self, locale: &str, path: &str) -> Result<(), String> {
+        let table = LocaleTable::load_path(locale, path)?;
+        self.add_table(table);
+        Ok(())
+    }
+
+    /// Get the table for the locale
+    pub fn get_table(&self, locale: &str) -> Option<&LocaleTable> {
+        self.tables.get(locale)
+    }
+
+    /// The active locale
+    pub fn locale(&self) -> &str {
+        self.locale.as_str()
+    }
+
+    /// Set the active locale, text items are updated on the next frame
+    pub fn set_locale(&mut self, locale: &str) {
+        self.locale = locale.to_string();
+    }
+
+    /// Get the string for the key in the active locale with the placeholders
+    /// replaced by args, returns the key if it is missing from every table
+    pub fn translate(&self, key: &str, args: &[(String, String)]) -> String {
+        let count = args
+            .iter()
+            .find(|(name, _)| name == COUNT_ARG)
+            .and_then(|(_, value)| value.parse::<i64>().ok());
+
+        let string = [self.locale.as_str(), self.fallback_locale.as_str()]
+            .iter()
+            .filter_map(|l| self.tables.get(*l))
+            .filter_map(|t| t.get(key, count))
+            .next();
+
+        match string {
+            Some(s) => substitute(s, args),
+            None => key.to_string(),
+        }
+    }
+
+    /// Set the text of all the text items that have a localization key
+    pub fn localize_text_items(&self, text_items: &mut [TextItem]) {
+        for text_item in text_items.iter_mut() {
+            if let Some(ref key) = text_item.loc_key {
+                text_item.text = self.translate(key, &text_item.loc_args);
+            }
+        }
+    }
+}
+
+/// Replace each `{name}` placeholder in the string with the value of the arg
+pub fn substitute(string: &str, args: &[(String, String)]) -> String {
+    args.iter().fold(string.to_string(), |s, (name, value)| {
+        s.replace(&format!("{{{}}}", name), value)
+    })
+}
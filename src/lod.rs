@@ -0,0 +1,117 @@
+use mesh::simplify;
+use renderer::FIELD_OF_VIEW;
+use types::Vertex;
+
+/// What the thresholds of the LOD levels are measured in
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub enum LodMetric {
+    /// A level is used when the instance is at least the threshold away from the camera
+    #[default]
+    Distance,
+    /// A level is used when the instance covers at most the threshold of the
+    /// screen's height
+    ScreenSize,
+}
+
+/// A lower detail mesh of a render item
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq)]
+#[builder(default)]
+pub struct LodLevel {
+    /// The vertices of the mesh
+    pub vertices: Vec<Vertex>,
+    /// Where the level starts being used, measured by the metric of the lods
+    pub threshold: f32,
+}
+
+impl Default for LodLevel {
+    fn default() -> Self {
+        LodLevel {
+            vertices: Vec::new(),
+            threshold: 50f32,
+        }
+    }
+}
+
+/// The lower detail meshes of a render item, chosen for each instance when it's
+/// culled, the render item's own vertices are level 0, skinned and morphed
+/// items always use their own vertices
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq)]
+#[builder(default)]
+pub struct Lods {
+    /// The levels from most to least detailed, ordered by their thresholds
+    pub levels: Vec<LodLevel>,
+    /// What the thresholds are measured in
+    pub metric: LodMetric,
+    /// The width of the band before each threshold where the current level
+    /// fades out as the next level fades in, 0 switches straight away. The fade
+    /// is applied to the alpha of the instance's tint, so custom shaders have
+    /// to multiply their output by the tint for it to show
+    pub fade: f32,
+}
+
+impl Default for Lods {
+    fn default() -> Self {
+        Lods {
+            levels: Vec::new(),
+            metric: LodMetric::Distance,
+            fade: 0f32,
+        }
+    }
+}
+
+impl Lods {
+    /// Generate the levels by simplifying the vertices, each level is a
+    /// (threshold, cells) pair where cells is the resolution it's simplified to
+    pub fn generate(vertices: &[Vertex], levels: &[(f32, u32)], metric: LodMetric) -> Lods {
+        Lods {
+            levels: levels
+                .iter()
+                .map(|&(threshold, cells)| LodLevel {
+                    vertices: simplify(vertices, cells),
+                    threshold,
+                })
+                .collect(),
+            metric,
+            ..Default::default()
+        }
+    }
+
+    /// The vertices of a level, level 0 is the render item's own vertices
+    pub fn vertices<'a>(&'a self, base: &'a [Vertex], level: usize) -> &'a [Vertex] {
+        match level {
+            0 => base,
+            _ => self
+                .levels
+                .get(level - 1)
+                .map_or(base, |l| l.vertices.as_slice()),
+        }
+    }
+
+    /// The level of an instance at the distance from the camera with the
+    /// bounding radius, and how far the next level is faded in from 0 to 1
+    pub fn select(&self, distance: f32, radius: f32) -> (usize, f32) {
+        // how far the instance is past a threshold, positive once it's past
+        let past = |threshold: f32| match self.metric {
+            LodMetric::Distance => distance - threshold,
+            LodMetric::ScreenSize => threshold - screen_size(distance, radius),
+        };
+
+        let level = self
+            .levels
+            .iter()
+            .take_while(|l| past(l.threshold) >= 0f32)
+            .count();
+        let fade = match self.levels.get(level) {
+            Some(next) if self.fade > 0f32 => (1f32 + past(next.threshold) / self.fade).max(0f32),
+            _ => 0f32,
+        };
+
+        (level, fade)
+    }
+}
+
+/// The fraction of the screen's height covered by a sphere with the radius at
+/// the distance from the camera
+pub fn screen_size(distance: f32, radius: f32) -> f32 {
+    radius / (distance * (FIELD_OF_VIEW.to_radians() / 2f32).tan())
+}
@@ -0,0 +1,740 @@
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use types::{Matrix4, Quaternion, Vector3};
+use utils::{to_euler, to_quaternion};
+
+/// The difference below which two quaternions are lerped rather than slerped
+const SLERP_THRESHOLD: f32 = 0.9995f32;
+
+/// A 2d vector
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Vec2 {
+    /// The x component
+    pub x: f32,
+    /// The y component
+    pub y: f32,
+}
+
+/// A 3d vector
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Vec3 {
+    /// The x component
+    pub x: f32,
+    /// The y component
+    pub y: f32,
+    /// The z component
+    pub z: f32,
+}
+
+/// A 4d vector
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Vec4 {
+    /// The x component
+    pub x: f32,
+    /// The y component
+    pub y: f32,
+    /// The z component
+    pub z: f32,
+    /// The w component
+    pub w: f32,
+}
+
+/// Implements the operators and methods shared by all the vector types
+macro_rules! impl_vector {
+    ($name:ident, $($field:ident),+) => {
+        impl $name {
+            /// Create a new vector from its components
+            pub fn new($($field: f32),+) -> $name {
+                $name { $($field),+ }
+            }
+
+            /// The dot product of the two vectors
+            pub fn dot(self, other: $name) -> f32 {
+                0f32 $(+ self.$field * other.$field)+
+            }
+
+            /// The squared length of the vector
+            pub fn length_squared(self) -> f32 {
+                self.dot(self)
+            }
+
+            /// The length of the vector
+            pub fn length(self) -> f32 {
+                self.length_squared().sqrt()
+            }
+
+            /// The distance between the two vectors
+            pub fn distance(self, other: $name) -> f32 {
+                (other - self).length()
+            }
+
+            /// The vector with a length of 1, zero length vectors are returned unchanged
+            pub fn normalize(self) -> $name {
+                let len = self.length();
+                if len > 0f32 {
+                    self / len
+                } else {
+                    self
+                }
+            }
+
+            /// Linearly interpolate towards other by t
+            pub fn lerp(self, other: $name, t: f32) -> $name {
+                self + (other - self) * t
+            }
+        }
+
+        impl Add for $name {
+            type Output = $name;
+
+            fn add(self, other: $name) -> $name {
+                $name { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl Sub for $name {
+            type Output = $name;
+
+            fn sub(self, other: $name) -> $name {
+                $name { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl Mul for $name {
+            type Output = $name;
+
+            fn mul(self, other: $name) -> $name {
+                $name { $($field: self.$field * other.$field),+ }
+            }
+        }
+
+        impl Mul<f32> for $name {
+            type Output = $name;
+
+            fn mul(self, s: f32) -> $name {
+                $name { $($field: self.$field * s),+ }
+            }
+        }
+
+        impl Mul<$name> for f32 {
+            type Output = $name;
+
+            fn mul(self, v: $name) -> $name {
+                v * self
+            }
+        }
+
+        impl Div<f32> for $name {
+            type Output = $name;
+
+            fn div(self, s: f32) -> $name {
+                $name { $($field: self.$field / s),+ }
+            }
+        }
+
+        impl Neg for $name {
+            type Output = $name;
+
+            fn neg(self) -> $name {
+                $name { $($field: -self.$field),+ }
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, other: $name) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, other: $name) {
+                *self = *self - other;
+            }
+        }
+
+        impl MulAssign<f32> for $name {
+            fn mul_assign(&mut self, s: f32) {
+                *self = *self * s;
+            }
+        }
+    };
+}
+
+impl_vector!(Vec2, x, y);
+impl_vector!(Vec3, x, y, z);
+impl_vector!(Vec4, x, y, z, w);
+
+impl Vec3 {
+    /// The cross product of the two vectors
+    pub fn cross(self, other: Vec3) -> Vec3 {
+        Vec3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    /// Extend the vector with a w component
+    pub fn extend(self, w: f32) -> Vec4 {
+        Vec4::new(self.x, self.y, self.z, w)
+    }
+}
+
+impl Vec4 {
+    /// The x, y and z components
+    pub fn truncate(self) -> Vec3 {
+        Vec3::new(self.x, self.y, self.z)
+    }
+}
+
+impl From<(f32, f32)> for Vec2 {
+    fn from(v: (f32, f32)) -> Vec2 {
+        Vec2::new(v.0, v.1)
+    }
+}
+
+impl From<Vec2> for (f32, f32) {
+    fn from(v: Vec2) -> (f32, f32) {
+        (v.x, v.y)
+    }
+}
+
+impl From<[f32; 2]> for Vec2 {
+    fn from(v: [f32; 2]) -> Vec2 {
+        Vec2::new(v[0], v[1])
+    }
+}
+
+impl From<Vec2> for [f32; 2] {
+    fn from(v: Vec2) -> [f32; 2] {
+        [v.x, v.y]
+    }
+}
+
+impl From<Vector3> for Vec3 {
+    fn from(v: Vector3) -> Vec3 {
+        Vec3::new(v.0, v.1, v.2)
+    }
+}
+
+impl From<Vec3> for Vector3 {
+    fn from(v: Vec3) -> Vector3 {
+        (v.x, v.y, v.z)
+    }
+}
+
+impl From<[f32; 3]> for Vec3 {
+    fn from(v: [f32; 3]) -> Vec3 {
+        Vec3::new(v[0], v[1], v[2])
+    }
+}
+
+impl From<Vec3> for [f32; 3] {
+    fn from(v: Vec3) -> [f32; 3] {
+        [v.x, v.y, v.z]
+    }
+}
+
+impl From<(f32, f32, f32, f32)> for Vec4 {
+    fn from(v: (f32, f32, f32, f32)) -> Vec4 {
+        Vec4::new(v.0, v.1, v.2, v.3)
+    }
+}
+
+impl From<Vec4> for (f32, f32, f32, f32) {
+    fn from(v: Vec4) -> (f32, f32, f32, f32) {
+        (v.x, v.y, v.z, v.w)
+    }
+}
+
+impl From<[f32; 4]> for Vec4 {
+    fn from(v: [f32; 4]) -> Vec4 {
+        Vec4::new(v[0], v[1], v[2], v[3])
+    }
+}
+
+impl From<Vec4> for [f32; 4] {
+    fn from(v: Vec4) -> [f32; 4] {
+        [v.x, v.y, v.z, v.w]
+    }
+}
+
+/// A quaternion representing a rotation
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct Quat {
+    /// The x component
+    pub x: f32,
+    /// The y component
+    pub y: f32,
+    /// The z component
+    pub z: f32,
+    /// The w component
+    pub w: f32,
+}
+
+impl Default for Quat {
+    fn default() -> Self {
+        Quat::identity()
+    }
+}
+
+impl Quat {
+    /// Create a new quaternion from its components
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Quat {
+        Quat { x, y, z, w }
+    }
+
+    /// The quaternion with no rotation
+    pub fn identity() -> Quat {
+        Quat::new(0f32, 0f32, 0f32, 1f32)
+    }
+
+    /// The rotation of angle radians around the axis
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Quat {
+        let axis = axis.normalize();
+        let (s, c) = (angle / 2f32).sin_cos();
+        Quat::new(axis.x * s, axis.y * s, axis.z * s, c)
+    }
+
+    /// The axis and angle in radians of the rotation
+    pub fn to_axis_angle(self) -> (Vec3, f32) {
+        let q = self.normalize();
+        let s = (1f32 - q.w * q.w).max(0f32).sqrt();
+        let angle = 2f32 * q.w.clamp(-1f32, 1f32).acos();
+
+        if s < 0.000_001f32 {
+            (Vec3::new(1f32, 0f32, 0f32), angle)
+        } else {
+            (Vec3::new(q.x / s, q.y / s, q.z / s), angle)
+        }
+    }
+
+    /// The rotation from euler angles, matching `utils::to_quaternion`
+    pub fn from_euler(angle: Vector3) -> Quat {
+        to_quaternion(angle).into()
+    }
+
+    /// The euler angles of the rotation, matching `utils::to_euler`
+    pub fn to_euler(self) -> Vector3 {
+        to_euler(self.into())
+    }
+
+    /// The rotation that points -z along forward with y as close to up as possible
+    pub fn look_rotation(forward: Vec3, up: Vec3) -> Quat {
+        let z = (-forward).normalize();
+        let x = up.cross(z).normalize();
+        let y = z.cross(x);
+
+        Quat::from_axes(x, y, z)
+    }
+
+    /// The rotation that maps the unit axes onto the orthonormal x, y and z
+    pub fn from_axes(x: Vec3, y: Vec3, z: Vec3) -> Quat {
+        let trace = x.x + y.y + z.z;
+
+        let q = if trace > 0f32 {
+            let s = (trace + 1f32).sqrt() * 2f32;
+            Quat::new(
+                (y.z - z.y) / s,
+                (z.x - x.z) / s,
+                (x.y - y.x) / s,
+                0.25f32 * s,
+            )
+        } else if x.x > y.y && x.x > z.z {
+            let s = (1f32 + x.x - y.y - z.z).sqrt() * 2f32;
+            Quat::new(
+                0.25f32 * s,
+                (y.x + x.y) / s,
+                (z.x + x.z) / s,
+                (y.z - z.y) / s,
+            )
+        } else if y.y > z.z {
+            let s = (1f32 + y.y - x.x - z.z).sqrt() * 2f32;
+            Quat::new(
+                (y.x + x.y) / s,
+                0.25f32 * s,
+                (z.y + y.z) / s,
+                (z.x - x.z) / s,
+            )
+        } else {
+            let s = (1f32 + z.z - x.x - y.y).sqrt() * 2f32;
+            Quat::new(
+                (z.x + x.z) / s,
+                (z.y + y.z) / s,
+                0.25f32 * s,
+                (x.y - y.x) / s,
+            )
+        };
+
+        q.normalize()
+    }
+
+    /// The dot product of the two quaternions
+    pub fn dot(self, other: Quat) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    /// The length of the quaternion
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// The quaternion with a length of 1, zero length quaternions become the identity
+    pub fn normalize(self) -> Quat {
+        let len = self.length();
+        if len > 0f32 {
+            Quat::new(self.x / len, self.y / len, self.z / len, self.w / len)
+        } else {
+            Quat::identity()
+        }
+    }
+
+    /// The conjugate of the quaternion, the inverse for unit quaternions
+    pub fn conjugate(self) -> Quat {
+        Quat::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    /// The inverse of the quaternion
+    pub fn inverse(self) -> Quat {
+        let len_sq = self.dot(self);
+        if len_sq > 0f32 {
+            let c = self.conjugate();
+            Quat::new(c.x / len_sq, c.y / len_sq, c.z / len_sq, c.w / len_sq)
+        } else {
+            Quat::identity()
+        }
+    }
+
+    /// Spherically interpolate towards other by t, taking the shortest path
+    pub fn slerp(self, other: Quat, t: f32) -> Quat {
+        let mut other = other;
+        let mut cos_theta = self.dot(other);
+        if cos_theta < 0f32 {
+            other = Quat::new(-other.x, -other.y, -other.z, -other.w);
+            cos_theta = -cos_theta;
+        }
+
+        let (a, b) = if cos_theta > SLERP_THRESHOLD {
+            // too close together to slerp accurately
+            (1f32 - t, t)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            (
+                ((1f32 - t) * theta).sin() / sin_theta,
+                (t * theta).sin() / sin_theta,
+            )
+        };
+
+        Quat::new(
+            self.x * a + other.x * b,
+            self.y * a + other.y * b,
+            self.z * a + other.z * b,
+            self.w * a + other.w * b,
+        )
+        .normalize()
+    }
+
+    /// Rotate the vector by the quaternion
+    pub fn rotate(self, v: Vec3) -> Vec3 {
+        let q = Vec3::new(self.x, self.y, self.z);
+        let t = q.cross(v) * 2f32;
+        v + t * self.w + q.cross(t)
+    }
+}
+
+impl Mul for Quat {
+    type Output = Quat;
+
+    /// Combine the rotations, other is applied first
+    fn mul(self, other: Quat) -> Quat {
+        Quat::new(
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+        )
+    }
+}
+
+impl Mul<Vec3> for Quat {
+    type Output = Vec3;
+
+    fn mul(self, v: Vec3) -> Vec3 {
+        self.rotate(v)
+    }
+}
+
+impl From<Quaternion> for Quat {
+    fn from(q: Quaternion) -> Quat {
+        Quat::new(q.0, q.1, q.2, q.3)
+    }
+}
+
+impl From<Quat> for Quaternion {
+    fn from(q: Quat) -> Quaternion {
+        (q.x, q.y, q.z, q.w)
+    }
+}
+
+/// A column major 4x4 matrix laid out the same as `types::Matrix4`
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct Mat4 {
+    /// The columns of the matrix
+    pub cols: [[f32; 4]; 4],
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Mat4::identity()
+    }
+}
+
+impl Mat4 {
+    /// The identity matrix
+    pub fn identity() -> Mat4 {
+        Mat4 {
+            cols: [
+                [1f32, 0f32, 0f32, 0f32],
+                [0f32, 1f32, 0f32, 0f32],
+                [0f32, 0f32, 1f32, 0f32],
+                [0f32, 0f32, 0f32, 1f32],
+            ],
+        }
+    }
+
+    /// The matrix that translates by t
+    pub fn from_translation(t: Vec3) -> Mat4 {
+        let mut m = Mat4::identity();
+        m.cols[3] = [t.x, t.y, t.z, 1f32];
+        m
+    }
+
+    /// The matrix that scales by s
+    pub fn from_scale(s: Vec3) -> Mat4 {
+        let mut m = Mat4::identity();
+        m.cols[0][0] = s.x;
+        m.cols[1][1] = s.y;
+        m.cols[2][2] = s.z;
+        m
+    }
+
+    /// The matrix that rotates by q
+    pub fn from_quat(q: Quat) -> Mat4 {
+        let x = q.rotate(Vec3::new(1f32, 0f32, 0f32));
+        let y = q.rotate(Vec3::new(0f32, 1f32, 0f32));
+        let z = q.rotate(Vec3::new(0f32, 0f32, 1f32));
+
+        Mat4 {
+            cols: [
+                [x.x, x.y, x.z, 0f32],
+                [y.x, y.y, y.z, 0f32],
+                [z.x, z.y, z.z, 0f32],
+                [0f32, 0f32, 0f32, 1f32],
+            ],
+        }
+    }
+
+    /// The matrix that scales, then rotates, then translates
+    pub fn from_trs(translation: Vec3, rotation: Quat, scale: Vec3) -> Mat4 {
+        Mat4::from_translation(translation) * Mat4::from_quat(rotation) * Mat4::from_scale(scale)
+    }
+
+    /// The view matrix for a camera at eye looking at target
+    pub fn look_at(eye: Vec3, target: Vec3, up: Vec3) -> Mat4 {
+        let z = (eye - target).normalize();
+        let x = up.cross(z).normalize();
+        let y = z.cross(x);
+
+        Mat4 {
+            cols: [
+                [x.x, y.x, z.x, 0f32],
+                [x.y, y.y, z.y, 0f32],
+                [x.z, y.z, z.z, 0f32],
+                [-x.dot(eye), -y.dot(eye), -z.dot(eye), 1f32],
+            ],
+        }
+    }
+
+    /// The perspective projection for the vertical field of view in radians
+    pub fn perspective(fov_y: f32, aspect: f32, znear: f32, zfar: f32) -> Mat4 {
+        let f = 1f32 / (fov_y / 2f32).tan();
+
+        Mat4 {
+            cols: [
+                [f / aspect, 0f32, 0f32, 0f32],
+                [0f32, f, 0f32, 0f32],
+                [0f32, 0f32, (zfar + znear) / (znear - zfar), -1f32],
+                [0f32, 0f32, (2f32 * zfar * znear) / (znear - zfar), 0f32],
+            ],
+        }
+    }
+
+    /// The element at the row and column
+    pub fn get(&self, row: usize, col: usize) -> f32 {
+        self.cols[col][row]
+    }
+
+    /// The transpose of the matrix
+    pub fn transpose(&self) -> Mat4 {
+        let mut m = [[0f32; 4]; 4];
+        for (c, col) in m.iter_mut().enumerate() {
+            for (r, item) in col.iter_mut().enumerate() {
+                *item = self.cols[r][c];
+            }
+        }
+        Mat4 { cols: m }
+    }
+
+    /// The determinant of the matrix
+    pub fn determinant(&self) -> f32 {
+        let (inv, m) = self.cofactors();
+        m[0] * inv[0] + m[1] * inv[4] + m[2] * inv[8] + m[3] * inv[12]
+    }
+
+    /// The inverse of the matrix, None if it can not be inverted
+    pub fn inverse(&self) -> Option<Mat4> {
+        let (inv, m) = self.cofactors();
+        let det = m[0] * inv[0] + m[1] * inv[4] + m[2] * inv[8] + m[3] * inv[12];
+        if det.abs() < f32::MIN_POSITIVE {
+            return None;
+        }
+
+        let mut cols = [[0f32; 4]; 4];
+        for (i, v) in inv.iter().enumerate() {
+            cols[i / 4][i % 4] = v / det;
+        }
+        Some(Mat4 { cols })
+    }
+
+    /// Transform the point, including the translation and perspective divide
+    pub fn transform_point(&self, p: Vec3) -> Vec3 {
+        let v = *self * p.extend(1f32);
+        if v.w != 0f32 && v.w != 1f32 {
+            v.truncate() / v.w
+        } else {
+            v.truncate()
+        }
+    }
+
+    /// Transform the direction, ignoring the translation
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        (*self * v.extend(0f32)).truncate()
+    }
+
+    /// The flattened elements and adjugate used for the determinant and inverse
+    fn cofactors(&self) -> ([f32; 16], [f32; 16]) {
+        let mut m = [0f32; 16];
+        for (i, v) in m.iter_mut().enumerate() {
+            *v = self.cols[i / 4][i % 4];
+        }
+
+        let mut inv = [0f32; 16];
+        inv[0] = m[5] * m[10] * m[15] - m[5] * m[11] * m[14] - m[9] * m[6] * m[15]
+            + m[9] * m[7] * m[14]
+            + m[13] * m[6] * m[11]
+            - m[13] * m[7] * m[10];
+        inv[4] = -m[4] * m[10] * m[15] + m[4] * m[11] * m[14] + m[8] * m[6] * m[15]
+            - m[8] * m[7] * m[14]
+            - m[12] * m[6] * m[11]
+            + m[12] * m[7] * m[10];
+        inv[8] = m[4] * m[9] * m[15] - m[4] * m[11] * m[13] - m[8] * m[5] * m[15]
+            + m[8] * m[7] * m[13]
+            + m[12] * m[5] * m[11]
+            - m[12] * m[7] * m[9];
+        inv[12] = -m[4] * m[9] * m[14] + m[4] * m[10] * m[13] + m[8] * m[5] * m[14]
+            - m[8] * m[6] * m[13]
+            - m[12] * m[5] * m[10]
+            + m[12] * m[6] * m[9];
+        inv[1] = -m[1] * m[10] * m[15] + m[1] * m[11] * m[14] + m[9] * m[2] * m[15]
+            - m[9] * m[3] * m[14]
+            - m[13] * m[2] * m[11]
+            + m[13] * m[3] * m[10];
+        inv[5] = m[0] * m[10] * m[15] - m[0] * m[11] * m[14] - m[8] * m[2] * m[15]
+            + m[8] * m[3] * m[14]
+            + m[12] * m[2] * m[11]
+            - m[12] * m[3] * m[10];
+        inv[9] = -m[0] * m[9] * m[15] + m[0] * m[11] * m[13] + m[8] * m[1] * m[15]
+            - m[8] * m[3] * m[13]
+            - m[12] * m[1] * m[11]
+            + m[12] * m[3] * m[9];
+        inv[13] = m[0] * m[9] * m[14] - m[0] * m[10] * m[13] - m[8] * m[1] * m[14]
+            + m[8] * m[2] * m[13]
+            + m[12] * m[1] * m[10]
+            - m[12] * m[2] * m[9];
+        inv[2] = m[1] * m[6] * m[15] - m[1] * m[7] * m[14] - m[5] * m[2] * m[15]
+            + m[5] * m[3] * m[14]
+            + m[13] * m[2] * m[7]
+            - m[13] * m[3] * m[6];
+        inv[6] = -m[0] * m[6] * m[15] + m[0] * m[7] * m[14] + m[4] * m[2] * m[15]
+            - m[4] * m[3] * m[14]
+            - m[12] * m[2] * m[7]
+            + m[12] * m[3] * m[6];
+        inv[10] = m[0] * m[5] * m[15] - m[0] * m[7] * m[13] - m[4] * m[1] * m[15]
+            + m[4] * m[3] * m[13]
+            + m[12] * m[1] * m[7]
+            - m[12] * m[3] * m[5];
+        inv[14] = -m[0] * m[5] * m[14] + m[0] * m[6] * m[13] + m[4] * m[1] * m[14]
+            - m[4] * m[2] * m[13]
+            - m[12] * m[1] * m[6]
+            + m[12] * m[2] * m[5];
+        inv[3] = -m[1] * m[6] * m[11] + m[1] * m[7] * m[10] + m[5] * m[2] * m[11]
+            - m[5] * m[3] * m[10]
+            - m[9] * m[2] * m[7]
+            + m[9] * m[3] * m[6];
+        inv[7] = m[0] * m[6] * m[11] - m[0] * m[7] * m[10] - m[4] * m[2] * m[11]
+            + m[4] * m[3] * m[10]
+            + m[8] * m[2] * m[7]
+            - m[8] * m[3] * m[6];
+        inv[11] = -m[0] * m[5] * m[11] + m[0] * m[7] * m[9] + m[4] * m[1] * m[11]
+            - m[4] * m[3] * m[9]
+            - m[8] * m[1] * m[7]
+            + m[8] * m[3] * m[5];
+        inv[15] = m[0] * m[5] * m[10] - m[0] * m[6] * m[9] - m[4] * m[1] * m[10]
+            + m[4] * m[2] * m[9]
+            + m[8] * m[1] * m[6]
+            - m[8] * m[2] * m[5];
+
+        (inv, m)
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, other: Mat4) -> Mat4 {
+        let mut cols = [[0f32; 4]; 4];
+        for (c, col) in cols.iter_mut().enumerate() {
+            for (r, item) in col.iter_mut().enumerate() {
+                *item = (0..4).map(|k| self.cols[k][r] * other.cols[c][k]).sum();
+            }
+        }
+        Mat4 { cols }
+    }
+}
+
+impl Mul<Vec4> for Mat4 {
+    type Output = Vec4;
+
+    fn mul(self, v: Vec4) -> Vec4 {
+        let c = &self.cols;
+        Vec4::new(
+            c[0][0] * v.x + c[1][0] * v.y + c[2][0] * v.z + c[3][0] * v.w,
+            c[0][1] * v.x + c[1][1] * v.y + c[2][1] * v.z + c[3][1] * v.w,
+            c[0][2] * v.x + c[1][2] * v.y + c[2][2] * v.z + c[3][2] * v.w,
+            c[0][3] * v.x + c[1][3] * v.y + c[2][3] * v.z + c[3][3] * v.w,
+        )
+    }
+}
+
+impl From<Matrix4> for Mat4 {
+    fn from(m: Matrix4) -> Mat4 {
+        Mat4 { cols: m }
+    }
+}
+
+impl From<Mat4> for Matrix4 {
+    fn from(m: Mat4) -> Matrix4 {
+        m.cols
+    }
+}
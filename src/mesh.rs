@@ -1,4 +1,5 @@
 use noise::{NoiseFn, Perlin, Seedable};
+use std::collections::HashMap;
 use std::f32::consts::PI;
 use types::Vertex;
 use utils::calc_normal;
@@ -391,3 +392,77 @@ pub fn gen_proc_mesh(
 
     vertices
 }
+
+/// Simplify a mesh by clustering its vertices onto a grid with cells divisions
+/// along the longest side of its bounds, triangles that collapse are removed,
+/// used for generating lower detail meshes
+pub fn simplify(vertices: &[Vertex], cells: u32) -> Vec<Vertex> {
+    if vertices.is_empty() || cells == 0 {
+        return vertices.to_vec();
+    }
+
+    // the bounds of the mesh
+    let mut min = vertices[0].position;
+    let mut max = vertices[0].position;
+    for v in vertices {
+        for i in 0..3 {
+            min[i] = min[i].min(v.position[i]);
+            max[i] = max[i].max(v.position[i]);
+        }
+    }
+    let size = (0..3).map(|i| max[i] - min[i]).fold(0f32, f32::max);
+    if size <= 0f32 {
+        return vertices.to_vec();
+    }
+    let cell_size = size / cells as f32;
+    let cell = |p: [f32; 3]| {
+        let axis = |i: usize| (((p[i] - min[i]) / cell_size) as u32).min(cells - 1);
+        (axis(0), axis(1), axis(2))
+    };
+
+    // the average position and uv of the vertices in each cell
+    let mut clusters = HashMap::new();
+    for v in vertices {
+        let cluster = clusters
+            .entry(cell(v.position))
+            .or_insert(([0f32; 3], [0f32; 2], 0f32));
+        for i in 0..3 {
+            cluster.0[i] += v.position[i];
+        }
+        for i in 0..2 {
+            cluster.1[i] += v.texture[i];
+        }
+        cluster.2 += 1f32;
+    }
+    let average = |c| {
+        let (pos, uv, count): ([f32; 3], [f32; 2], f32) = clusters[&c];
+        (
+            [pos[0] / count, pos[1] / count, pos[2] / count],
+            [uv[0] / count, uv[1] / count],
+        )
+    };
+
+    let mut simplified = Vec::new();
+    for tri in vertices.chunks(3).filter(|t| t.len() == 3) {
+        let c = [
+            cell(tri[0].position),
+            cell(tri[1].position),
+            cell(tri[2].position),
+        ];
+        if c[0] == c[1] || c[1] == c[2] || c[0] == c[2] {
+            continue;
+        }
+
+        let verts = [average(c[0]), average(c[1]), average(c[2])];
+        let normal = calc_normal(verts[0].0, verts[1].0, verts[2].0);
+        for (position, texture) in verts.iter() {
+            simplified.push(Vertex {
+                position: *position,
+                normal,
+                texture: *texture,
+            });
+        }
+    }
+
+    simplified
+}
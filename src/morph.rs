@@ -0,0 +1,228 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use types::Vertex;
+
+/// The width of the texture the morph deltas are passed to the shader in
+pub const MORPH_TEX_WIDTH: usize = 1024;
+
+/// The offset of a vertex's position and normal for a morph target
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct MorphDelta {
+    /// The offset added to the position
+    pub position: [f32; 3],
+    /// The offset added to the normal
+    pub normal: [f32; 3],
+}
+
+/// A named shape the mesh can be blended towards, such as a smile or blink
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct MorphTarget {
+    /// The name of the target
+    #[builder(setter(into))]
+    pub name: String,
+    /// The offset of each of the render item's vertices
+    pub deltas: Vec<MorphDelta>,
+}
+
+impl Default for MorphTarget {
+    fn default() -> Self {
+        MorphTarget {
+            name: "morph".into(),
+            deltas: Vec::new(),
+        }
+    }
+}
+
+impl MorphTarget {
+    /// Create a target from the difference between the base mesh and the
+    /// mesh in the target shape, which must have the same number of vertices
+    pub fn from_vertices(
+        name: &str,
+        base: &[Vertex],
+        target: &[Vertex],
+    ) -> Result<MorphTarget, String> {
+        if base.len() != target.len() {
+            return Err(format!(
+                "Morph target {} has {} vertices but the base has {}",
+                name,
+                target.len(),
+                base.len()
+            ));
+        }
+
+        let deltas = base
+            .iter()
+            .zip(target.iter())
+            .map(|(b, t)| MorphDelta {
+                position: [
+                    t.position[0] - b.position[0],
+                    t.position[1] - b.position[1],
+                    t.position[2] - b.position[2],
+                ],
+                normal: [
+                    t.normal[0] - b.normal[0],
+                    t.normal[1] - b.normal[1],
+                    t.normal[2] - b.normal[2],
+                ],
+            })
+            .collect();
+
+        Ok(MorphTarget {
+            name: name.into(),
+            deltas,
+        })
+    }
+}
+
+/// The id given to the next morphs
+static NEXT_MORPHS_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Get a new morphs id
+fn next_morphs_id() -> usize {
+    NEXT_MORPHS_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// The morph targets of a render item and the weight of each per instance,
+/// blended in the vertex shader
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default, build_fn(validate = "Self::validate"))]
+pub struct Morphs {
+    /// The targets the mesh can be blended towards
+    targets: Vec<MorphTarget>,
+    /// The weight of each target for each instance, missing weights are 0
+    pub weights: Vec<Vec<f32>>,
+    /// Identifies the targets so the renderer can keep their deltas on the
+    /// GPU, shared by clones as the targets can't change
+    #[builder(setter(skip))]
+    #[serde(skip, default = "next_morphs_id")]
+    id: usize,
+}
+
+impl Default for Morphs {
+    fn default() -> Self {
+        Morphs {
+            targets: Vec::new(),
+            weights: Vec::new(),
+            id: next_morphs_id(),
+        }
+    }
+}
+
+impl MorphsBuilder {
+    /// Check every target has a delta for the same number of vertices
+    fn validate(&self) -> Result<(), String> {
+        let targets = self.targets.as_deref().unwrap_or_default();
+        match targets
+            .iter()
+            .find(|t| t.deltas.len() != targets[0].deltas.len())
+        {
+            Some(t) => Err(format!(
+                "Morph target {} has {} deltas but {} has {}",
+                t.name,
+                t.deltas.len(),
+                targets[0].name,
+                targets[0].deltas.len()
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Morphs {
+    /// The targets the mesh can be blended towards
+    pub fn targets(&self) -> &[MorphTarget] {
+        &self.targets
+    }
+
+    /// The id of the targets, used by the renderer to cache their deltas
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// The index of the target with the name
+    pub fn target_index(&self, name: &str) -> Option<usize> {
+        self.targets.iter().position(|t| t.name == name)
+    }
+
+    /// The weight of a target for an instance
+    pub fn weight(&self, instance: usize, target: usize) -> f32 {
+        self.weights
+            .get(instance)
+            .and_then(|w| w.get(target))
+            .cloned()
+            .unwrap_or(0f32)
+    }
+
+    /// Set the weight of a target for an instance, returning false if there
+    /// is no target at the index
+    pub fn set_weight(&mut self, instance: usize, target: usize, weight: f32) -> bool {
+        if target >= self.targets.len() {
+            return false;
+        }
+
+        if self.weights.len() <= instance {
+            self.weights.resize(instance + 1, Vec::new());
+        }
+        let weights = &mut self.weights[instance];
+        if weights.len() < self.targets.len() {
+            weights.resize(self.targets.len(), 0f32);
+        }
+        weights[target] = weight;
+        true
+    }
+
+    /// Set the weight of the target with the name for an instance
+    pub fn set_weight_by_name(&mut self, instance: usize, name: &str, weight: f32) -> bool {
+        match self.target_index(name) {
+            Some(target) => self.set_weight(instance, target, weight),
+            None => false,
+        }
+    }
+
+    /// The weight of every target for an instance
+    pub fn instance_weights(&self, instance: usize) -> Vec<f32> {
+        (0..self.targets.len())
+            .map(|t| self.weight(instance, t))
+            .collect()
+    }
+
+    /// Whether every target has a delta for each of the vertices
+    pub fn matches(&self, vertex_count: usize) -> bool {
+        self.targets.iter().all(|t| t.deltas.len() == vertex_count)
+    }
+
+    /// The vertices of an instance with its weights applied, the same as the
+    /// shader computes
+    pub fn apply(&self, vertices: &[Vertex], instance: usize) -> Vec<Vertex> {
+        let mut vertices = vertices.to_vec();
+        for (t, target) in self.targets.iter().enumerate() {
+            let weight = self.weight(instance, t);
+            if weight == 0f32 {
+                continue;
+            }
+            for (v, d) in vertices.iter_mut().zip(target.deltas.iter()) {
+                for i in 0..3 {
+                    v.position[i] += d.position[i] * weight;
+                    v.normal[i] += d.normal[i] * weight;
+                }
+            }
+        }
+        vertices
+    }
+
+    /// The deltas laid out for the morph texture, each vertex of each target
+    /// is a position texel followed by a normal texel
+    pub fn delta_texels(&self) -> Vec<(f32, f32, f32, f32)> {
+        self.targets
+            .iter()
+            .flat_map(|t| t.deltas.iter())
+            .flat_map(|d| {
+                vec![
+                    (d.position[0], d.position[1], d.position[2], 0f32),
+                    (d.normal[0], d.normal[1], d.normal[2], 0f32),
+                ]
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,223 @@
+use rayon::prelude::*;
+
+use types::Vector3;
+
+/// How the particles of an emitter are blended with the scene
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum ParticleBlend {
+    /// Particles are alpha blended, sorted back to front
+    Alpha,
+    /// Particles are added to the colour behind them, useful for sparks and fire
+    Additive,
+}
+
+/// A single simulated particle
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct Particle {
+    /// The position of the particle in world space
+    pub pos: Vector3,
+    /// The velocity of the particle
+    pub velocity: Vector3,
+    /// How long the particle has been alive in seconds
+    pub age: f32,
+    /// How long the particle lives for in seconds
+    pub lifetime: f32,
+}
+
+impl Particle {
+    /// The progress of the particle through its life from 0 to 1
+    pub fn life(&self) -> f32 {
+        (self.age / self.lifetime).min(1f32)
+    }
+}
+
+/// struct for defining an emitter that spawns and simulates particles
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq)]
+#[builder(default)]
+pub struct ParticleEmitter {
+    /// The name of the emitter for lookup
+    #[builder(setter(into))]
+    pub name: String,
+    /// The position particles are spawned at
+    pub pos: Vector3,
+    /// The number of particles spawned per second
+    pub spawn_rate: f32,
+    /// The maximum number of particles alive at once
+    pub max_particles: usize,
+    /// How long each particle lives for in seconds
+    pub lifetime: f32,
+    /// The initial velocity of each particle
+    pub velocity: Vector3,
+    /// The random spread applied to the initial velocity on each axis
+    pub velocity_spread: Vector3,
+    /// The acceleration applied to every particle
+    pub gravity: Vector3,
+    /// The multiplier on the velocity of a particle when spawned
+    pub start_speed: f32,
+    /// The multiplier on the velocity of a particle at the end of its life
+    pub end_speed: f32,
+    /// The colour of a particle when spawned
+    pub start_color: (f32, f32, f32, f32),
+    /// The colour of a particle at the end of its life
+    pub end_color: (f32, f32, f32, f32),
+    /// The size of a particle when spawned
+    pub start_size: f32,
+    /// The size of a particle at the end of its life
+    pub end_size: f32,
+    /// The texture used for each particle, a soft circle is used if None
+    pub texture_name: Option<String>,
+    /// How the particles are blended
+    pub blend: ParticleBlend,
+    /// Whether the emitter is spawning new particles
+    pub emitting: bool,
+    /// Whether this emitter is active/should be updated and rendered
+    pub active: bool,
+    /// The seed used to randomise the particles
+    pub seed: u32,
+    /// The particles currently alive
+    #[builder(setter(skip))]
+    #[serde(skip)]
+    pub particles: Vec<Particle>,
+    /// The fraction of a particle carried over between frames
+    #[builder(setter(skip))]
+    #[serde(skip)]
+    spawn_accumulator: f32,
+}
+
+impl Default for ParticleEmitter {
+    fn default() -> Self {
+        ParticleEmitter {
+            name: "pe".into(),
+            pos: (0f32, 0f32, 0f32),
+            spawn_rate: 20f32,
+            max_particles: 1000,
+            lifetime: 2f32,
+            velocity: (0f32, 1f32, 0f32),
+            velocity_spread: (0.5f32, 0.5f32, 0.5f32),
+            gravity: (0f32, 0f32, 0f32),
+            start_speed: 1f32,
+            end_speed: 1f32,
+            start_color: (1f32, 1f32, 1f32, 1f32),
+            end_color: (1f32, 1f32, 1f32, 0f32),
+            start_size: 0.2f32,
+            end_size: 0.2f32,
+            texture_name: None,
+            blend: ParticleBlend::Alpha,
+            emitting: true,
+            active: true,
+            seed: 1,
+            particles: Vec::new(),
+            spawn_accumulator: 0f32,
+        }
+    }
+}
+
+impl ParticleEmitter {
+    /// Simulate the emitter for delta seconds, spawning and removing particles
+    pub fn update(&mut self, delta: f32) {
+        let gravity = self.gravity;
+        let (start_speed, end_speed) = (self.start_speed, self.end_speed);
+
+        // move all of the current particles
+        self.particles.par_iter_mut().for_each(|p| {
+            let speed = start_speed + (end_speed - start_speed) * p.life();
+            p.velocity.0 += gravity.0 * delta;
+            p.velocity.1 += gravity.1 * delta;
+            p.velocity.2 += gravity.2 * delta;
+            p.pos.0 += p.velocity.0 * speed * delta;
+            p.pos.1 += p.velocity.1 * speed * delta;
+            p.pos.2 += p.velocity.2 * speed * delta;
+            p.age += delta;
+        });
+
+        // remove the dead particles
+        self.particles.retain(|p| p.age < p.lifetime);
+
+        if !self.emitting {
+            self.spawn_accumulator = 0f32;
+            return;
+        }
+
+        // spawn any new particles
+        self.spawn_accumulator += self.spawn_rate * delta;
+        while self.spawn_accumulator >= 1f32 {
+            self.spawn_accumulator -= 1f32;
+            if self.particles.len() < self.max_particles {
+                let particle = self.spawn();
+                self.particles.push(particle);
+            }
+        }
+    }
+
+    /// Spawn a burst of count particles immediately
+    pub fn burst(&mut self, count: usize) {
+        for _ in 0..count {
+            if self.particles.len() >= self.max_particles {
+                break;
+            }
+            let particle = self.spawn();
+            self.particles.push(particle);
+        }
+    }
+
+    /// Get the colour of a particle at the life value
+    pub fn color_at(&self, life: f32) -> (f32, f32, f32, f32) {
+        let (s, e) = (self.start_color, self.end_color);
+        (
+            s.0 + (e.0 - s.0) * life,
+            s.1 + (e.1 - s.1) * life,
+            s.2 + (e.2 - s.2) * life,
+            s.3 + (e.3 - s.3) * life,
+        )
+    }
+
+    /// Get the size of a particle at the life value
+    pub fn size_at(&self, life: f32) -> f32 {
+        self.start_size + (self.end_size - self.start_size) * life
+    }
+
+    /// Get the multiplier on the velocity of a particle at the life value
+    pub fn speed_at(&self, life: f32) -> f32 {
+        self.start_speed + (self.end_speed - self.start_speed) * life
+    }
+
+    /// Create a new particle at the emitter position
+    fn spawn(&mut self) -> Particle {
+        let spread = self.velocity_spread;
+        let velocity = (
+            self.velocity.0 + spread.0 * self.next_random(),
+            self.velocity.1 + spread.1 * self.next_random(),
+            self.velocity.2 + spread.2 * self.next_random(),
+        );
+
+        Particle {
+            pos: self.pos,
+            velocity,
+            age: 0f32,
+            lifetime: self.lifetime,
+        }
+    }
+
+    /// Returns a pseudo random number between -1 and 1 using xorshift
+    fn next_random(&mut self) -> f32 {
+        let mut x = self.seed.max(1);
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.seed = x;
+
+        (x as f32 / u32::MAX as f32) * 2f32 - 1f32
+    }
+}
+
+/// struct for the per particle attributes passed to the particle shader
+#[derive(Copy, Clone)]
+pub struct ParticleIn {
+    /// The world position of the particle
+    pub particle_position: Vector3,
+    /// The colour of the particle
+    pub particle_color: (f32, f32, f32, f32),
+    /// The size of the particle
+    pub particle_size: f32,
+}
+implement_vertex!(ParticleIn, particle_position, particle_color, particle_size);
@@ -9,9 +9,13 @@ use std::thread;
 /// The base path the persistent items will be placed
 const PERSIST_BASE_PATH: &str = "./persist/";
 
+/// The version of the persisted format, written before each saved item and
+/// increased whenever the layout of a persisted type changes
+pub const PERSIST_VERSION: u32 = 1;
+
 /// Save and encodable type to persistence at the key
 pub fn save<T: Serialize>(to_save: &T, key: &'static str) {
-    let encoded: Vec<u8> = serialize(to_save).unwrap();
+    let encoded: Vec<u8> = serialize(&(PERSIST_VERSION, to_save)).unwrap();
 
     let _ = thread::spawn(move || {
         // TODO handle this better
@@ -29,7 +33,8 @@ pub fn save<T: Serialize>(to_save: &T, key: &'static str) {
     .join();
 }
 
-/// Load a decodable type from persistence using the key
+/// Load a decodable type from persistence using the key, failing if it was
+/// saved with a different version of the format or can't be decoded
 pub fn load<T: for<'a> Deserialize<'a>>(key: &'static str) -> Result<T, String> {
     let mut f = match File::open(format!("{}{}", PERSIST_BASE_PATH, key)) {
         Ok(f) => f,
@@ -39,7 +44,15 @@ pub fn load<T: for<'a> Deserialize<'a>>(key: &'static str) -> Result<T, String>
     let mut byte_vec = Vec::new();
     let _ = f.read_to_end(&mut byte_vec);
 
-    let decoded: T = deserialize(&byte_vec[..]).unwrap();
+    let version: u32 = deserialize(&byte_vec[..]).map_err(|e| format!("{}", e))?;
+    if version != PERSIST_VERSION {
+        return Err(format!(
+            "{} was saved with persist version {} but version {} is expected",
+            key, version, PERSIST_VERSION
+        ));
+    }
+
+    let (_, decoded): (u32, T) = deserialize(&byte_vec[..]).map_err(|e| format!("{}", e))?;
 
     Ok(decoded)
 }
@@ -63,4 +76,17 @@ fn save_load_test() {
 
     save(&world, "test");
     let _ = load::<Entity>("test");
+    assert!(load::<World>("test").unwrap() == world);
+
+    // items saved with another version or that can't be decoded fail to load
+    let old = serialize(&(PERSIST_VERSION + 1, &world)).unwrap();
+    File::create(format!("{}{}", PERSIST_BASE_PATH, "test_old"))
+        .and_then(|mut f| f.write_all(&old))
+        .unwrap();
+    assert!(load::<World>("test_old").is_err());
+
+    File::create(format!("{}{}", PERSIST_BASE_PATH, "test_short"))
+        .and_then(|mut f| f.write_all(&[1u8]))
+        .unwrap();
+    assert!(load::<World>("test_short").is_err());
 }
@@ -0,0 +1,297 @@
+use std::f32::consts::PI;
+
+use types::Vector3;
+use utils::crossp;
+
+/// The number of segments used to draw each circle of a debug sphere
+const SPHERE_SEGMENTS: usize = 16;
+
+/// struct for the vertices sent to the debug shader
+#[derive(Copy, Clone)]
+pub struct DebugVertex {
+    /// The position of the vertex
+    pub position: [f32; 3],
+    /// The colour of the vertex
+    pub color: [f32; 4],
+}
+implement_vertex!(DebugVertex, position, color);
+
+/// A single line to be drawn by the debug renderer
+#[derive(Clone, PartialEq)]
+struct DebugLine {
+    /// The start point of the line
+    start: Vector3,
+    /// The end point of the line
+    end: Vector3,
+    /// The colour of the line
+    color: (f32, f32, f32, f32),
+    /// The remaining time the line will be drawn for
+    duration: f32,
+    /// Whether the line is drawn over the scene
+    on_top: bool,
+}
+
+/// Immediate mode debug drawing, primitives are added each frame and
+/// drawn after the scene
+pub struct DebugDraw {
+    /// Whether the debug primitives are drawn
+    pub enabled: bool,
+    /// Whether primitives added are drawn over the scene rather than depth tested
+    pub on_top: bool,
+    /// All of the lines currently being drawn
+    lines: Vec<DebugLine>,
+}
+
+impl Default for DebugDraw {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugDraw {
+    /// Create a new debug draw instance
+    pub fn new() -> DebugDraw {
+        DebugDraw {
+            enabled: true,
+            on_top: false,
+            lines: Vec::new(),
+        }
+    }
+
+    /// Draw a line from start to end, for the duration in seconds or a single frame if None
+    pub fn line(
+        &mut self,
+        start: Vector3,
+        end: Vector3,
+        color: (f32, f32, f32, f32),
+        duration: Option<f32>,
+    ) {
+        self.lines.push(DebugLine {
+            start,
+            end,
+            color,
+            duration: duration.unwrap_or(0f32),
+            on_top: self.on_top,
+        });
+    }
+
+    /// Draw a ray from origin in the direction dir with length
+    pub fn ray(
+        &mut self,
+        origin: Vector3,
+        dir: Vector3,
+        length: f32,
+        color: (f32, f32, f32, f32),
+        duration: Option<f32>,
+    ) {
+        let end = (
+            origin.0 + dir.0 * length,
+            origin.1 + dir.1 * length,
+            origin.2 + dir.2 * length,
+        );
+        self.line(origin, end, color, duration);
+    }
+
+    /// Draw an axis aligned box with the same pos/size layout used by `collision::overlapping_3d`
+    pub fn aabb(
+        &mut self,
+        pos: Vector3,
+        size: Vector3,
+        color: (f32, f32, f32, f32),
+        duration: Option<f32>,
+    ) {
+        let corner =
+            |x: f32, y: f32, z: f32| (pos.0 + size.0 * x, pos.1 + size.1 * y, pos.2 + size.2 * z);
+        let corners = [
+            corner(0f32, 0f32, 0f32),
+            corner(1f32, 0f32, 0f32),
+            corner(1f32, 0f32, 1f32),
+            corner(0f32, 0f32, 1f32),
+            corner(0f32, 1f32, 0f32),
+            corner(1f32, 1f32, 0f32),
+            corner(1f32, 1f32, 1f32),
+            corner(0f32, 1f32, 1f32),
+        ];
+        self.box_edges(&corners, color, duration);
+    }
+
+    /// Draw a wireframe sphere made of three circles around the center
+    pub fn sphere(
+        &mut self,
+        center: Vector3,
+        radius: f32,
+        color: (f32, f32, f32, f32),
+        duration: Option<f32>,
+    ) {
+        let step = (PI * 2f32) / SPHERE_SEGMENTS as f32;
+
+        for i in 0..SPHERE_SEGMENTS {
+            let (s0, c0) = ((i as f32 * step).sin(), (i as f32 * step).cos());
+            let (s1, c1) = (((i + 1) as f32 * step).sin(), ((i + 1) as f32 * step).cos());
+
+            // xy circle
+            self.line(
+                (center.0 + c0 * radius, center.1 + s0 * radius, center.2),
+                (center.0 + c1 * radius, center.1 + s1 * radius, center.2),
+                color,
+                duration,
+            );
+            // xz circle
+            self.line(
+                (center.0 + c0 * radius, center.1, center.2 + s0 * radius),
+                (center.0 + c1 * radius, center.1, center.2 + s1 * radius),
+                color,
+                duration,
+            );
+            // yz circle
+            self.line(
+                (center.0, center.1 + c0 * radius, center.2 + s0 * radius),
+                (center.0, center.1 + c1 * radius, center.2 + s1 * radius),
+                color,
+                duration,
+            );
+        }
+    }
+
+    /// Draw the frustum described by the six planes returned from `utils::get_frustum_planes`
+    pub fn frustum(
+        &mut self,
+        planes: &[(f32, f32, f32, f32); 6],
+        color: (f32, f32, f32, f32),
+        duration: Option<f32>,
+    ) {
+        // planes are ordered left, right, top, bottom, near, far
+        let (left, right, top, bottom, near, far) = (
+            planes[0], planes[1], planes[2], planes[3], planes[4], planes[5],
+        );
+
+        let corners = [
+            intersect_planes(near, bottom, left),
+            intersect_planes(near, bottom, right),
+            intersect_planes(far, bottom, right),
+            intersect_planes(far, bottom, left),
+            intersect_planes(near, top, left),
+            intersect_planes(near, top, right),
+            intersect_planes(far, top, right),
+            intersect_planes(far, top, left),
+        ];
+        self.box_edges(&corners, color, duration);
+    }
+
+    /// Draw the x, y and z axes at pos coloured red, green and blue
+    pub fn axes(&mut self, pos: Vector3, size: f32, duration: Option<f32>) {
+        let red = (1f32, 0f32, 0f32, 1f32);
+        let green = (0f32, 1f32, 0f32, 1f32);
+        let blue = (0f32, 0f32, 1f32, 1f32);
+
+        self.line(pos, (pos.0 + size, pos.1, pos.2), red, duration);
+        self.line(pos, (pos.0, pos.1 + size, pos.2), green, duration);
+        self.line(pos, (pos.0, pos.1, pos.2 + size), blue, duration);
+    }
+
+    /// Draw a grid on the xz plane centered on center
+    pub fn grid(
+        &mut self,
+        center: Vector3,
+        size: f32,
+        divisions: u32,
+        color: (f32, f32, f32, f32),
+        duration: Option<f32>,
+    ) {
+        let half = size / 2f32;
+        let step = size / divisions.max(1) as f32;
+
+        for i in 0..=divisions.max(1) {
+            let offset = -half + step * i as f32;
+            self.line(
+                (center.0 + offset, center.1, center.2 - half),
+                (center.0 + offset, center.1, center.2 + half),
+                color,
+                duration,
+            );
+            self.line(
+                (center.0 - half, center.1, center.2 + offset),
+                (center.0 + half, center.1, center.2 + offset),
+                color,
+                duration,
+            );
+        }
+    }
+
+    /// Remove all the debug primitives
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// The number of lines currently being drawn
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Whether there are no lines to be drawn
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Advance the durations of the primitives, removing any that have expired
+    pub fn update(&mut self, delta: f32) {
+        self.lines.iter_mut().for_each(|l| l.duration -= delta);
+        self.lines.retain(|l| l.duration > 0f32);
+    }
+
+    /// Get the vertices for the lines, either those drawn on top or depth tested
+    pub fn vertices(&self, on_top: bool) -> Vec<DebugVertex> {
+        self.lines
+            .iter()
+            .filter(|l| l.on_top == on_top)
+            .flat_map(|l| {
+                let color = [l.color.0, l.color.1, l.color.2, l.color.3];
+                vec![
+                    DebugVertex {
+                        position: [l.start.0, l.start.1, l.start.2],
+                        color,
+                    },
+                    DebugVertex {
+                        position: [l.end.0, l.end.1, l.end.2],
+                        color,
+                    },
+                ]
+            })
+            .collect()
+    }
+
+    /// Draw the twelve edges of a box given its bottom then top four corners
+    fn box_edges(
+        &mut self,
+        corners: &[Vector3; 8],
+        color: (f32, f32, f32, f32),
+        duration: Option<f32>,
+    ) {
+        for i in 0..4 {
+            let next = (i + 1) % 4;
+            // bottom face
+            self.line(corners[i], corners[next], color, duration);
+            // top face
+            self.line(corners[i + 4], corners[next + 4], color, duration);
+            // sides
+            self.line(corners[i], corners[i + 4], color, duration);
+        }
+    }
+}
+
+/// Returns the point where three planes intersect
+fn intersect_planes(
+    p1: (f32, f32, f32, f32),
+    p2: (f32, f32, f32, f32),
+    p3: (f32, f32, f32, f32),
+) -> Vector3 {
+    let (n1, n2, n3) = ([p1.0, p1.1, p1.2], [p2.0, p2.1, p2.2], [p3.0, p3.1, p3.2]);
+    let (c23, c31, c12) = (crossp(n2, n3), crossp(n3, n1), crossp(n1, n2));
+    let denom = n1[0] * c23[0] + n1[1] * c23[1] + n1[2] * c23[2];
+
+    (
+        -(p1.3 * c23[0] + p2.3 * c31[0] + p3.3 * c12[0]) / denom,
+        -(p1.3 * c23[1] + p2.3 * c31[1] + p3.3 * c12[1]) / denom,
+        -(p1.3 * c23[2] + p2.3 * c31[2] + p3.3 * c12[2]) / denom,
+    )
+}
@@ -0,0 +1,285 @@
+use glium::backend::{Context, Facade};
+use glium_text::{Error, FontTexture, TextDisplay, TextSystem};
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::Read;
+use std::rc::Rc;
+
+use types::{Matrix4, TextAlign, TextItem, TextSpace};
+
+/// The name of the font bundled with the renderer
+pub const DEFAULT_FONT: &str = "default";
+
+/// A font loaded into the registry along with the glyphs it has rasterized
+struct Font {
+    /// The raw TTF/OTF data of the font
+    data: Vec<u8>,
+    /// The size in pixels the glyphs are rasterized at
+    size: u32,
+    /// The characters that are in the texture
+    characters: BTreeSet<char>,
+    /// The characters that failed to be rasterized, so they aren't retried
+    failed: BTreeSet<char>,
+    /// The texture containing the rasterized glyphs
+    texture: FontTexture,
+}
+
+/// Registry of all the fonts that TextItems can be drawn with
+pub struct FontRegistry {
+    /// Ref to the render context
+    context: Rc<Context>,
+    /// The fonts by name
+    fonts: HashMap<String, Font>,
+}
+
+impl FontRegistry {
+    /// Create a new empty font registry
+    pub fn new<F>(facade: &F) -> FontRegistry
+    where
+        F: Facade + Clone,
+    {
+        FontRegistry {
+            context: facade.get_context().clone(),
+            fonts: HashMap::new(),
+        }
+    }
+
+    /// Load a font from TTF/OTF bytes with the glyphs rasterized at size pixels
+    pub fn load_font(&mut self, name: &str, data: Vec<u8>, size: u32) -> Result<(), String> {
+        let characters = FontTexture::ascii_character_list()
+            .into_iter()
+            .collect::<BTreeSet<char>>();
+        let (texture, characters) = build_texture(&self.context, &data, size, characters)?;
+
+        self.fonts.insert(
+            name.to_string(),
+            Font {
+                data,
+                size,
+                characters,
+                failed: BTreeSet::new(),
+                texture,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Load a font from a TTF/OTF file at path with the glyphs rasterized at size pixels
+    pub fn load_font_path(&mut self, name: &str, path: &str, size: u32) -> Result<(), String> {
+        let mut data = Vec::new();
+        File::open(path)
+            .and_then(|mut f| f.read_to_end(&mut data))
+            .map_err(|e| format!("{}", e))?;
+
+        self.load_font(name, data, size)
+    }
+
+    /// Whether a font with the name has been loaded
+    pub fn has_font(&self, name: &str) -> bool {
+        self.fonts.contains_key(name)
+    }
+
+    /// The name of the font to draw with, falling back to the default font
+    /// if no font with the name has been loaded
+    pub fn resolve<'a>(&self, name: &'a str) -> &'a str {
+        if self.has_font(name) {
+            name
+        } else {
+            DEFAULT_FONT
+        }
+    }
+
+    /// Get the texture for the font, if it has been loaded
+    pub fn get(&self, name: &str) -> Option<&FontTexture> {
+        self.fonts.get(name).map(|f| &f.texture)
+    }
+
+    /// Make sure all the characters in text have been rasterized for the font,
+    /// rebuilding the font texture if any are missing, characters that fail
+    /// to be rasterized are only reported the first time
+    pub fn ensure_characters(&mut self, name: &str, text: &str) -> Result<(), String> {
+        let font = match self.fonts.get_mut(name) {
+            Some(f) => f,
+            None => return Err(format!("No font named {}", name)),
+        };
+
+        let missing = text
+            .chars()
+            .filter(|c| !c.is_control() && !font.characters.contains(c) && !font.failed.contains(c))
+            .collect::<BTreeSet<char>>();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let characters = font.characters.union(&missing).cloned().collect();
+        let (texture, characters) =
+            match build_texture(&self.context, &font.data, font.size, characters) {
+                Ok(built) => built,
+                Err(e) => {
+                    font.failed.extend(missing);
+                    return Err(e);
+                }
+            };
+        font.texture = texture;
+        // glyphs the font does not contain are kept so they are not retried every frame
+        font.characters = characters.union(&missing).cloned().collect();
+
+        Ok(())
+    }
+}
+
+/// Builds the font texture, dropping any characters that the font has no glyph for
+fn build_texture(
+    context: &Rc<Context>,
+    data: &[u8],
+    size: u32,
+    mut characters: BTreeSet<char>,
+) -> Result<(FontTexture, BTreeSet<char>), String> {
+    loop {
+        match FontTexture::new(context, data, size, characters.iter().cloned()) {
+            Ok(texture) => return Ok((texture, characters)),
+            Err(Error::NoGlyph(c)) => {
+                characters.remove(&c);
+            }
+            Err(e) => return Err(format!("{:?}", e)),
+        }
+    }
+}
+
+/// Splits the text of the item into lines, wrapping words at max_width
+/// where unit_width is the width of one text unit once drawn
+pub fn layout_text<'a>(
+    system: &TextSystem,
+    font: &'a FontTexture,
+    text_item: &TextItem,
+    unit_width: f32,
+) -> Vec<(TextDisplay<&'a FontTexture>, f32, f32)> {
+    let max_width = text_item
+        .max_width
+        .filter(|_| unit_width > 0f32)
+        .map(|w| w / unit_width);
+    let lines = wrap_text(&text_item.text, max_width, |line| {
+        TextDisplay::new(system, font, line).get_width()
+    });
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let display = TextDisplay::new(system, font, line.as_str());
+            let (x_offset, y_offset) = line_offset(
+                text_item.alignment(),
+                display.get_width(),
+                i,
+                text_item.line_spacing,
+            );
+
+            (display, x_offset, y_offset)
+        })
+        .collect()
+}
+
+/// Splits the text into lines at new lines and between words so that no line
+/// measures wider than max_width, unless it's a single word
+pub fn wrap_text<F>(text: &str, max_width: Option<f32>, measure: F) -> Vec<String>
+where
+    F: Fn(&str) -> f32,
+{
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let max_width = match max_width {
+            Some(w) => w,
+            None => {
+                lines.push(paragraph.to_string());
+                continue;
+            }
+        };
+
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+
+            if measure(&candidate) > max_width && !line.is_empty() {
+                lines.push(line);
+                line = word.to_string();
+            } else {
+                line = candidate;
+            }
+        }
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// The offset in text units of the line with the width from the item's position
+pub fn line_offset(align: TextAlign, width: f32, line: usize, line_spacing: f32) -> (f32, f32) {
+    let x_offset = match align {
+        TextAlign::Left => 0f32,
+        TextAlign::Center => -width / 2f32,
+        TextAlign::Right => -width,
+    };
+
+    (x_offset, -(line as f32) * line_spacing)
+}
+
+/// The model matrix of a line of world text offset from the item's position
+/// in text units, billboards are turned to face the camera of the modelview
+pub fn world_text_matrix(
+    text_item: &TextItem,
+    modelview_matrix: Matrix4,
+    offset: (f32, f32),
+) -> Matrix4 {
+    // the axes the text is laid out along
+    let (right, up, forward) = match text_item.space {
+        TextSpace::WorldBillboard => (
+            [
+                modelview_matrix[0][0],
+                modelview_matrix[1][0],
+                modelview_matrix[2][0],
+            ],
+            [
+                modelview_matrix[0][1],
+                modelview_matrix[1][1],
+                modelview_matrix[2][1],
+            ],
+            [
+                modelview_matrix[0][2],
+                modelview_matrix[1][2],
+                modelview_matrix[2][2],
+            ],
+        ),
+        _ => ([1f32, 0f32, 0f32], [0f32, 1f32, 0f32], [0f32, 0f32, 1f32]),
+    };
+    let scale = text_item.scale;
+    let (x_offset, y_offset) = (offset.0 * scale.0, offset.1 * scale.1);
+
+    [
+        [
+            right[0] * scale.0,
+            right[1] * scale.0,
+            right[2] * scale.0,
+            0f32,
+        ],
+        [up[0] * scale.1, up[1] * scale.1, up[2] * scale.1, 0f32],
+        [
+            forward[0] * scale.2,
+            forward[1] * scale.2,
+            forward[2] * scale.2,
+            0f32,
+        ],
+        [
+            text_item.pos.0 + right[0] * x_offset + up[0] * y_offset,
+            text_item.pos.1 + right[1] * x_offset + up[1] * y_offset,
+            text_item.pos.2 + right[2] * x_offset + up[2] * y_offset,
+            1f32,
+        ],
+    ]
+}
@@ -88,6 +88,12 @@ impl Lighting {
         &mut self.directional_lights[index]
     }
 
+    /// Get mutable refs to all of the directional lights
+    /// Note: regenerate_lighting_tex must be called after mutating them
+    pub fn directional_lights_mut(&mut self) -> &mut [DirectionalLight] {
+        &mut self.directional_lights
+    }
+
     /// Get a ref to a directional light from its name, returning the first found
     pub fn get_directional_light_by_name(&mut self, name: &str) -> Option<&mut DirectionalLight> {
         for i in 0..self.directional_lights.len() {
@@ -1,10 +1,16 @@
 /// Module for utility functions for textures
 #[macro_use]
 pub mod texture;
+/// Module for immediate mode debug drawing
+pub mod debug;
+/// Module for loading fonts and laying out text
+pub mod font;
 /// Module for the lighting system
 pub mod lighting;
 /// Rendering post processing effects
 pub mod posteffect;
+/// Module for ordering render items for drawing
+pub mod queue;
 /// Module for dealing with shaders
 pub mod shader;
 
@@ -16,14 +22,20 @@ use glium::glutin::{
     Api, ContextBuilder, GlRequest,
 };
 use glium::index::{NoIndices, PrimitiveType};
-use glium::texture::RawImage2d;
+use glium::texture::{
+    MipmapsOption, RawImage2d, Texture2d, TextureCreationError, UncompressedFloatFormat,
+};
 use glium::vertex::VertexBuffer;
-use glium::DepthTest::IfLess;
+use glium::DepthTest::{self, IfLess};
 use glium::Frame;
-use glium::{Blend, Depth, Display, DrawParameters, Surface};
+use glium::{
+    Blend, BlendingFunction, Depth, Display, DrawError, DrawParameters, LinearBlendingFactor,
+    PolygonMode, Rect, Surface,
+};
 
+use glium::uniforms::{MagnifySamplerFilter, MinifySamplerFilter, SamplerBehavior};
 use glium_text;
-use glium_text::{FontTexture, TextDisplay, TextSystem};
+use glium_text::TextSystem;
 
 use fps_counter::FPSCounter;
 use std::default::Default;
@@ -38,31 +50,56 @@ use image;
 
 use rayon::prelude::*;
 
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::fs::OpenOptions;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::thread;
 
+use self::font::{layout_text, world_text_matrix, FontRegistry, DEFAULT_FONT};
+use self::queue::build_queue;
+use debug::{DebugDraw, DebugVertex};
 use input::{Input, MouseButton};
 use lighting::Lighting;
+use mesh::gen_quad;
+use morph::{Morphs, MORPH_TEX_WIDTH};
+use particles::{ParticleBlend, ParticleEmitter, ParticleIn};
 use posteffect::{render_to_texture, PostEffect};
 use shader::Shaders;
+use skinning::SkinVertex;
 #[cfg(feature = "nphysics")]
 use types::PhysicsType;
-use types::{Camera, RenderItem, ShaderIn, TextItem};
+use types::{
+    BlendMode, Camera, CullMode, Material, Matrix4, RenderItem, ShaderIn, SpriteItem, TextItem,
+    TextSpace, Vector3,
+};
 use utils::{
-    build_fp_view_matrix, build_persp_proj_mat, frustrum_test, get_frustum_planes, mul_mat4,
+    build_fp_view_matrix, build_persp_proj_mat, dotp, get_frustum_planes, mul_mat4, sub_vec3,
+    transpose_mat4,
 };
 
+/// The vertical field of view used for the projection matrix
+pub const FIELD_OF_VIEW: f32 = 60f32;
+/// The distance to the near clipping plane
+pub const Z_NEAR: f32 = 0.01f32;
+/// The distance to the far clipping plane
+pub const Z_FAR: f32 = 1000f32;
+/// The number of debug vertices the buffer starts with room for
+const DEBUG_BUFFER_SIZE: usize = 1024;
+
 /// struct for abstracting the render state
 pub struct Renderer {
     /// The glium display used for rendering
     pub display: Display,
     /// The glium_text system used for rendering TextItem
     text_system: Arc<Mutex<TextSystem>>,
-    /// Fefault font that the text renderer will use
-    default_font: Arc<Mutex<FontTexture>>,
+    /// The fonts that the text renderer can use
+    pub fonts: FontRegistry,
+    /// The errors from loading glyphs and drawing text in the last drawn frame
+    pub text_errors: Vec<String>,
     /// Main imgui system
     pub imgui: imgui::Context,
     /// The sub renderer for imgui
@@ -73,6 +110,22 @@ pub struct Renderer {
     pub shaders: Shaders,
     /// The lighting system
     pub lighting: Lighting,
+    /// The immediate mode debug drawing system
+    pub debug_draw: DebugDraw,
+    /// The debug line vertices, grown when there are more lines than fit
+    debug_buffer: RefCell<VertexBuffer<DebugVertex>>,
+    /// The joint influences of the skins drawn last frame by their id
+    skin_buffers: RefCell<HashMap<usize, VertexBuffer<SkinVertex>>>,
+    /// Joint influences with no weight for morphed items without a skin, by vertex count
+    unskinned_buffers: RefCell<HashMap<usize, VertexBuffer<SkinVertex>>>,
+    /// The joint matrices of the skinned item being drawn
+    joint_tex: RefCell<Texture2d>,
+    /// The deltas of the morphs drawn last frame by their id
+    morph_textures: RefCell<HashMap<usize, Texture2d>>,
+    /// The deltas for skinned items without morphs, never sampled
+    no_morph_tex: Texture2d,
+    /// The morph weights of the instances of the item being drawn
+    morph_weight_tex: RefCell<Texture2d>,
     /// Info on the current gif being written to
     gif_info: Option<GifInfo>,
     /// stuct to track the fps
@@ -110,13 +163,14 @@ impl Renderer {
         let text_system = TextSystem::new(&display);
         let scale_factor = display.gl_window().window().scale_factor();
 
-        let font = FontTexture::new(
-            &display,
-            &include_bytes!("./resources/font.ttf")[..],
-            (100 as f64 * scale_factor) as u32,
-            glium_text::FontTexture::ascii_character_list(),
-        )
-        .unwrap();
+        let mut fonts = FontRegistry::new(&display);
+        fonts
+            .load_font(
+                DEFAULT_FONT,
+                include_bytes!("./resources/font.ttf").to_vec(),
+                (100 as f64 * scale_factor) as u32,
+            )
+            .unwrap();
 
         let mut imgui = imgui::Context::create();
         {
@@ -171,11 +225,37 @@ impl Renderer {
         let lighting = Lighting::new(&display);
 
         let fps_counter = FPSCounter::new();
+        let joint_tex = Texture2d::empty_with_format(
+            &display,
+            UncompressedFloatFormat::F32F32F32F32,
+            MipmapsOption::NoMipmap,
+            4,
+            1,
+        )
+        .unwrap();
+        let no_morph_tex = Texture2d::empty_with_format(
+            &display,
+            UncompressedFloatFormat::F32F32F32F32,
+            MipmapsOption::NoMipmap,
+            1,
+            1,
+        )
+        .unwrap();
+        let morph_weight_tex = Texture2d::empty_with_format(
+            &display,
+            UncompressedFloatFormat::F32,
+            MipmapsOption::NoMipmap,
+            1,
+            1,
+        )
+        .unwrap();
+        let debug_buffer = VertexBuffer::empty_dynamic(&display, DEBUG_BUFFER_SIZE).unwrap();
 
         let renderer = Renderer {
             display,
             text_system: Arc::new(Mutex::new(text_system)),
-            default_font: Arc::new(Mutex::new(font)),
+            fonts,
+            text_errors: Vec::new(),
             imgui,
             imgui_rend,
             post_effect,
@@ -185,6 +265,14 @@ impl Renderer {
             fps: 0f32,
             gif_info: None,
             lighting,
+            debug_draw: DebugDraw::new(),
+            debug_buffer: RefCell::new(debug_buffer),
+            skin_buffers: RefCell::new(HashMap::new()),
+            unskinned_buffers: RefCell::new(HashMap::new()),
+            joint_tex: RefCell::new(joint_tex),
+            morph_textures: RefCell::new(HashMap::new()),
+            no_morph_tex,
+            morph_weight_tex: RefCell::new(morph_weight_tex),
             render_count: 0usize,
             show_editor: false,
         };
@@ -285,6 +373,228 @@ impl Renderer {
             });
         }
     }
+
+    /// Draws the particles of each emitter as camera facing billboards,
+    /// returning the number of particles drawn
+    fn draw_particles<S: Surface>(
+        &self,
+        target: &mut S,
+        particle_emitters: &[ParticleEmitter],
+        cam_pos: Vector3,
+        projection_matrix: Matrix4,
+        modelview_matrix: Matrix4,
+    ) -> usize {
+        let vertex_buffer = VertexBuffer::new(&self.display, &gen_quad()).unwrap();
+        let mut count = 0usize;
+
+        for emitter in particle_emitters
+            .iter()
+            .filter(|e| e.active && !e.particles.is_empty())
+        {
+            let mut data = emitter
+                .particles
+                .par_iter()
+                .map(|p| ParticleIn {
+                    particle_position: p.pos,
+                    particle_color: emitter.color_at(p.life()),
+                    particle_size: emitter.size_at(p.life()),
+                })
+                .collect::<Vec<_>>();
+
+            let blend = match emitter.blend {
+                ParticleBlend::Alpha => {
+                    // sort back to front so blending is correct
+                    let dist = |p: &ParticleIn| {
+                        let d = sub_vec3(
+                            [
+                                p.particle_position.0,
+                                p.particle_position.1,
+                                p.particle_position.2,
+                            ],
+                            [cam_pos.0, cam_pos.1, cam_pos.2],
+                        );
+                        dotp(&d, &d)
+                    };
+                    data.par_sort_by(|a, b| dist(b).total_cmp(&dist(a)));
+
+                    Blend::alpha_blending()
+                }
+                ParticleBlend::Additive => {
+                    let additive = BlendingFunction::Addition {
+                        source: LinearBlendingFactor::SourceAlpha,
+                        destination: LinearBlendingFactor::One,
+                    };
+                    Blend {
+                        color: additive,
+                        alpha: additive,
+                        ..Default::default()
+                    }
+                }
+            };
+
+            let params = DrawParameters {
+                depth: Depth {
+                    test: IfLess,
+                    write: false,
+                    ..Default::default()
+                },
+                blend,
+                ..Default::default()
+            };
+
+            let tex_name = emitter
+                .texture_name
+                .clone()
+                .unwrap_or_else(|| "default".to_string());
+
+            let uniforms = uniform! {
+                projection_matrix: projection_matrix,
+                modelview_matrix: modelview_matrix,
+                tex: &self.shaders.textures[tex_name.as_str()],
+                textured: emitter.texture_name.is_some(),
+            };
+
+            count += data.len();
+            let per_instance = VertexBuffer::dynamic(&self.display, &data).unwrap();
+
+            target
+                .draw(
+                    (&vertex_buffer, per_instance.per_instance().unwrap()),
+                    NoIndices(PrimitiveType::TrianglesList),
+                    &self.shaders.shaders["particle"],
+                    &uniforms,
+                    &params,
+                )
+                .unwrap();
+        }
+
+        count
+    }
+
+    /// Draws the text items positioned in world space, depth tested against the scene
+    fn draw_world_text_items<S: Surface>(
+        &self,
+        target: &mut S,
+        text_items: &[TextItem],
+        projection_matrix: Matrix4,
+        modelview_matrix: Matrix4,
+    ) -> Result<(), DrawError> {
+        let params = DrawParameters {
+            depth: Depth {
+                test: IfLess,
+                write: true,
+                ..Default::default()
+            },
+            blend: Blend::alpha_blending(),
+            ..Default::default()
+        };
+        let behavior = SamplerBehavior {
+            magnify_filter: MagnifySamplerFilter::Linear,
+            minify_filter: MinifySamplerFilter::Linear,
+            ..Default::default()
+        };
+
+        // the combined view projection in column-major order
+        let view_proj = transpose_mat4(mul_mat4(projection_matrix, modelview_matrix));
+
+        let text_system = self.text_system.lock().unwrap();
+
+        for text_item in text_items
+            .iter()
+            .filter(|t| t.active && t.space != TextSpace::Screen)
+        {
+            let font = match self.fonts.get(self.fonts.resolve(&text_item.font)) {
+                Some(f) => f,
+                None => continue,
+            };
+
+            for (text, x_offset, y_offset) in
+                layout_text(&text_system, font, text_item, text_item.scale.0)
+            {
+                let model_matrix =
+                    world_text_matrix(text_item, modelview_matrix, (x_offset, y_offset));
+                let matrix = transpose_mat4(mul_mat4(view_proj, model_matrix));
+
+                glium_text::draw_with_params(
+                    &text,
+                    &text_system,
+                    target,
+                    matrix,
+                    text_item.color,
+                    behavior,
+                    &params,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rasterizes any glyphs the text items use that their fonts are missing,
+    /// glyphs that fail are added to text_errors once and then skipped
+    fn load_text_glyphs(&mut self, text_items: &[TextItem]) {
+        for text_item in text_items.iter().filter(|t| t.active) {
+            let font = self.fonts.resolve(&text_item.font).to_string();
+            if let Err(e) = self.fonts.ensure_characters(&font, &text_item.text) {
+                self.text_errors.push(e);
+            }
+        }
+    }
+
+    /// Draws the debug lines that are either depth tested or drawn on top
+    fn draw_debug<S: Surface>(
+        &self,
+        target: &mut S,
+        on_top: bool,
+        projection_matrix: Matrix4,
+        modelview_matrix: Matrix4,
+    ) {
+        if !self.debug_draw.enabled {
+            return;
+        }
+
+        let vertices = self.debug_draw.vertices(on_top);
+        if vertices.is_empty() {
+            return;
+        }
+
+        let params = DrawParameters {
+            depth: Depth {
+                test: if on_top { DepthTest::Overwrite } else { IfLess },
+                write: !on_top,
+                ..Default::default()
+            },
+            blend: Blend::alpha_blending(),
+            ..Default::default()
+        };
+
+        let uniforms = uniform! {
+            projection_matrix: projection_matrix,
+            modelview_matrix: modelview_matrix,
+        };
+
+        // only replace the buffer when the lines no longer fit
+        let mut buffer = self.debug_buffer.borrow_mut();
+        if buffer.len() < vertices.len() {
+            let size = vertices.len().next_power_of_two();
+            match VertexBuffer::empty_dynamic(&self.display, size) {
+                Ok(b) => *buffer = b,
+                Err(_) => return,
+            }
+        }
+        let slice = buffer.slice(0..vertices.len()).unwrap();
+        slice.write(&vertices);
+
+        target
+            .draw(
+                slice,
+                NoIndices(PrimitiveType::LinesList),
+                &self.shaders.shaders["debug"],
+                &uniforms,
+                &params,
+            )
+            .unwrap();
+    }
 }
 
 /// Trait for drawing to screen
@@ -295,15 +605,21 @@ pub trait Draw {
         cams: &mut Vec<Camera>,
         render_items: &mut Vec<RenderItem<T>>,
         text_items: &mut Vec<TextItem>,
+        particle_emitters: &mut Vec<ParticleEmitter>,
+        sprite_items: &mut Vec<SpriteItem>,
         f: F,
     );
-    /// Draws render_items
+    /// Draws render_items, particle_emitters and world space text_items
     fn draw_render_items<T: Default>(
         &mut self,
         target: Arc<Mutex<Frame>>,
         cams: &mut Vec<Camera>,
         render_items: &mut Vec<RenderItem<T>>,
+        particle_emitters: &mut Vec<ParticleEmitter>,
+        text_items: &mut Vec<TextItem>,
     );
+    /// Draws the sprite_items
+    fn draw_sprite_items(&mut self, target: Arc<Mutex<Frame>>, sprite_items: &mut Vec<SpriteItem>);
     /// Draws the text_items
     fn draw_text_items(&mut self, target: Arc<Mutex<Frame>>, text_items: &mut Vec<TextItem>);
     /// Draws the ui
@@ -324,11 +640,23 @@ impl Draw for Renderer {
         cams: &mut Vec<Camera>,
         render_items: &mut Vec<RenderItem<T>>,
         text_items: &mut Vec<TextItem>,
+        particle_emitters: &mut Vec<ParticleEmitter>,
+        sprite_items: &mut Vec<SpriteItem>,
         f: F,
     ) {
+        self.text_errors.clear();
+        self.load_text_glyphs(text_items);
+
         let target = Arc::new(Mutex::new(self.display.draw()));
 
-        self.draw_render_items(Arc::clone(&target), cams, render_items);
+        self.draw_render_items(
+            Arc::clone(&target),
+            cams,
+            render_items,
+            particle_emitters,
+            text_items,
+        );
+        self.draw_sprite_items(Arc::clone(&target), sprite_items);
         self.draw_text_items(Arc::clone(&target), text_items);
         self.draw_ui(Arc::clone(&target), cams, render_items, text_items, f);
 
@@ -341,25 +669,30 @@ impl Draw for Renderer {
         };
     }
 
-    /// Draw render_items
+    /// Draw render_items, particle_emitters and world space text_items
     fn draw_render_items<T: Default>(
         &mut self,
         target: Arc<Mutex<Frame>>,
         cams: &mut Vec<Camera>,
         render_items: &mut Vec<RenderItem<T>>,
+        particle_emitters: &mut Vec<ParticleEmitter>,
+        text_items: &mut Vec<TextItem>,
     ) {
-        // draw parameters
-        let params = DrawParameters {
-            depth: Depth {
-                test: IfLess,
-                write: true,
-                clamp: DepthClamp::Clamp,
-                ..Default::default()
-            },
-            blend: Blend::alpha_blending(),
-            backface_culling: BackfaceCullingMode::CullClockwise,
-            ..Default::default()
-        };
+        // drop the buffers of skins and morphs that are no longer drawn
+        let skin_ids = render_items
+            .iter()
+            .filter_map(|r| r.skin.as_ref().map(|s| s.id()))
+            .collect::<HashSet<_>>();
+        self.skin_buffers
+            .borrow_mut()
+            .retain(|id, _| skin_ids.contains(id));
+        let morph_ids = render_items
+            .iter()
+            .filter_map(|r| r.morphs.as_ref().map(|m| m.id()))
+            .collect::<HashSet<_>>();
+        self.morph_textures
+            .borrow_mut()
+            .retain(|id, _| morph_ids.contains(id));
 
         // drawing a frame
         let context = self.display.get_context().clone();
@@ -369,11 +702,12 @@ impl Draw for Renderer {
         let mut depths = Vec::new();
         let mut p_mat = None;
         let mut mv_mat = None;
+        let mut text_errors = Vec::new();
 
         cams.iter_mut().for_each(|cam| {
             // uniforms passed to the shaders
             let projection_matrix =
-                build_persp_proj_mat(60f32, width as f32 / height as f32, 0.01f32, 1000f32);
+                build_persp_proj_mat(FIELD_OF_VIEW, width as f32 / height as f32, Z_NEAR, Z_FAR);
             if p_mat.is_none() {
                 p_mat = Some(projection_matrix);
             }
@@ -395,41 +729,51 @@ impl Draw for Renderer {
                     // clear the colour and depth buffers
                     target.clear_color_and_depth((1.0, 1.0, 1.0, 1.0), 1.0);
 
-                    // drawing the render items (with more than one instance)
-                    render_items
+                    // drawing the render items, opaque then transparent
+                    build_queue(render_items, cam_pos, &frustum_planes)
                         .iter()
-                        .filter(|r| r.active && !r.instance_transforms.is_empty())
-                        .for_each(|item| {
+                        .for_each(|entry| {
+                            let item = &render_items[entry.item];
+                            let visible = &entry.instances;
+                            let mut params = material_params(&item.material);
+
+                            // levels cross-fading are blended by the tint alpha
+                            if entry.fading {
+                                if item.material.blend == BlendMode::Opaque {
+                                    params.blend = Blend::alpha_blending();
+                                }
+                                params.depth.write = false;
+                            }
+
                             // building the vertex and index buffers
-                            let vertex_buffer =
-                                VertexBuffer::new(&self.display, &item.vertices).unwrap();
+                            let vertices = match item.lods {
+                                Some(ref lods) => lods.vertices(&item.vertices, entry.level),
+                                None => &item.vertices,
+                            };
+                            let vertex_buffer = VertexBuffer::new(&self.display, vertices).unwrap();
 
                             // add positions for instances
                             let per_instance = {
-                                let data = item
-                                    .instance_transforms
-                                    .par_iter()
-                                    .filter(|t| {
-                                        (!t.cull
-                                            || frustrum_test(
-                                                &t.pos,
-                                                t.scale.0.max(t.scale.1.max(t.scale.2)) * 2.5f32,
-                                                &frustum_planes,
-                                            ))
-                                            && t.active
-                                    })
-                                    .map(|t| ShaderIn {
-                                        world_position: t.pos,
-                                        world_rotation: t.rot,
-                                        world_scale: t.scale,
+                                let data = visible
+                                    .iter()
+                                    .zip(entry.fades.iter())
+                                    .map(|(&i, fade)| {
+                                        let t = &item.instance_transforms[i];
+                                        ShaderIn {
+                                            world_position: t.pos,
+                                            world_rotation: t.rot,
+                                            world_scale: t.scale,
+                                            world_tint: (
+                                                t.tint.0,
+                                                t.tint.1,
+                                                t.tint.2,
+                                                t.tint.3 * fade,
+                                            ),
+                                            world_data: t.data,
+                                        }
                                     })
                                     .collect::<Vec<_>>();
 
-                                // if there are no active transforms skip ri
-                                if data.is_empty() {
-                                    return;
-                                }
-
                                 // add instances to render_count
                                 render_count += data.len();
 
@@ -449,6 +793,115 @@ impl Draw for Renderer {
 
                             let dir_lights = self.lighting.directional_tex.borrow();
 
+                            // skinned and morphed items are deformed in the skinned shader
+                            if item.skin.is_some() || item.morphs.is_some() {
+                                let vertex_count = item.vertices.len();
+                                // checked when the render item is built
+                                debug_assert!(
+                                    item.skin
+                                        .as_ref()
+                                        .is_none_or(|s| s.vertices().len() == vertex_count)
+                                        && item
+                                            .morphs
+                                            .as_ref()
+                                            .is_none_or(|m| m.matches(vertex_count)),
+                                    "The skin or morphs of {} don't match its vertices",
+                                    item.name
+                                );
+
+                                // items without a skin aren't moved by the joints
+                                let mut skin_buffers = self.skin_buffers.borrow_mut();
+                                let mut unskinned_buffers = self.unskinned_buffers.borrow_mut();
+                                let skin_buffer = match item.skin {
+                                    Some(ref skin) => {
+                                        skin_buffers.entry(skin.id()).or_insert_with(|| {
+                                            VertexBuffer::new(&self.display, skin.vertices())
+                                                .unwrap()
+                                        })
+                                    }
+                                    None => {
+                                        unskinned_buffers.entry(vertex_count).or_insert_with(|| {
+                                            VertexBuffer::new(
+                                                &self.display,
+                                                &vec![SkinVertex::default(); vertex_count],
+                                            )
+                                            .unwrap()
+                                        })
+                                    }
+                                };
+                                let mut morph_textures = self.morph_textures.borrow_mut();
+                                let morph_tex = match item.morphs {
+                                    Some(ref morphs) => match morph_textures.entry(morphs.id()) {
+                                        Entry::Occupied(e) => &*e.into_mut(),
+                                        Entry::Vacant(e) => {
+                                            match morph_delta_texture(&self.display, morphs) {
+                                                Ok(tex) => &*e.insert(tex),
+                                                Err(_) => return,
+                                            }
+                                        }
+                                    },
+                                    None => &self.no_morph_tex,
+                                };
+
+                                // the item can't be drawn if its textures can't be made
+                                let written = write_joint_texture(
+                                    &self.display,
+                                    &mut self.joint_tex.borrow_mut(),
+                                    item.skin.as_ref().map_or(&[], |s| s.joint_matrices()),
+                                )
+                                .and_then(|_| {
+                                    write_morph_weights(
+                                        &self.display,
+                                        &mut self.morph_weight_tex.borrow_mut(),
+                                        item.morphs.as_ref(),
+                                        visible,
+                                    )
+                                });
+                                if written.is_err() {
+                                    return;
+                                }
+                                let joint_tex = self.joint_tex.borrow();
+                                let morph_weight_tex = self.morph_weight_tex.borrow();
+                                let morph_count =
+                                    item.morphs.as_ref().map_or(0, |m| m.targets().len()) as i32;
+
+                                let uniforms = uniform! {
+                                    projection_matrix: projection_matrix,
+                                    modelview_matrix: modelview_matrix,
+                                    cam_pos: cam_pos,
+                                    viewport: (width as f32, height as f32),
+                                    time: time,
+                                    tex: &self.shaders.textures[tex_name.as_str()],
+                                    normal_tex: &self.shaders.textures[normal_tex_name.as_str()],
+                                    dir_lights: &*dir_lights,
+                                    joint_tex: &*joint_tex,
+                                    morph_tex: morph_tex,
+                                    morph_weight_tex: &*morph_weight_tex,
+                                    morph_count: morph_count,
+                                    vertex_count: vertex_count as i32,
+                                };
+
+                                target
+                                    .draw(
+                                        (
+                                            &vertex_buffer,
+                                            &*skin_buffer,
+                                            per_instance.per_instance().unwrap(),
+                                        ),
+                                        NoIndices(PrimitiveType::Patches {
+                                            vertices_per_patch: 3,
+                                        }),
+                                        self.shaders
+                                            .skinned_shaders
+                                            .get(item.material.shader_name.as_str())
+                                            .unwrap_or(&self.shaders.skinned_shaders["dist"]),
+                                        &uniforms,
+                                        &params,
+                                    )
+                                    .unwrap();
+                                return;
+                            }
+
                             let uniforms = uniform! {
                                 projection_matrix: projection_matrix,
                                 modelview_matrix: modelview_matrix,
@@ -463,7 +916,7 @@ impl Draw for Renderer {
                             target
                                 .draw(
                                     (&vertex_buffer, per_instance.per_instance().unwrap()),
-                                    &NoIndices(PrimitiveType::Patches {
+                                    NoIndices(PrimitiveType::Patches {
                                         vertices_per_patch: 3,
                                     }),
                                     &self.shaders.shaders[item.material.shader_name.as_str()],
@@ -472,6 +925,28 @@ impl Draw for Renderer {
                                 )
                                 .unwrap();
                         });
+
+                    // draw the particles after the render items
+                    render_count += self.draw_particles(
+                        target,
+                        particle_emitters,
+                        cam_pos,
+                        projection_matrix,
+                        modelview_matrix,
+                    );
+
+                    // draw the text positioned in the world
+                    if let Err(e) = self.draw_world_text_items(
+                        target,
+                        text_items,
+                        projection_matrix,
+                        modelview_matrix,
+                    ) {
+                        text_errors.push(e.to_string());
+                    }
+
+                    // draw the depth tested debug lines
+                    self.draw_debug(target, false, projection_matrix, modelview_matrix);
                 });
 
             cols.push(target_color);
@@ -570,46 +1045,101 @@ impl Draw for Renderer {
                     &Default::default(),
                 )
                 .unwrap();
+
+            // draw the debug lines over the scene
+            self.draw_debug(&mut *target, true, p_mat.unwrap(), mv_mat.unwrap());
         }
 
         self.render_count = render_count;
+        self.text_errors.extend(text_errors);
+    }
+
+    fn draw_sprite_items(&mut self, target: Arc<Mutex<Frame>>, sprite_items: &mut Vec<SpriteItem>) {
+        let (width, height) = self.display.get_framebuffer_dimensions();
+
+        // sort by z so sprites drawn on top come last
+        let mut sorted = sprite_items.iter().filter(|s| s.active).collect::<Vec<_>>();
+        sorted.sort_by_key(|s| s.z);
+
+        let params = DrawParameters {
+            blend: Blend::alpha_blending(),
+            ..Default::default()
+        };
+
+        // batch runs of sprites sharing a texture into a single draw
+        let mut target = target.lock().unwrap();
+        for batch in sorted.chunk_by(|a, b| a.texture_name == b.texture_name) {
+            let vertices = batch
+                .par_iter()
+                .flat_map(|s| s.vertices())
+                .collect::<Vec<_>>();
+
+            let uniforms = uniform! {
+                resolution: (width as f32, height as f32),
+                tex: &self.shaders.textures[batch[0].texture_name.as_str()],
+            };
+
+            target
+                .draw(
+                    &VertexBuffer::new(&self.display, &vertices).unwrap(),
+                    NoIndices(PrimitiveType::TrianglesList),
+                    &self.shaders.shaders["sprite"],
+                    &uniforms,
+                    &params,
+                )
+                .unwrap();
+        }
     }
 
     fn draw_text_items(&mut self, target: Arc<Mutex<Frame>>, text_items: &mut Vec<TextItem>) {
         let (width, height) = self.display.get_framebuffer_dimensions();
-        let renderer = Arc::new(Mutex::new(self));
+        let aspect = (width as f32) / (height as f32);
+        let text_system = self.text_system.lock().unwrap();
 
         // drawing the text items
+        let mut text_errors = Vec::new();
         text_items
             .iter()
-            .filter(|r| r.active)
+            .filter(|r| r.active && r.space == TextSpace::Screen)
             .for_each(|text_item| {
-                // create the matrix for the text
-                let matrix = [
-                    [0.02 * text_item.scale.0, 0.0, 0.0, 0.0],
-                    [
-                        0.0,
-                        0.02 * text_item.scale.1 * (width as f32) / (height as f32),
-                        0.0,
-                        0.0,
-                    ],
-                    [0.0, 0.0, 0.02 * text_item.scale.2, 0.0],
-                    [text_item.pos.0, text_item.pos.1, text_item.pos.2, 1.0f32],
-                ];
-
-                // lock required members
-                let renderer = renderer.lock().unwrap();
-                let text_system = renderer.text_system.lock().unwrap();
-                let default_font = renderer.default_font.lock().unwrap();
-
-                // create TextDisplay for item
-                let text = TextDisplay::new(&*text_system, &*default_font, text_item.text.as_str());
-
-                // draw the text
-                let mut target = target.lock().unwrap();
-                let _ =
-                    glium_text::draw(&text, &*text_system, &mut *target, matrix, text_item.color);
+                let font = match self.fonts.get(self.fonts.resolve(&text_item.font)) {
+                    Some(f) => f,
+                    None => return,
+                };
+
+                let scale = (
+                    0.02 * text_item.scale.0,
+                    0.02 * text_item.scale.1 * aspect,
+                    0.02 * text_item.scale.2,
+                );
+
+                for (text, x_offset, y_offset) in
+                    layout_text(&text_system, font, text_item, scale.0)
+                {
+                    // create the matrix for the line of text
+                    let matrix = [
+                        [scale.0, 0.0, 0.0, 0.0],
+                        [0.0, scale.1, 0.0, 0.0],
+                        [0.0, 0.0, scale.2, 0.0],
+                        [
+                            text_item.pos.0 + x_offset * scale.0,
+                            text_item.pos.1 + y_offset * scale.1,
+                            text_item.pos.2,
+                            1.0f32,
+                        ],
+                    ];
+
+                    // draw the text
+                    let mut target = target.lock().unwrap();
+                    if let Err(e) =
+                        glium_text::draw(&text, &text_system, &mut *target, matrix, text_item.color)
+                    {
+                        text_errors.push(e.to_string());
+                    }
+                }
             });
+
+        self.text_errors.extend(text_errors);
     }
 
     fn draw_ui<F: FnMut(&Ui), T: Default>(
@@ -681,6 +1211,37 @@ impl Draw for Renderer {
                             ui.tree_node(&im_str!("name:{}", render_item.name))
                                 .build(|| {
                                     ui.checkbox(im_str!("active"), &mut render_item.active);
+                                    // material render state
+                                    let mut blend = match render_item.material.blend {
+                                        BlendMode::Opaque => 0,
+                                        BlendMode::Alpha => 1,
+                                        BlendMode::Additive => 2,
+                                        BlendMode::Multiply => 3,
+                                    };
+                                    ComboBox::new(im_str!("blend")).build_simple_string(
+                                        &ui,
+                                        &mut blend,
+                                        &[
+                                            im_str!("Opaque"),
+                                            im_str!("Alpha"),
+                                            im_str!("Additive"),
+                                            im_str!("Multiply"),
+                                        ],
+                                    );
+                                    render_item.material.blend = match blend {
+                                        0 => BlendMode::Opaque,
+                                        1 => BlendMode::Alpha,
+                                        2 => BlendMode::Additive,
+                                        _ => BlendMode::Multiply,
+                                    };
+                                    ui.checkbox(
+                                        im_str!("depth_write"),
+                                        &mut render_item.material.depth_write,
+                                    );
+                                    ui.checkbox(
+                                        im_str!("wireframe"),
+                                        &mut render_item.material.wireframe,
+                                    );
                                     // physics type TODO make sure this is propagated
                                     #[cfg(feature = "nphysics")]
                                     {
@@ -710,6 +1271,36 @@ impl Draw for Renderer {
                                         render_item.instance_transforms.len()
                                     ));
                                     ui.text(im_str!("vert_count:{}", render_item.vertices.len()));
+                                    if let Some(ref lods) = render_item.lods {
+                                        ui.text(im_str!("lod_levels:{}", lods.levels.len()));
+                                    }
+                                    // morph target weights for each instance
+                                    if let Some(ref mut morphs) = render_item.morphs {
+                                        let instance_count = render_item.instance_transforms.len();
+                                        if ui.collapsing_header(im_str!("morphs")).build() {
+                                            for i in 0..instance_count {
+                                                ui.tree_node(&im_str!("instance:{}", i)).build(
+                                                    || {
+                                                        for t in 0..morphs.targets().len() {
+                                                            let mut weight = morphs.weight(i, t);
+                                                            let name = im_str!(
+                                                                "{}",
+                                                                morphs.targets()[t].name
+                                                            );
+                                                            if ui
+                                                                .input_float(&name, &mut weight)
+                                                                .step(0.01)
+                                                                .step_fast(0.1)
+                                                                .build()
+                                                            {
+                                                                morphs.set_weight(i, t, weight);
+                                                            }
+                                                        }
+                                                    },
+                                                );
+                                            }
+                                        }
+                                    }
                                 });
                         }
                     }
@@ -781,3 +1372,179 @@ impl Draw for Renderer {
         renderer.imgui_rend.render(&mut *target, draw_data).unwrap();
     }
 }
+
+/// The draw parameters for the render state of a material
+fn material_params(material: &Material) -> DrawParameters<'static> {
+    let blend = match material.blend {
+        BlendMode::Opaque => Blend::default(),
+        BlendMode::Alpha => Blend::alpha_blending(),
+        BlendMode::Additive => {
+            let additive = BlendingFunction::Addition {
+                source: LinearBlendingFactor::SourceAlpha,
+                destination: LinearBlendingFactor::One,
+            };
+            Blend {
+                color: additive,
+                alpha: additive,
+                ..Default::default()
+            }
+        }
+        BlendMode::Multiply => {
+            let multiply = BlendingFunction::Addition {
+                source: LinearBlendingFactor::DestinationColor,
+                destination: LinearBlendingFactor::Zero,
+            };
+            Blend {
+                color: multiply,
+                alpha: BlendingFunction::AlwaysReplace,
+                ..Default::default()
+            }
+        }
+    };
+
+    DrawParameters {
+        depth: Depth {
+            test: if material.depth_test {
+                IfLess
+            } else {
+                DepthTest::Overwrite
+            },
+            write: material.depth_write,
+            clamp: DepthClamp::Clamp,
+            ..Default::default()
+        },
+        blend,
+        backface_culling: match material.cull {
+            CullMode::None => BackfaceCullingMode::CullingDisabled,
+            CullMode::Clockwise => BackfaceCullingMode::CullClockwise,
+            CullMode::CounterClockwise => BackfaceCullingMode::CullCounterClockwise,
+        },
+        polygon_mode: if material.wireframe {
+            PolygonMode::Line
+        } else {
+            PolygonMode::Fill
+        },
+        ..Default::default()
+    }
+}
+
+/// Write the joint matrices for the skinned shader to the texture, each row is
+/// a joint and each texel a column of its matrix, the texture is replaced with
+/// a taller one when there are more joints than rows
+fn write_joint_texture(
+    display: &Display,
+    texture: &mut Texture2d,
+    joint_matrices: &[Matrix4],
+) -> Result<(), TextureCreationError> {
+    let identity = [
+        [1f32, 0f32, 0f32, 0f32],
+        [0f32, 1f32, 0f32, 0f32],
+        [0f32, 0f32, 1f32, 0f32],
+        [0f32, 0f32, 0f32, 1f32],
+    ];
+    let rows = if joint_matrices.is_empty() {
+        vec![identity]
+    } else {
+        joint_matrices.to_vec()
+    };
+
+    let data = rows
+        .iter()
+        .map(|m| {
+            m.iter()
+                .map(|c| (c[0], c[1], c[2], c[3]))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let height = data.len() as u32;
+
+    if texture.height() < height {
+        *texture = Texture2d::empty_with_format(
+            display,
+            UncompressedFloatFormat::F32F32F32F32,
+            MipmapsOption::NoMipmap,
+            4,
+            height,
+        )?;
+    }
+    texture.write(
+        Rect {
+            left: 0,
+            bottom: 0,
+            width: 4,
+            height,
+        },
+        data,
+    );
+    Ok(())
+}
+
+/// Create the texture of the morph deltas for the skinned shader, the deltas
+/// wrap into rows of MORPH_TEX_WIDTH texels
+fn morph_delta_texture(
+    display: &Display,
+    morphs: &Morphs,
+) -> Result<Texture2d, TextureCreationError> {
+    let texels = morphs.delta_texels();
+
+    // wrap the deltas into rows, padding the last
+    let mut rows = texels
+        .chunks(MORPH_TEX_WIDTH)
+        .map(|c| c.to_vec())
+        .collect::<Vec<_>>();
+    let width = rows.first().map_or(1, |r| r.len());
+    if rows.is_empty() {
+        rows.push(vec![(0f32, 0f32, 0f32, 0f32)]);
+    }
+    for row in rows.iter_mut() {
+        row.resize(width, (0f32, 0f32, 0f32, 0f32));
+    }
+
+    Texture2d::with_format(
+        display,
+        rows,
+        UncompressedFloatFormat::F32F32F32F32,
+        MipmapsOption::NoMipmap,
+    )
+}
+
+/// Write the morph weights of the drawn instances to the texture, a row per
+/// instance, the texture is replaced with a larger one when it's too small
+fn write_morph_weights(
+    display: &Display,
+    texture: &mut Texture2d,
+    morphs: Option<&Morphs>,
+    visible: &[usize],
+) -> Result<(), TextureCreationError> {
+    let weights = morphs
+        .filter(|m| !m.targets().is_empty())
+        .map(|m| {
+            visible
+                .iter()
+                .map(|&i| m.instance_weights(i))
+                .collect::<Vec<_>>()
+        })
+        .filter(|w| !w.is_empty())
+        .unwrap_or_else(|| vec![vec![0f32]]);
+    let (width, height) = (weights[0].len() as u32, weights.len() as u32);
+
+    if texture.width() < width || texture.height() < height {
+        *texture = Texture2d::empty_with_format(
+            display,
+            UncompressedFloatFormat::F32,
+            MipmapsOption::NoMipmap,
+            width.max(texture.width()),
+            height.max(texture.height()),
+        )?;
+    }
+    texture.write(
+        Rect {
+            left: 0,
+            bottom: 0,
+            width,
+            height,
+        },
+        weights,
+    );
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use types::{RenderItem, Vector3};
+use utils::{dotp, frustrum_test, sub_vec3};
+
+use rayon::prelude::*;
+
+/// A render item to be drawn and its visible instances in the order they're drawn
+#[derive(Clone, PartialEq, Debug)]
+pub struct QueueItem {
+    /// The index of the render item
+    pub item: usize,
+    /// The LOD level the instances are drawn at, 0 is the item's own vertices
+    pub level: usize,
+    /// The indices of the instances that are drawn
+    pub instances: Vec<usize>,
+    /// The opacity of each instance, below 1 when cross-fading between LOD levels
+    pub fades: Vec<f32>,
+    /// Whether the instances are cross-fading between LOD levels, drawn blended
+    /// without writing depth
+    pub fading: bool,
+}
+
+/// Build the queue of render items to draw from the camera position, opaque
+/// items are drawn front to back so hidden fragments fail the depth test
+/// then transparent items back to front so they blend over what is behind them,
+/// inactive and culled instances are left out along with items with none visible
+///
+/// Instances of items with lods are drawn in a batch for each level, instances
+/// cross-fading are drawn with the transparent items at their level faded out
+/// and again at the next level faded in
+pub fn build_queue<T: Default>(
+    render_items: &[RenderItem<T>],
+    cam_pos: Vector3,
+    frustum_planes: &[(f32, f32, f32, f32)],
+) -> Vec<QueueItem> {
+    let dist = |pos: &Vector3| {
+        let d = sub_vec3([pos.0, pos.1, pos.2], [cam_pos.0, cam_pos.1, cam_pos.2]);
+        dotp(&d, &d)
+    };
+
+    let mut queue = Vec::new();
+    for (i, item) in render_items.iter().enumerate().filter(|(_, r)| r.active) {
+        // skinned and morphed items only have deformations for their own vertices
+        let lods = item
+            .lods
+            .as_ref()
+            .filter(|_| item.skin.is_none() && item.morphs.is_none());
+
+        let instances = item
+            .instance_transforms
+            .par_iter()
+            .enumerate()
+            .filter_map(|(j, t)| {
+                let radius = t.scale.0.max(t.scale.1.max(t.scale.2)) * 2.5f32;
+                if !t.active || (t.cull && !frustrum_test(&t.pos, radius, frustum_planes)) {
+                    return None;
+                }
+
+                let d = dist(&t.pos);
+                let (level, fade) = lods.map_or((0, 0f32), |l| l.select(d.sqrt(), radius));
+                Some((j, d, level, fade))
+            })
+            .collect::<Vec<_>>();
+
+        // group the instances by level and whether they're cross-fading
+        let mut batches = BTreeMap::new();
+        for (j, d, level, fade) in instances {
+            if fade > 0f32 {
+                let fade = fade.min(1f32);
+                batches
+                    .entry((level, true))
+                    .or_insert_with(Vec::new)
+                    .push((j, d, 1f32 - fade));
+                batches
+                    .entry((level + 1, true))
+                    .or_insert_with(Vec::new)
+                    .push((j, d, fade));
+            } else {
+                batches
+                    .entry((level, false))
+                    .or_insert_with(Vec::new)
+                    .push((j, d, 1f32));
+            }
+        }
+
+        for ((level, fading), mut batch) in batches {
+            let transparent = fading || item.material.blend.is_transparent();
+
+            // the instances of an item are ordered the same way as the items
+            batch.par_sort_by(|a, b| {
+                let order = a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal);
+                if transparent {
+                    order.reverse()
+                } else {
+                    order
+                }
+            });
+            let key = batch[0].1;
+
+            queue.push((
+                transparent,
+                key,
+                QueueItem {
+                    item: i,
+                    level,
+                    instances: batch.iter().map(|b| b.0).collect(),
+                    fades: batch.iter().map(|b| b.2).collect(),
+                    fading,
+                },
+            ));
+        }
+    }
+
+    queue.sort_by(|a, b| {
+        a.0.cmp(&b.0).then_with(|| {
+            let order = a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal);
+            if a.0 {
+                order.reverse()
+            } else {
+                order
+            }
+        })
+    });
+
+    queue.into_iter().map(|(_, _, q)| q).collect()
+}
@@ -0,0 +1,33 @@
+/// OpenGL 3.3 shaders for immediate mode debug drawing
+pub mod gl330 {
+    /// Debug vertex shader
+    pub const VERT: &str = "
+        #version 330
+
+        uniform mat4 projection_matrix;
+        uniform mat4 modelview_matrix;
+
+        layout(location = 0) in vec3 position;
+        layout(location = 1) in vec4 color;
+
+        out vec4 v_color;
+
+        void main() {
+            gl_Position = projection_matrix * modelview_matrix * vec4(position, 1.0);
+            v_color = color;
+        }
+    ";
+
+    /// Debug fragment shader
+    pub const FRAG: &str = "
+        #version 330
+
+        in vec4 v_color;
+
+        out vec4 frag_output;
+
+        void main() {
+            frag_output = v_color;
+        }
+    ";
+}
@@ -10,9 +10,13 @@ pub mod gl330 {
         layout(location = 3) in vec4 world_rotation;
         layout(location = 4) in vec3 world_scale;
         layout(location = 5) in vec2 texture;
+        layout(location = 6) in vec4 world_tint;
+        layout(location = 7) in vec4 world_data;
 
         out vec3 v_normal;
         out vec2 v_texture;
+        out vec4 v_tint;
+        out vec4 v_data;
 
         void main() {
             vec3 pos_scaled = position * world_scale;
@@ -26,6 +30,8 @@ pub mod gl330 {
 
             v_normal = normal;
             v_texture = texture;
+            v_tint = world_tint;
+            v_data = world_data;
         }
     ";
 
@@ -39,16 +45,22 @@ pub mod gl330 {
         in vec3 te_normal[];
         in vec3 te_pos[];
         in vec2 te_texture[];
+        in vec4 te_tint[];
+        in vec4 te_data[];
 
         out vec3 g_normal;
         out vec3 g_pos;
         out vec2 g_texture;
+        out vec4 g_tint;
+        out vec4 g_data;
 
         void main(void) {
             for(int i = 0; i < gl_in.length(); i++){
                 g_normal = te_normal[i];
                 g_pos = te_pos[i];
                 g_texture = te_texture[i];
+                g_tint = te_tint[i];
+                g_data = te_data[i];
                 gl_Position = gl_in[i].gl_Position;
                 EmitVertex();
             }
@@ -64,15 +76,21 @@ pub mod gl330 {
 
         in vec3 v_normal[];
         in vec2 v_texture[];
+        in vec4 v_tint[];
+        in vec4 v_data[];
 
         out vec3 tc_normal[];
         out vec2 tc_texture[];
+        out vec4 tc_tint[];
+        out vec4 tc_data[];
 
         const float tess_level = 1.0;
 
         void main() {
             tc_normal[gl_InvocationID] = v_normal[gl_InvocationID];
             tc_texture[gl_InvocationID] = v_texture[gl_InvocationID];
+            tc_tint[gl_InvocationID] = v_tint[gl_InvocationID];
+            tc_data[gl_InvocationID] = v_data[gl_InvocationID];
             gl_out[gl_InvocationID].gl_Position = gl_in[gl_InvocationID].gl_Position;
 
             gl_TessLevelOuter[0] = tess_level;
@@ -93,10 +111,14 @@ pub mod gl330 {
 
         in vec3 tc_normal[];
         in vec2 tc_texture[];
+        in vec4 tc_tint[];
+        in vec4 tc_data[];
 
         out vec3 te_normal;
         out vec3 te_pos;
         out vec2 te_texture;
+        out vec4 te_tint;
+        out vec4 te_data;
 
         vec3 tess_calc (vec3 one, vec3 two, vec3 three) {
             return ((gl_TessCoord.x) * one) +
@@ -122,6 +144,10 @@ pub mod gl330 {
             vec2 texture = tex_calc(tc_texture[0], tc_texture[1], tc_texture[2]);
             te_texture = texture;
 
+            // the instance attributes are the same for each vertex of the patch
+            te_tint = tc_tint[0];
+            te_data = tc_data[0];
+
             gl_Position = projection_matrix *
                 modelview_matrix *
                 vec4(position, 1.0);
@@ -9,6 +9,7 @@ pub mod gl330 {
 
         in vec3 g_normal;
         in vec3 g_pos;
+        in vec4 g_tint;
 
         out vec4 frag_output;
 
@@ -23,7 +24,7 @@ pub mod gl330 {
             float dist = abs(distance(cam_pos, g_pos)) / 80.0;
 
             vec3 color = vec3(0.3 + (0.2 * lum) + (0.5 * dist));
-            frag_output = vec4(color, 1.0);
+            frag_output = vec4(color, 1.0) * g_tint;
         }
     ";
 }
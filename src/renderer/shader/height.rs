@@ -9,6 +9,7 @@ pub mod gl330 {
 
         in vec3 g_normal;
         in vec3 g_pos;
+        in vec4 g_tint;
 
         out vec4 frag_output;
 
@@ -21,7 +22,7 @@ pub mod gl330 {
             base_color += dist;
 
             vec3 color = base_color * ((0.2 * lum) + (0.8 * dist));
-            frag_output = vec4(color, 1.0);
+            frag_output = vec4(color, 1.0) * g_tint;
         }
     ";
 }
@@ -8,6 +8,7 @@ pub mod gl330 {
 
         in vec3 g_normal;
         in vec3 g_pos;
+        in vec4 g_tint;
 
         out vec4 frag_output;
 
@@ -15,10 +16,10 @@ pub mod gl330 {
             float lum = max(dot(normalize(g_normal), normalize(LIGHT)), 0.0);
             float dist = max(dot(normalize(g_pos), normalize(LIGHT)), 0.0);
 
-            vec3 base_color = vec3(1.0, 0.0, 0.0);
+            vec3 base_color = vec3(1.0, 0.0, 0.0) * g_tint.rgb;
 
             vec3 color = base_color * (0.3 + (0.2 * lum) + (0.5 * dist));
-            frag_output = vec4(color, 1.0);
+            frag_output = vec4(color, g_tint.a);
         }
     ";
 
@@ -32,15 +33,21 @@ pub mod gl330 {
         in vec3 te_normal[];
         in vec3 te_pos[];
         in vec2 te_texture[];
+        in vec4 te_tint[];
+        in vec4 te_data[];
 
         out vec3 g_normal;
         out vec3 g_pos;
         out vec2 g_texture;
+        out vec4 g_tint;
+        out vec4 g_data;
 
         void main(void) {
             for(int i = 0; i < gl_in.length(); i++){
                 g_normal = te_normal[i];
                 g_pos = te_pos[i];
+                g_tint = te_tint[i];
+                g_data = te_data[i];
                 gl_Position = gl_in[i].gl_Position;
                 EmitVertex();
             }
@@ -1,3 +1,5 @@
+/// Debug shaders for immediate mode line drawing
+pub mod debug;
 /// Default shaders
 pub mod default;
 /// Distance shaders for roll off to white in relation to the camera position
@@ -6,11 +8,18 @@ pub mod dist;
 pub mod height;
 /// Line shaders for rendering wireframes
 pub mod line;
+/// Particle shaders for rendering camera facing billboards
+pub mod particle;
 /// Default post effect shader
 pub mod post;
+/// Skinned shaders for deforming meshes by the joints of a skeleton
+pub mod skinned;
+/// Sprite shaders for rendering batched 2d sprites
+pub mod sprite;
 /// Shader for rendering with textures
 pub mod texture;
 
+use glium::program::ProgramChooserCreationError;
 use glium::texture::compressed_srgb_texture2d::CompressedSrgbTexture2d;
 use glium::{Display, Program};
 use std::collections::HashMap;
@@ -20,6 +29,9 @@ use std::error::Error;
 pub struct Shaders {
     /// Map containing all the shaders used to render the RenderItems
     pub shaders: HashMap<&'static str, Program>,
+    /// Map containing the skinned variant of the shaders, used to render the
+    /// RenderItems with a skin or morph targets
+    pub skinned_shaders: HashMap<&'static str, Program>,
     /// Map containing all the shaders to be used for post processing
     pub post_shaders: HashMap<&'static str, Program>,
     /// Texture that can be passed to the shaders
@@ -87,6 +99,57 @@ impl Shaders {
             .unwrap(),
         );
 
+        shaders.insert(
+            "debug",
+            program!(display,
+            330 => {
+                vertex: debug::gl330::VERT,
+                fragment: debug::gl330::FRAG,
+            })
+            .unwrap(),
+        );
+
+        shaders.insert(
+            "particle",
+            program!(display,
+            330 => {
+                vertex: particle::gl330::VERT,
+                fragment: particle::gl330::FRAG,
+            })
+            .unwrap(),
+        );
+
+        shaders.insert(
+            "sprite",
+            program!(display,
+            330 => {
+                vertex: sprite::gl330::VERT,
+                fragment: sprite::gl330::FRAG,
+            })
+            .unwrap(),
+        );
+
+        // the skinned variants of the shader programs
+        let mut skinned_shaders = HashMap::new();
+        for (name, frag, geom) in [
+            ("dist", dist::gl330::FRAG, default::gl330::GEOM),
+            ("height", height::gl330::FRAG, default::gl330::GEOM),
+            ("line", line::gl330::FRAG, line::gl330::GEOM),
+            ("texture", texture::gl330::FRAG, default::gl330::GEOM),
+        ] {
+            skinned_shaders.insert(
+                name,
+                skinned_program(
+                    display,
+                    frag,
+                    geom,
+                    default::gl330::TESS_CONTROL,
+                    default::gl330::TESS_EVAL,
+                )
+                .unwrap(),
+            );
+        }
+
         let mut post_shaders = HashMap::new();
 
         post_shaders.insert(
@@ -109,12 +172,16 @@ impl Shaders {
 
         Shaders {
             shaders,
+            skinned_shaders,
             post_shaders,
             textures,
         }
     }
 
-    /// Add a new shader to the map that can used for rendering the RenderItems
+    /// Add a new shader to the map that can used for rendering the RenderItems,
+    /// a skinned variant is also added if the fragment, geometry and tessellation
+    /// shaders link with the skinned vertex shader, otherwise skinned and morphed
+    /// items using the shader are drawn with the skinned dist shader
     pub fn add_shader(
         &mut self,
         display: &Display,
@@ -141,6 +208,9 @@ impl Shaders {
         };
 
         self.shaders.insert(name, shader_prog);
+        if let Ok(skinned_prog) = skinned_program(display, frag, geom, tess_cont, tess_eval) {
+            self.skinned_shaders.insert(name, skinned_prog);
+        }
 
         Ok("shader added")
     }
@@ -170,3 +240,22 @@ impl Shaders {
         Ok("post shader added")
     }
 }
+
+/// Create the program that deforms the vertices with the skinned vertex shader
+/// before the rest of the shader stages
+fn skinned_program(
+    display: &Display,
+    frag: &str,
+    geom: &str,
+    tess_cont: &str,
+    tess_eval: &str,
+) -> Result<Program, ProgramChooserCreationError> {
+    program!(display,
+    330 => {
+        vertex: skinned::gl330::VERT,
+        fragment: frag,
+        geometry: geom,
+        tessellation_control: tess_cont,
+        tessellation_evaluation: tess_eval,
+    })
+}
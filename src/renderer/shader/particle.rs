@@ -0,0 +1,56 @@
+/// OpenGL 3.3 shaders for camera facing particle billboards
+pub mod gl330 {
+    /// Particle vertex shader
+    pub const VERT: &str = "
+        #version 330
+
+        uniform mat4 projection_matrix;
+        uniform mat4 modelview_matrix;
+
+        layout(location = 0) in vec3 position;
+        layout(location = 1) in vec2 texture;
+        layout(location = 2) in vec3 particle_position;
+        layout(location = 3) in vec4 particle_color;
+        layout(location = 4) in float particle_size;
+
+        out vec2 v_texture;
+        out vec4 v_color;
+
+        void main() {
+            vec3 cam_right = vec3(modelview_matrix[0][0], modelview_matrix[1][0], modelview_matrix[2][0]);
+            vec3 cam_up = vec3(modelview_matrix[0][1], modelview_matrix[1][1], modelview_matrix[2][1]);
+
+            vec3 pos = particle_position +
+                ((cam_right * position.x) + (cam_up * position.y)) * particle_size;
+
+            gl_Position = projection_matrix * modelview_matrix * vec4(pos, 1.0);
+
+            v_texture = texture;
+            v_color = particle_color;
+        }
+    ";
+
+    /// Particle fragment shader
+    pub const FRAG: &str = "
+        #version 330
+
+        uniform sampler2D tex;
+        uniform bool textured;
+
+        in vec2 v_texture;
+        in vec4 v_color;
+
+        out vec4 frag_output;
+
+        void main() {
+            if (textured) {
+                frag_output = v_color * texture(tex, v_texture);
+            } else {
+                // soft circle when there is no texture
+                float dist = distance(v_texture, vec2(0.5));
+                float alpha = 1.0 - smoothstep(0.3, 0.5, dist);
+                frag_output = vec4(v_color.rgb, v_color.a * alpha);
+            }
+        }
+    ";
+}
@@ -0,0 +1,84 @@
+/// OpenGL 3.3 skinned shaders
+pub mod gl330 {
+    /// Skinned vertex shader, blends each vertex by the instance's morph
+    /// weights then deforms it by its joints before applying the instance
+    /// transform, the joint matrices are the columns of each row of joint_tex,
+    /// the morph deltas are a position then normal texel for each vertex of
+    /// each target and the weights are a row per instance
+    pub const VERT: &str = "
+        #version 330
+
+        uniform sampler2D joint_tex;
+        uniform sampler2D morph_tex;
+        uniform sampler2D morph_weight_tex;
+        uniform int morph_count;
+        uniform int vertex_count;
+
+        layout(location = 0) in vec3 position;
+        layout(location = 1) in vec3 normal;
+        layout(location = 2) in vec3 world_position;
+        layout(location = 3) in vec4 world_rotation;
+        layout(location = 4) in vec3 world_scale;
+        layout(location = 5) in vec2 texture;
+        layout(location = 6) in uvec4 joints;
+        layout(location = 7) in vec4 weights;
+        layout(location = 8) in vec4 world_tint;
+        layout(location = 9) in vec4 world_data;
+
+        out vec3 v_normal;
+        out vec2 v_texture;
+        out vec4 v_tint;
+        out vec4 v_data;
+
+        mat4 joint_matrix(uint joint) {
+            int row = int(joint);
+            return mat4(texelFetch(joint_tex, ivec2(0, row), 0),
+                texelFetch(joint_tex, ivec2(1, row), 0),
+                texelFetch(joint_tex, ivec2(2, row), 0),
+                texelFetch(joint_tex, ivec2(3, row), 0));
+        }
+
+        vec4 morph_texel(int index) {
+            int width = textureSize(morph_tex, 0).x;
+            return texelFetch(morph_tex, ivec2(index % width, index / width), 0);
+        }
+
+        void main() {
+            vec3 morph_position = position;
+            vec3 morph_normal = normal;
+            for (int i = 0; i < morph_count; i++) {
+                float weight = texelFetch(morph_weight_tex, ivec2(i, gl_InstanceID), 0).r;
+                if (weight != 0.0) {
+                    int index = (i * vertex_count + gl_VertexID) * 2;
+                    morph_position += weight * morph_texel(index).xyz;
+                    morph_normal += weight * morph_texel(index + 1).xyz;
+                }
+            }
+
+            mat4 skin = weights.x * joint_matrix(joints.x) +
+                weights.y * joint_matrix(joints.y) +
+                weights.z * joint_matrix(joints.z) +
+                weights.w * joint_matrix(joints.w);
+
+            // vertices without any weights are left where they are
+            if (dot(weights, vec4(1.0)) <= 0.0) {
+                skin = mat4(1.0);
+            }
+
+            vec3 pos_skinned = (skin * vec4(morph_position, 1.0)).xyz;
+            vec3 pos_scaled = pos_skinned * world_scale;
+
+            vec3 temp = cross(world_rotation.xyz, pos_scaled) + world_rotation.w * pos_scaled;
+            vec3 pos_rotated = pos_scaled + 2.0 * cross(world_rotation.xyz, temp);
+
+            vec3 pos_final = pos_rotated + world_position;
+
+            gl_Position = vec4(pos_final, 1.0);
+
+            v_normal = normalize(mat3(skin) * morph_normal);
+            v_texture = texture;
+            v_tint = world_tint;
+            v_data = world_data;
+        }
+    ";
+}
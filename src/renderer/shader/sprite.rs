@@ -0,0 +1,44 @@
+/// OpenGL 3.3 shaders for batched 2d sprites
+pub mod gl330 {
+    /// Sprite vertex shader
+    pub const VERT: &str = "
+        #version 330
+
+        uniform vec2 resolution;
+
+        layout(location = 0) in vec2 position;
+        layout(location = 1) in vec2 texture;
+        layout(location = 2) in vec4 color;
+
+        out vec2 v_texture;
+        out vec4 v_color;
+
+        void main() {
+            // pixel coordinates from the top left to clip space
+            vec2 clip = vec2(
+                (position.x / resolution.x) * 2.0 - 1.0,
+                1.0 - (position.y / resolution.y) * 2.0
+            );
+            gl_Position = vec4(clip, 0.0, 1.0);
+
+            v_texture = texture;
+            v_color = color;
+        }
+    ";
+
+    /// Sprite fragment shader
+    pub const FRAG: &str = "
+        #version 330
+
+        uniform sampler2D tex;
+
+        in vec2 v_texture;
+        in vec4 v_color;
+
+        out vec4 frag_output;
+
+        void main() {
+            frag_output = v_color * texture(tex, v_texture);
+        }
+    ";
+}
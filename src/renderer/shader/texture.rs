@@ -13,6 +13,7 @@ pub mod gl330 {
         in vec3 g_normal;
         in vec3 g_pos;
         in vec2 g_texture;
+        in vec4 g_tint;
 
         out vec4 frag_output;
 
@@ -30,7 +31,7 @@ pub mod gl330 {
 
             float dist = abs(distance(cam_pos, g_pos)) / 80.0;
 
-            frag_output = texture(tex, g_texture) * vec4(vec3((0.6 * avg_lum) + (0.4 * dist)), 1.0);
+            frag_output = texture(tex, g_texture) * vec4(vec3((0.6 * avg_lum) + (0.4 * dist)), 1.0) * g_tint;
         }
     ";
 }
@@ -0,0 +1,395 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+use camera::look_rotation;
+use math::{Quat, Vec3};
+use renderer::lighting::DirectionalLight;
+use types::{Camera, RenderItem, TextItem, Transform};
+
+/// An item in the scene that can be parented to another, referred to by its
+/// index so items removed other than through Game must also be removed from
+/// the SceneGraph to keep the links on the right items
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub enum SceneNode {
+    /// An instance transform, the indices of the render item and the instance
+    Instance(usize, usize),
+    /// A text item, only the position and scale are inherited
+    Text(usize),
+    /// A camera
+    Camera(usize),
+    /// A directional light, only the rotation is inherited
+    Light(usize),
+}
+
+/// The link between a child and its parent
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct SceneLink {
+    /// The node the child is attached to
+    pub parent: SceneNode,
+    /// The transform of the child relative to the parent
+    pub local: Transform,
+}
+
+/// The items of the scene that nodes refer to
+pub struct SceneItems<'a, T: Default> {
+    /// The render items that instance nodes refer to
+    pub render_items: &'a mut [RenderItem<T>],
+    /// The text items that text nodes refer to
+    pub text_items: &'a mut [TextItem],
+    /// The cameras that camera nodes refer to
+    pub cams: &'a mut [Camera],
+    /// The directional lights that light nodes refer to
+    pub lights: &'a mut [DirectionalLight],
+}
+
+/// Parent/child relationships between the items of the scene, each child
+/// has a local transform and its world transform is written to the item
+/// when updated, nodes without a parent are left as they are
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct SceneGraph {
+    /// The parent and local transform of each child
+    links: BTreeMap<SceneNode, SceneLink>,
+}
+
+impl SceneGraph {
+    /// Create a new empty scene graph
+    pub fn new() -> SceneGraph {
+        Default::default()
+    }
+
+    /// Attach the child to the parent with a transform relative to the parent
+    pub fn set_parent(
+        &mut self,
+        child: SceneNode,
+        parent: SceneNode,
+        local: Transform,
+    ) -> Result<(), String> {
+        if self.is_ancestor(child, parent) {
+            return Err(format!(
+                "Attaching {:?} to {:?} would create a cycle",
+                child, parent
+            ));
+        }
+
+        self.links.insert(child, SceneLink { parent, local });
+        Ok(())
+    }
+
+    /// Attach the child to the parent, keeping its current world transform,
+    /// a child that is already attached is moved to the new parent
+    pub fn attach<T: Default>(
+        &mut self,
+        child: SceneNode,
+        parent: SceneNode,
+        items: &SceneItems<T>,
+    ) -> Result<(), String> {
+        let child_world = self
+            .world(child, items)
+            .ok_or_else(|| format!("{:?} does not exist", child))?;
+        let parent_world = self
+            .world(parent, items)
+            .ok_or_else(|| format!("{:?} does not exist", parent))?;
+
+        self.set_parent(
+            child,
+            parent,
+            relative_transform(&parent_world, &child_world),
+        )
+    }
+
+    /// Detach the child from its parent, leaving the item at its current world
+    /// transform, returns the link that was removed
+    pub fn detach<T: Default>(
+        &mut self,
+        child: SceneNode,
+        items: &mut SceneItems<T>,
+    ) -> Option<SceneLink> {
+        let world = self.world(child, items);
+        let link = self.links.remove(&child);
+
+        if let Some(world) = world {
+            write_transform(child, &world, items);
+        }
+
+        link
+    }
+
+    /// The parent of the node if it has one
+    pub fn parent(&self, node: SceneNode) -> Option<SceneNode> {
+        self.links.get(&node).map(|l| l.parent)
+    }
+
+    /// The nodes directly attached to the parent
+    pub fn children(&self, parent: SceneNode) -> Vec<SceneNode> {
+        self.links
+            .iter()
+            .filter(|(_, l)| l.parent == parent)
+            .map(|(c, _)| *c)
+            .collect()
+    }
+
+    /// Get a ref to the local transform of a child
+    pub fn local(&self, child: SceneNode) -> Option<&Transform> {
+        self.links.get(&child).map(|l| &l.local)
+    }
+
+    /// Get a mutable ref to the local transform of a child
+    pub fn local_mut(&mut self, child: SceneNode) -> Option<&mut Transform> {
+        self.links.get_mut(&child).map(|l| &mut l.local)
+    }
+
+    /// Calculate the world transform of the node
+    pub fn world<T: Default>(&self, node: SceneNode, items: &SceneItems<T>) -> Option<Transform> {
+        self.world_cached(node, items, &mut HashMap::new())
+    }
+
+    /// Write the world transform of every child to its item, returning
+    /// whether any lights were changed so their data can be regenerated
+    pub fn update<T: Default>(&self, items: &mut SceneItems<T>) -> bool {
+        let mut cache = HashMap::new();
+        let worlds = self
+            .links
+            .keys()
+            .filter_map(|c| self.world_cached(*c, items, &mut cache).map(|w| (*c, w)))
+            .collect::<Vec<_>>();
+
+        let mut lights_changed = false;
+        for (child, world) in worlds {
+            write_transform(child, &world, items);
+            if let SceneNode::Light(_) = child {
+                lights_changed = true;
+            }
+        }
+
+        lights_changed
+    }
+
+    /// Remove the links to and from the node after its item is removed, the
+    /// nodes after it are moved down to the index they are now at and its
+    /// children are left where they were last updated to
+    pub fn remove(&mut self, node: SceneNode) {
+        self.remap(|n| removed_node(n, node));
+    }
+
+    /// Remove the links to and from the instances of a render item after it
+    /// is removed, the instances of later render items are moved down an index
+    pub fn remove_render_item(&mut self, index: usize) {
+        self.remap(|n| match n {
+            SceneNode::Instance(i, j) => removed_index(i, index).map(|i| SceneNode::Instance(i, j)),
+            _ => Some(n),
+        });
+    }
+
+    /// Change the node of every link, dropping the links where either node is None
+    fn remap<F: Fn(SceneNode) -> Option<SceneNode>>(&mut self, f: F) {
+        self.links = self
+            .links
+            .iter()
+            .filter_map(|(child, link)| {
+                let child = f(*child)?;
+                let parent = f(link.parent)?;
+                Some((
+                    child,
+                    SceneLink {
+                        parent,
+                        local: link.local,
+                    },
+                ))
+            })
+            .collect();
+    }
+
+    /// Whether the ancestor is the node or one of its parents
+    fn is_ancestor(&self, ancestor: SceneNode, node: SceneNode) -> bool {
+        let mut current = Some(node);
+        while let Some(n) = current {
+            if n == ancestor {
+                return true;
+            }
+            current = self.parent(n);
+        }
+        false
+    }
+
+    /// Calculate the world transform of the node, reusing those already calculated
+    fn world_cached<T: Default>(
+        &self,
+        node: SceneNode,
+        items: &SceneItems<T>,
+        cache: &mut HashMap<SceneNode, Transform>,
+    ) -> Option<Transform> {
+        if let Some(world) = cache.get(&node) {
+            return Some(*world);
+        }
+
+        let world = match self.links.get(&node) {
+            Some(link) => {
+                combine_transforms(&self.world_cached(link.parent, items, cache)?, &link.local)
+            }
+            None => read_transform(node, items)?,
+        };
+
+        cache.insert(node, world);
+        Some(world)
+    }
+}
+
+/// Apply the local transform relative to the parent transform
+pub fn combine_transforms(parent: &Transform, local: &Transform) -> Transform {
+    let p_rot = Quat::from(parent.rot).normalize();
+    let p_scale = Vec3::from(parent.scale);
+
+    Transform {
+        pos: (Vec3::from(parent.pos) + p_rot * (p_scale * Vec3::from(local.pos))).into(),
+        rot: (p_rot * Quat::from(local.rot).normalize()).into(),
+        scale: (p_scale * Vec3::from(local.scale)).into(),
+        active: parent.active && local.active,
+        cull: local.cull,
+        tint: local.tint,
+        data: local.data,
+    }
+}
+
+/// The transform relative to the parent that gives the world transform
+pub fn relative_transform(parent: &Transform, world: &Transform) -> Transform {
+    let inv_rot = Quat::from(parent.rot).normalize().inverse();
+    let div = |a: f32, b: f32| if b != 0f32 { a / b } else { 0f32 };
+    let unscale = |v: Vec3| {
+        Vec3::new(
+            div(v.x, parent.scale.0),
+            div(v.y, parent.scale.1),
+            div(v.z, parent.scale.2),
+        )
+    };
+
+    Transform {
+        pos: unscale(inv_rot * (Vec3::from(world.pos) - Vec3::from(parent.pos))).into(),
+        rot: (inv_rot * Quat::from(world.rot).normalize()).into(),
+        scale: unscale(Vec3::from(world.scale)).into(),
+        active: world.active,
+        cull: world.cull,
+        tint: world.tint,
+        data: world.data,
+    }
+}
+
+/// The rotation of a camera from its pitch, yaw and roll
+pub fn camera_rotation(cam: &Camera) -> Quat {
+    let (pitch, yaw, roll) = cam.euler_rot;
+    Quat::from_axis_angle(Vec3::new(0f32, 1f32, 0f32), yaw)
+        * Quat::from_axis_angle(Vec3::new(1f32, 0f32, 0f32), pitch)
+        * Quat::from_axis_angle(Vec3::new(0f32, 0f32, 1f32), roll)
+}
+
+/// The pitch, yaw and roll of a camera with the rotation
+pub fn camera_euler(rot: Quat) -> (f32, f32, f32) {
+    let forward = rot * Vec3::new(0f32, 0f32, -1f32);
+    let right = rot * Vec3::new(1f32, 0f32, 0f32);
+    let (pitch, yaw) = look_rotation(forward.into());
+
+    // the roll is the angle between the right and the right with no roll
+    let flat_right = Vec3::new(yaw.cos(), 0f32, -yaw.sin());
+    let flat_up = flat_right.cross(forward);
+    let roll = right.dot(flat_up).atan2(right.dot(flat_right));
+
+    (pitch, yaw, roll)
+}
+
+/// The index after the item at removed is removed, None if it was the item
+fn removed_index(index: usize, removed: usize) -> Option<usize> {
+    match index.cmp(&removed) {
+        Ordering::Less => Some(index),
+        Ordering::Equal => None,
+        Ordering::Greater => Some(index - 1),
+    }
+}
+
+/// The node after the removed node's item is removed, None if it was the item
+fn removed_node(node: SceneNode, removed: SceneNode) -> Option<SceneNode> {
+    match (node, removed) {
+        (SceneNode::Instance(i, j), SceneNode::Instance(ri, rj)) if i == ri => {
+            removed_index(j, rj).map(|j| SceneNode::Instance(i, j))
+        }
+        (SceneNode::Text(i), SceneNode::Text(r)) => removed_index(i, r).map(SceneNode::Text),
+        (SceneNode::Camera(i), SceneNode::Camera(r)) => removed_index(i, r).map(SceneNode::Camera),
+        (SceneNode::Light(i), SceneNode::Light(r)) => removed_index(i, r).map(SceneNode::Light),
+        _ => Some(node),
+    }
+}
+
+/// Get the current transform of the item the node refers to
+fn read_transform<T: Default>(node: SceneNode, items: &SceneItems<T>) -> Option<Transform> {
+    match node {
+        SceneNode::Instance(i, j) => items
+            .render_items
+            .get(i)
+            .and_then(|r| r.instance_transforms.get(j))
+            .cloned(),
+        SceneNode::Text(i) => items.text_items.get(i).map(|t| Transform {
+            pos: t.pos,
+            scale: t.scale,
+            rot: Quat::identity().into(),
+            active: t.active,
+            ..Default::default()
+        }),
+        SceneNode::Camera(i) => items.cams.get(i).map(|c| Transform {
+            pos: c.pos,
+            rot: camera_rotation(c).into(),
+            ..Default::default()
+        }),
+        SceneNode::Light(i) => items.lights.get(i).map(|l| {
+            let dir = Vec3::from(l.dir);
+            let up = if dir.normalize().y.abs() > 0.99f32 {
+                Vec3::new(0f32, 0f32, 1f32)
+            } else {
+                Vec3::new(0f32, 1f32, 0f32)
+            };
+            let len = dir.length();
+            Transform {
+                rot: Quat::look_rotation(dir, up).into(),
+                scale: (len, len, len),
+                active: l.active,
+                ..Default::default()
+            }
+        }),
+    }
+}
+
+/// Set the transform of the item the node refers to
+fn write_transform<T: Default>(node: SceneNode, world: &Transform, items: &mut SceneItems<T>) {
+    match node {
+        SceneNode::Instance(i, j) => {
+            if let Some(t) = items
+                .render_items
+                .get_mut(i)
+                .and_then(|r| r.instance_transforms.get_mut(j))
+            {
+                // the tint and data belong to the instance rather than the node
+                *t = Transform {
+                    tint: t.tint,
+                    data: t.data,
+                    ..*world
+                };
+            }
+        }
+        SceneNode::Text(i) => {
+            if let Some(t) = items.text_items.get_mut(i) {
+                t.pos = world.pos;
+                t.scale = world.scale;
+                t.active = world.active;
+            }
+        }
+        SceneNode::Camera(i) => {
+            if let Some(c) = items.cams.get_mut(i) {
+                c.pos = world.pos;
+                c.euler_rot = camera_euler(world.rot.into());
+            }
+        }
+        SceneNode::Light(i) => {
+            if let Some(l) = items.lights.get_mut(i) {
+                l.dir = (Quat::from(world.rot) * Vec3::new(0f32, 0f32, -world.scale.2)).into();
+                l.active = world.active;
+            }
+        }
+    }
+}
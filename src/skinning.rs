@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use animation::{AnimationClip, AnimationPlayer};
+use math::{Mat4, Quat, Vec3};
+use types::{Matrix4, Transform};
+
+/// The most joints that can influence a single vertex
+pub const MAX_INFLUENCES: usize = 4;
+
+/// The joints that influence a vertex and by how much, matching the
+/// JOINTS_0/WEIGHTS_0 attributes of a glTF skin
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct SkinVertex {
+    /// The indices of the joints in the skeleton
+    pub joints: [u32; 4],
+    /// The weight of each joint, summing to 1
+    pub weights: [f32; 4],
+}
+implement_vertex!(SkinVertex, joints, weights);
+
+impl SkinVertex {
+    /// Create a skin vertex from (joint, weight) pairs, keeping the heaviest
+    /// influences and normalising their weights
+    pub fn new(influences: &[(u32, f32)]) -> SkinVertex {
+        let mut influences = influences
+            .iter()
+            .filter(|(_, w)| *w > 0f32)
+            .cloned()
+            .collect::<Vec<_>>();
+        influences.sort_by(|a, b| b.1.total_cmp(&a.1));
+        influences.truncate(MAX_INFLUENCES);
+
+        let total = influences.iter().map(|(_, w)| w).sum::<f32>();
+        let mut vertex = SkinVertex::default();
+        for (i, (joint, weight)) in influences.into_iter().enumerate() {
+            vertex.joints[i] = joint;
+            vertex.weights[i] = weight / total;
+        }
+        vertex
+    }
+}
+
+/// A joint of a skeleton
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct Joint {
+    /// The name of the joint, used by the joint tracks of clips
+    #[builder(setter(into))]
+    pub name: String,
+    /// The index of the parent joint, which must come before this one
+    pub parent: Option<usize>,
+    /// The local transform of the joint relative to its parent in the bind pose
+    pub bind: Transform,
+    /// The transform from model space into the space of the joint in the bind pose
+    pub inverse_bind: Matrix4,
+}
+
+impl Default for Joint {
+    fn default() -> Self {
+        Joint {
+            name: "joint".into(),
+            parent: None,
+            bind: Transform {
+                rot: (0f32, 0f32, 0f32, 1f32),
+                ..Default::default()
+            },
+            inverse_bind: Mat4::identity().into(),
+        }
+    }
+}
+
+/// The joints of a skinned mesh, parents always come before their children
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Skeleton {
+    /// The joints of the skeleton
+    joints: Vec<Joint>,
+}
+
+impl Skeleton {
+    /// Create a skeleton, failing if a joint's parent doesn't come before it
+    pub fn new(joints: Vec<Joint>) -> Result<Skeleton, String> {
+        for (i, joint) in joints.iter().enumerate() {
+            if let Some(parent) = joint.parent {
+                if parent >= i {
+                    return Err(format!(
+                        "The parent of joint {} must come before it",
+                        joint.name
+                    ));
+                }
+            }
+        }
+
+        Ok(Skeleton { joints })
+    }
+
+    /// Create a skeleton, computing the inverse bind matrices from the bind pose
+    pub fn from_bind_pose(joints: Vec<Joint>) -> Result<Skeleton, String> {
+        let mut skeleton = Skeleton::new(joints)?;
+        let worlds = skeleton.bind_pose().world_matrices(&skeleton);
+        for (joint, world) in skeleton.joints.iter_mut().zip(worlds) {
+            joint.inverse_bind = world.inverse().unwrap_or_else(Mat4::identity).into();
+        }
+        Ok(skeleton)
+    }
+
+    /// The joints of the skeleton
+    pub fn joints(&self) -> &[Joint] {
+        &self.joints
+    }
+
+    /// The number of joints
+    pub fn len(&self) -> usize {
+        self.joints.len()
+    }
+
+    /// Whether the skeleton has no joints
+    pub fn is_empty(&self) -> bool {
+        self.joints.is_empty()
+    }
+
+    /// The index of the joint with the name
+    pub fn joint_index(&self, name: &str) -> Option<usize> {
+        self.joints.iter().position(|j| j.name == name)
+    }
+
+    /// The pose of the skeleton with every joint at its bind transform
+    pub fn bind_pose(&self) -> Pose {
+        Pose {
+            locals: self.joints.iter().map(|j| j.bind).collect(),
+        }
+    }
+}
+
+/// The local transforms of the joints of a skeleton
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Pose {
+    /// The transform of each joint relative to its parent
+    pub locals: Vec<Transform>,
+}
+
+impl Pose {
+    /// Blend towards the other pose by the weight
+    pub fn blend(&mut self, other: &Pose, weight: f32) {
+        for (local, other) in self.locals.iter_mut().zip(other.locals.iter()) {
+            local.pos = Vec3::from(local.pos).lerp(other.pos.into(), weight).into();
+            local.rot = rotation(local.rot)
+                .slerp(rotation(other.rot), weight)
+                .into();
+            local.scale = Vec3::from(local.scale)
+                .lerp(other.scale.into(), weight)
+                .into();
+        }
+    }
+
+    /// The model space transform of each joint
+    pub fn world_matrices(&self, skeleton: &Skeleton) -> Vec<Mat4> {
+        let mut worlds: Vec<Mat4> = Vec::with_capacity(self.locals.len());
+        for (joint, local) in skeleton.joints.iter().zip(self.locals.iter()) {
+            let local = Mat4::from_trs(local.pos.into(), rotation(local.rot), local.scale.into());
+            let world = match joint.parent.and_then(|p| worlds.get(p)) {
+                Some(parent) => *parent * local,
+                None => local,
+            };
+            worlds.push(world);
+        }
+        worlds
+    }
+
+    /// The matrix for each joint that moves a vertex from the bind pose to this pose
+    pub fn joint_matrices(&self, skeleton: &Skeleton) -> Vec<Matrix4> {
+        self.world_matrices(skeleton)
+            .into_iter()
+            .zip(skeleton.joints.iter())
+            .map(|(world, joint)| (world * Mat4::from(joint.inverse_bind)).into())
+            .collect()
+    }
+}
+
+/// The rotation of a transform, a zero quaternion is treated as no rotation
+fn rotation(rot: (f32, f32, f32, f32)) -> Quat {
+    Quat::from(rot).normalize()
+}
+
+/// The id given to the next skin
+static NEXT_SKIN_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Get a new skin id
+fn next_skin_id() -> usize {
+    NEXT_SKIN_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Deforms the vertices of a render item by the joints of a skeleton, the
+/// pose is shared by all of the instances and the item is drawn with the
+/// skinned shader
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct Skin {
+    /// The skeleton deforming the mesh
+    pub skeleton: Skeleton,
+    /// The joint influences of each of the render item's vertices
+    vertices: Vec<SkinVertex>,
+    /// Plays the joint tracks of clips on the skeleton
+    pub player: AnimationPlayer,
+    /// Identifies the vertices so the renderer can keep them on the GPU,
+    /// shared by clones as the vertices can't change
+    #[serde(skip, default = "next_skin_id")]
+    id: usize,
+    /// The current pose of the skeleton
+    #[serde(skip)]
+    pose: Pose,
+    /// The joint matrices for the current pose
+    #[serde(skip)]
+    joint_matrices: Vec<Matrix4>,
+}
+
+impl Default for Skin {
+    fn default() -> Self {
+        Skin {
+            skeleton: Skeleton::default(),
+            vertices: Vec::new(),
+            player: AnimationPlayer::new(),
+            id: next_skin_id(),
+            pose: Pose::default(),
+            joint_matrices: Vec::new(),
+        }
+    }
+}
+
+impl Skin {
+    /// Create a skin in the bind pose, failing if a vertex is influenced by a
+    /// joint that isn't in the skeleton
+    pub fn new(skeleton: Skeleton, vertices: Vec<SkinVertex>) -> Result<Skin, String> {
+        for (i, vertex) in vertices.iter().enumerate() {
+            let missing = vertex
+                .joints
+                .iter()
+                .zip(vertex.weights.iter())
+                .find(|(j, w)| **w > 0f32 && **j as usize >= skeleton.len());
+            if let Some((joint, _)) = missing {
+                return Err(format!(
+                    "Vertex {} is influenced by joint {} but the skeleton has {} joints",
+                    i,
+                    joint,
+                    skeleton.len()
+                ));
+            }
+        }
+
+        let mut skin = Skin {
+            skeleton,
+            vertices,
+            ..Default::default()
+        };
+        skin.set_pose(skin.skeleton.bind_pose());
+        Ok(skin)
+    }
+
+    /// Advance the player and pose the skeleton from the bind pose
+    pub fn update(&mut self, delta: f32, clips: &HashMap<String, AnimationClip>) {
+        self.player.advance(delta, clips);
+
+        let mut pose = self.skeleton.bind_pose();
+        self.player.apply_pose(clips, &self.skeleton, &mut pose);
+        self.set_pose(pose);
+    }
+
+    /// The joint influences of each of the render item's vertices
+    pub fn vertices(&self) -> &[SkinVertex] {
+        &self.vertices
+    }
+
+    /// The id of the skin's vertices, used by the renderer to cache them
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Set the pose of the skeleton directly
+    pub fn set_pose(&mut self, pose: Pose) {
+        self.joint_matrices = pose.joint_matrices(&self.skeleton);
+        self.pose = pose;
+    }
+
+    /// The current pose of the skeleton
+    pub fn pose(&self) -> &Pose {
+        &self.pose
+    }
+
+    /// The joint matrices for the current pose, passed to the skinned shader
+    pub fn joint_matrices(&self) -> &[Matrix4] {
+        &self.joint_matrices
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use game::{Game, UpdateStatus};
+use imgui::Ui;
+use particles::ParticleEmitter;
+use scene::SceneGraph;
+use types::{RenderItem, SpriteItem, TextItem};
+
+/// A state of the game such as a menu, gameplay or pause screen, states are
+/// kept in a stack on the game and only the top state is updated
+pub trait State<T: Default> {
+    /// Called when the state is pushed onto the stack
+    fn on_enter(&mut self, _game: &mut Game<T>) {}
+    /// Called when the state is popped off of the stack
+    fn on_exit(&mut self, _game: &mut Game<T>) {}
+    /// Called when another state is pushed on top of this one
+    fn on_pause(&mut self, _game: &mut Game<T>) {}
+    /// Called when the state above this one is popped
+    fn on_resume(&mut self, _game: &mut Game<T>) {}
+    /// Update the state each frame, returning a status to request a transition
+    fn update(&mut self, game: &mut Game<T>) -> UpdateStatus;
+    /// Draw the ui of the state each frame
+    fn draw_ui(&mut self, _ui: &Ui) {}
+    /// Whether the state shares the items of the state below it, if false the
+    /// state starts with no render, text or sprite items, particle emitters or
+    /// scene links and those below are restored when it is popped,
+    /// the ecs world is always shared
+    fn shares_items(&self) -> bool {
+        true
+    }
+}
+
+/// Creates a new instance of a state when it is pushed
+pub type StateFactory<T> = Box<dyn FnMut() -> Box<dyn State<T>>>;
+
+/// The items of the states below a state that doesn't share them
+pub(crate) struct StashedItems<T: Default> {
+    /// The render items
+    pub render_items: Vec<RenderItem<T>>,
+    /// The text items
+    pub text_items: Vec<TextItem>,
+    /// The particle emitters
+    pub particle_emitters: Vec<ParticleEmitter>,
+    /// The sprite items
+    pub sprite_items: Vec<SpriteItem>,
+    /// The parent/child relationships
+    pub scene: SceneGraph,
+}
+
+/// A state in the stack
+pub(crate) struct StateEntry<T: Default> {
+    /// The name the state was registered with
+    pub name: String,
+    /// The state, taken out while it is being called
+    pub state: Option<Box<dyn State<T>>>,
+    /// The items of the states below if this one doesn't share them
+    pub stash: Option<StashedItems<T>>,
+}
+
+/// The registered states and the stack of active ones
+pub struct StateStack<T: Default> {
+    /// The factories for each registered state
+    factories: HashMap<String, StateFactory<T>>,
+    /// The active states, the last is the top
+    pub(crate) entries: Vec<StateEntry<T>>,
+    /// Transitions requested while a state was being called, applied once it
+    /// is back in the stack
+    pub(crate) deferred: Vec<UpdateStatus>,
+}
+
+impl<T: Default> Default for StateStack<T> {
+    fn default() -> Self {
+        StateStack {
+            factories: HashMap::new(),
+            entries: Vec::new(),
+            deferred: Vec::new(),
+        }
+    }
+}
+
+impl<T: Default> StateStack<T> {
+    /// Create a new empty state stack
+    pub fn new() -> StateStack<T> {
+        Default::default()
+    }
+
+    /// Register a state that can be pushed by its name
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: FnMut() -> Box<dyn State<T>> + 'static,
+    {
+        self.factories.insert(name.into(), Box::new(factory));
+    }
+
+    /// Whether a state has been registered with the name
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// The name of the top state
+    pub fn current(&self) -> Option<&str> {
+        self.entries.last().map(|e| e.name.as_str())
+    }
+
+    /// The names of the active states from the bottom to the top
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.iter().map(|e| e.name.as_str()).collect()
+    }
+
+    /// The number of active states
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there are no active states
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether a state is taken out of the stack while it is being called
+    pub(crate) fn in_callback(&self) -> bool {
+        self.entries.iter().any(|e| e.state.is_none())
+    }
+
+    /// Fail if there's no state registered with the name
+    pub(crate) fn check_registered(&self, name: &str) -> Result<(), String> {
+        if self.is_registered(name) {
+            Ok(())
+        } else {
+            Err(format!("No state registered with the name {}", name))
+        }
+    }
+
+    /// Create a new instance of the registered state
+    pub(crate) fn create(&mut self, name: &str) -> Result<Box<dyn State<T>>, String> {
+        self.factories
+            .get_mut(name)
+            .map(|f| f())
+            .ok_or_else(|| format!("No state registered with the name {}", name))
+    }
+
+    /// Draw the ui of the top state
+    pub(crate) fn draw_ui(&mut self, ui: &Ui) {
+        if let Some(state) = self.entries.last_mut().and_then(|e| e.state.as_mut()) {
+            state.draw_ui(ui);
+        }
+    }
+}
@@ -0,0 +1,918 @@
+use std::f32::consts::PI;
+
+use math::{Quat, Vec2, Vec3, Vec4};
+use renderer::lighting::DirectionalLight;
+use renderer::posteffect::PostShaderOptions;
+use types::{Camera, Quaternion, RenderItem, TextItem, Transform, Vector3};
+
+/// The curve used to ease a tween between its values
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub enum Easing {
+    /// Constant speed
+    #[default]
+    Linear,
+    /// Quadratic, accelerating from zero
+    QuadIn,
+    /// Quadratic, decelerating to zero
+    QuadOut,
+    /// Quadratic, accelerating then decelerating
+    QuadInOut,
+    /// Cubic, accelerating from zero
+    CubicIn,
+    /// Cubic, decelerating to zero
+    CubicOut,
+    /// Cubic, accelerating then decelerating
+    CubicInOut,
+    /// Sinusoidal, accelerating from zero
+    SineIn,
+    /// Sinusoidal, decelerating to zero
+    SineOut,
+    /// Sinusoidal, accelerating then decelerating
+    SineInOut,
+    /// Exponential, accelerating from zero
+    ExpoIn,
+    /// Exponential, decelerating to zero
+    ExpoOut,
+    /// Exponential, accelerating then decelerating
+    ExpoInOut,
+    /// Pulls back before moving forward
+    BackIn,
+    /// Overshoots the end before settling
+    BackOut,
+    /// Pulls back at the start and overshoots the end
+    BackInOut,
+    /// Springs away from the start
+    ElasticIn,
+    /// Springs into the end
+    ElasticOut,
+    /// Bounces away from the start
+    BounceIn,
+    /// Bounces into the end
+    BounceOut,
+    /// Bounces away from the start and into the end
+    BounceInOut,
+}
+
+impl Easing {
+    /// Ease the progress t from 0 to 1
+    pub fn ease(&self, t: f32) -> f32 {
+        let t = t.clamp(0f32, 1f32);
+        // constants for the back and elastic curves
+        let c1 = 1.701_58f32;
+        let c2 = c1 * 1.525f32;
+        let c3 = c1 + 1f32;
+        let c4 = (2f32 * PI) / 3f32;
+
+        match *self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1f32 - (1f32 - t).powi(2),
+            Easing::QuadInOut => {
+                if t < 0.5f32 {
+                    2f32 * t * t
+                } else {
+                    1f32 - (-2f32 * t + 2f32).powi(2) / 2f32
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1f32 - (1f32 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5f32 {
+                    4f32 * t * t * t
+                } else {
+                    1f32 - (-2f32 * t + 2f32).powi(3) / 2f32
+                }
+            }
+            Easing::SineIn => 1f32 - (t * PI / 2f32).cos(),
+            Easing::SineOut => (t * PI / 2f32).sin(),
+            Easing::SineInOut => -((PI * t).cos() - 1f32) / 2f32,
+            Easing::ExpoIn => {
+                if t == 0f32 {
+                    0f32
+                } else {
+                    2f32.powf(10f32 * t - 10f32)
+                }
+            }
+            Easing::ExpoOut => {
+                if t == 1f32 {
+                    1f32
+                } else {
+                    1f32 - 2f32.powf(-10f32 * t)
+                }
+            }
+            Easing::ExpoInOut => {
+                if t == 0f32 || t == 1f32 {
+                    t
+                } else if t < 0.5f32 {
+                    2f32.powf(20f32 * t - 10f32) / 2f32
+                } else {
+                    (2f32 - 2f32.powf(-20f32 * t + 10f32)) / 2f32
+                }
+            }
+            Easing::BackIn => c3 * t * t * t - c1 * t * t,
+            Easing::BackOut => 1f32 + c3 * (t - 1f32).powi(3) + c1 * (t - 1f32).powi(2),
+            Easing::BackInOut => {
+                if t < 0.5f32 {
+                    ((2f32 * t).powi(2) * ((c2 + 1f32) * 2f32 * t - c2)) / 2f32
+                } else {
+                    ((2f32 * t - 2f32).powi(2) * ((c2 + 1f32) * (t * 2f32 - 2f32) + c2) + 2f32)
+                        / 2f32
+                }
+            }
+            Easing::ElasticIn => {
+                if t == 0f32 || t == 1f32 {
+                    t
+                } else {
+                    -(2f32.powf(10f32 * t - 10f32)) * ((t * 10f32 - 10.75f32) * c4).sin()
+                }
+            }
+            Easing::ElasticOut => {
+                if t == 0f32 || t == 1f32 {
+                    t
+                } else {
+                    2f32.powf(-10f32 * t) * ((t * 10f32 - 0.75f32) * c4).sin() + 1f32
+                }
+            }
+            Easing::BounceIn => 1f32 - bounce_out(1f32 - t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => {
+                if t < 0.5f32 {
+                    (1f32 - bounce_out(1f32 - 2f32 * t)) / 2f32
+                } else {
+                    (1f32 + bounce_out(2f32 * t - 1f32)) / 2f32
+                }
+            }
+        }
+    }
+}
+
+/// The bounce out curve that the other bounce curves are made from
+fn bounce_out(t: f32) -> f32 {
+    let n1 = 7.5625f32;
+    let d1 = 2.75f32;
+
+    if t < 1f32 / d1 {
+        n1 * t * t
+    } else if t < 2f32 / d1 {
+        let t = t - 1.5f32 / d1;
+        n1 * t * t + 0.75f32
+    } else if t < 2.5f32 / d1 {
+        let t = t - 2.25f32 / d1;
+        n1 * t * t + 0.9375f32
+    } else {
+        let t = t - 2.625f32 / d1;
+        n1 * t * t + 0.984_375f32
+    }
+}
+
+/// A value that can be tweened
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum TweenValue {
+    /// A single value
+    Float(f32),
+    /// Two values
+    Vec2((f32, f32)),
+    /// Three values such as a position or scale
+    Vec3(Vector3),
+    /// Four values such as a colour
+    Vec4((f32, f32, f32, f32)),
+    /// A rotation, interpolated along the shortest arc
+    Rotation(Quaternion),
+}
+
+impl Default for TweenValue {
+    fn default() -> Self {
+        TweenValue::Float(0f32)
+    }
+}
+
+impl TweenValue {
+    /// Interpolate between the values, if they are different kinds the
+    /// other value is returned
+    pub fn lerp(&self, other: &TweenValue, t: f32) -> TweenValue {
+        match (*self, *other) {
+            (TweenValue::Float(a), TweenValue::Float(b)) => TweenValue::Float(a + (b - a) * t),
+            (TweenValue::Vec2(a), TweenValue::Vec2(b)) => {
+                TweenValue::Vec2(Vec2::from(a).lerp(Vec2::from(b), t).into())
+            }
+            (TweenValue::Vec3(a), TweenValue::Vec3(b)) => {
+                TweenValue::Vec3(Vec3::from(a).lerp(Vec3::from(b), t).into())
+            }
+            (TweenValue::Vec4(a), TweenValue::Vec4(b)) => {
+                TweenValue::Vec4(Vec4::from(a).lerp(Vec4::from(b), t).into())
+            }
+            (TweenValue::Rotation(a), TweenValue::Rotation(b)) => {
+                TweenValue::Rotation(Quat::from(a).slerp(Quat::from(b), t).into())
+            }
+            _ => *other,
+        }
+    }
+}
+
+/// The fields of the post shader options that can be tweened
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum PostShaderField {
+    /// The offset for the chromatic aberration
+    ChromOffset,
+    /// The mix amount for the chromatic aberration
+    ChromAmt,
+    /// The amount of blur
+    BlurAmt,
+    /// The radius of the blur
+    BlurRadius,
+    /// The weight of the blur
+    BlurWeight,
+    /// Bokeh focal depth
+    BokehFocalDepth,
+    /// Bokeh focal width
+    BokehFocalWidth,
+    /// Colour grading, a Vec4
+    ColorOffset,
+    /// Noise
+    Noise,
+    /// Scanline
+    Scanline,
+}
+
+/// The value a tween animates, items are referred to by index
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum TweenTarget {
+    /// The position of a render item instance, a Vec3
+    InstancePos(usize, usize),
+    /// The rotation of a render item instance, a Rotation
+    InstanceRot(usize, usize),
+    /// The scale of a render item instance, a Vec3
+    InstanceScale(usize, usize),
+    /// The tint of a render item instance, a Vec4
+    InstanceTint(usize, usize),
+    /// The weight of a morph target of a render item instance, a Float
+    MorphWeight(usize, usize, usize),
+    /// The position of a text item, a Vec3
+    TextPos(usize),
+    /// The colour of a text item, a Vec4
+    TextColor(usize),
+    /// The scale of a text item, a Vec3
+    TextScale(usize),
+    /// The position of a camera, a Vec3
+    CameraPos(usize),
+    /// The euler rotation of a camera, a Vec3
+    CameraRot(usize),
+    /// The direction of a directional light, a Vec3
+    LightDir(usize),
+    /// A field of the post shader options
+    PostShader(PostShaderField),
+}
+
+impl Default for TweenTarget {
+    fn default() -> Self {
+        TweenTarget::CameraPos(0)
+    }
+}
+
+/// The items that tweens can animate
+pub struct TweenItems<'a, T: Default> {
+    /// The render items
+    pub render_items: &'a mut [RenderItem<T>],
+    /// The text items
+    pub text_items: &'a mut [TextItem],
+    /// The cameras
+    pub cams: &'a mut [Camera],
+    /// The directional lights
+    pub lights: &'a mut [DirectionalLight],
+    /// The options for the default post shader
+    pub post_shader_options: &'a mut PostShaderOptions,
+    /// Set when a light is changed so the lighting can be regenerated
+    pub lights_changed: bool,
+}
+
+impl<'a, T: Default> TweenItems<'a, T> {
+    /// Get the current value of the target, None if the item doesn't exist
+    pub fn get(&self, target: &TweenTarget) -> Option<TweenValue> {
+        match *target {
+            TweenTarget::InstancePos(i, j) => self.instance(i, j).map(|t| TweenValue::Vec3(t.pos)),
+            TweenTarget::InstanceRot(i, j) => {
+                self.instance(i, j).map(|t| TweenValue::Rotation(t.rot))
+            }
+            TweenTarget::InstanceScale(i, j) => {
+                self.instance(i, j).map(|t| TweenValue::Vec3(t.scale))
+            }
+            TweenTarget::InstanceTint(i, j) => {
+                self.instance(i, j).map(|t| TweenValue::Vec4(t.tint))
+            }
+            TweenTarget::MorphWeight(i, j, t) => self
+                .render_items
+                .get(i)
+                .and_then(|r| r.morphs.as_ref())
+                .filter(|m| t < m.targets().len())
+                .map(|m| TweenValue::Float(m.weight(j, t))),
+            TweenTarget::TextPos(i) => self.text_items.get(i).map(|t| TweenValue::Vec3(t.pos)),
+            TweenTarget::TextColor(i) => self.text_items.get(i).map(|t| TweenValue::Vec4(t.color)),
+            TweenTarget::TextScale(i) => self.text_items.get(i).map(|t| TweenValue::Vec3(t.scale)),
+            TweenTarget::CameraPos(i) => self.cams.get(i).map(|c| TweenValue::Vec3(c.pos)),
+            TweenTarget::CameraRot(i) => self.cams.get(i).map(|c| TweenValue::Vec3(c.euler_rot)),
+            TweenTarget::LightDir(i) => self.lights.get(i).map(|l| TweenValue::Vec3(l.dir)),
+            TweenTarget::PostShader(field) => {
+                let options = &*self.post_shader_options;
+                Some(match field {
+                    PostShaderField::ChromOffset => TweenValue::Float(options.chrom_offset),
+                    PostShaderField::ChromAmt => TweenValue::Float(options.chrom_amt),
+                    PostShaderField::BlurAmt => TweenValue::Float(options.blur_amt),
+                    PostShaderField::BlurRadius => TweenValue::Float(options.blur_radius),
+                    PostShaderField::BlurWeight => TweenValue::Float(options.blur_weight),
+                    PostShaderField::BokehFocalDepth => {
+                        TweenValue::Float(options.bokeh_focal_depth)
+                    }
+                    PostShaderField::BokehFocalWidth => {
+                        TweenValue::Float(options.bokeh_focal_width)
+                    }
+                    PostShaderField::ColorOffset => TweenValue::Vec4(options.color_offset),
+                    PostShaderField::Noise => TweenValue::Float(options.noise),
+                    PostShaderField::Scanline => TweenValue::Float(options.scanline),
+                })
+            }
+        }
+    }
+
+    /// Set the target to the value, returning false if the item doesn't exist
+    /// or the value is the wrong kind for the target
+    pub fn set(&mut self, target: &TweenTarget, value: TweenValue) -> bool {
+        match (*target, value) {
+            (TweenTarget::InstancePos(i, j), TweenValue::Vec3(v)) => {
+                self.instance_mut(i, j).map(|t| t.pos = v).is_some()
+            }
+            (TweenTarget::InstanceRot(i, j), TweenValue::Rotation(v)) => {
+                self.instance_mut(i, j).map(|t| t.rot = v).is_some()
+            }
+            (TweenTarget::InstanceScale(i, j), TweenValue::Vec3(v)) => {
+                self.instance_mut(i, j).map(|t| t.scale = v).is_some()
+            }
+            (TweenTarget::InstanceTint(i, j), TweenValue::Vec4(v)) => {
+                self.instance_mut(i, j).map(|t| t.tint = v).is_some()
+            }
+            (TweenTarget::MorphWeight(i, j, t), TweenValue::Float(v)) => self
+                .render_items
+                .get_mut(i)
+                .and_then(|r| r.morphs.as_mut())
+                .is_some_and(|m| m.set_weight(j, t, v)),
+            (TweenTarget::TextPos(i), TweenValue::Vec3(v)) => {
+                self.text_items.get_mut(i).map(|t| t.pos = v).is_some()
+            }
+            (TweenTarget::TextColor(i), TweenValue::Vec4(v)) => {
+                self.text_items.get_mut(i).map(|t| t.color = v).is_some()
+            }
+            (TweenTarget::TextScale(i), TweenValue::Vec3(v)) => {
+                self.text_items.get_mut(i).map(|t| t.scale = v).is_some()
+            }
+            (TweenTarget::CameraPos(i), TweenValue::Vec3(v)) => {
+                self.cams.get_mut(i).map(|c| c.pos = v).is_some()
+            }
+            (TweenTarget::CameraRot(i), TweenValue::Vec3(v)) => {
+                self.cams.get_mut(i).map(|c| c.euler_rot = v).is_some()
+            }
+            (TweenTarget::LightDir(i), TweenValue::Vec3(v)) => {
+                let set = self.lights.get_mut(i).map(|l| l.dir = v).is_some();
+                self.lights_changed |= set;
+                set
+            }
+            (TweenTarget::PostShader(PostShaderField::ColorOffset), TweenValue::Vec4(v)) => {
+                self.post_shader_options.color_offset = v;
+                true
+            }
+            (TweenTarget::PostShader(field), TweenValue::Float(v)) => {
+                let options = &mut *self.post_shader_options;
+                match field {
+                    PostShaderField::ChromOffset => options.chrom_offset = v,
+                    PostShaderField::ChromAmt => options.chrom_amt = v,
+                    PostShaderField::BlurAmt => options.blur_amt = v,
+                    PostShaderField::BlurRadius => options.blur_radius = v,
+                    PostShaderField::BlurWeight => options.blur_weight = v,
+                    PostShaderField::BokehFocalDepth => options.bokeh_focal_depth = v,
+                    PostShaderField::BokehFocalWidth => options.bokeh_focal_width = v,
+                    PostShaderField::Noise => options.noise = v,
+                    PostShaderField::Scanline => options.scanline = v,
+                    PostShaderField::ColorOffset => return false,
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Get an instance transform of a render item
+    fn instance(&self, i: usize, j: usize) -> Option<&Transform> {
+        self.render_items
+            .get(i)
+            .and_then(|r| r.instance_transforms.get(j))
+    }
+
+    /// Get a mutable instance transform of a render item
+    fn instance_mut(&mut self, i: usize, j: usize) -> Option<&mut Transform> {
+        self.render_items
+            .get_mut(i)
+            .and_then(|r| r.instance_transforms.get_mut(j))
+    }
+}
+
+/// How many times a tween or group is played
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub enum Repeat {
+    /// Play once
+    #[default]
+    Once,
+    /// Play the number of times
+    Times(u32),
+    /// Play until stopped
+    Forever,
+}
+
+impl Repeat {
+    /// Whether playing should stop after the number of plays
+    fn is_done(&self, plays: u32) -> bool {
+        match *self {
+            Repeat::Once => plays >= 1,
+            Repeat::Times(n) => plays >= n,
+            Repeat::Forever => false,
+        }
+    }
+}
+
+/// Animates a target from one value to another over time
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct Tween {
+    /// The value being animated
+    pub target: TweenTarget,
+    /// The value to start from, the current value when started if None
+    pub from: Option<TweenValue>,
+    /// The value to end at
+    pub to: TweenValue,
+    /// How long one play takes in seconds
+    pub duration: f32,
+    /// The curve used between the values
+    pub easing: Easing,
+    /// How many times the tween is played
+    pub repeat: Repeat,
+    /// Whether every other play goes back from the end to the start
+    pub yoyo: bool,
+    /// The value started from once resolved
+    #[builder(setter(skip))]
+    #[serde(skip)]
+    start: Option<TweenValue>,
+    /// The time into the current play
+    #[builder(setter(skip))]
+    #[serde(skip)]
+    elapsed: f32,
+    /// The number of finished plays
+    #[builder(setter(skip))]
+    #[serde(skip)]
+    plays: u32,
+    /// Whether the tween has finished
+    #[builder(setter(skip))]
+    #[serde(skip)]
+    finished: bool,
+}
+
+impl Default for Tween {
+    fn default() -> Self {
+        Tween {
+            target: TweenTarget::default(),
+            from: None,
+            to: TweenValue::default(),
+            duration: 1f32,
+            easing: Easing::Linear,
+            repeat: Repeat::Once,
+            yoyo: false,
+            start: None,
+            elapsed: 0f32,
+            plays: 0,
+            finished: false,
+        }
+    }
+}
+
+impl Tween {
+    /// Create a tween from the current value of the target
+    pub fn new(target: TweenTarget, to: TweenValue, duration: f32, easing: Easing) -> Tween {
+        Tween {
+            target,
+            to,
+            duration,
+            easing,
+            ..Default::default()
+        }
+    }
+
+    /// Whether the tween has finished
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Advance the tween, returning the time left over once it finishes
+    pub fn advance<T: Default>(&mut self, delta: f32, items: &mut TweenItems<T>) -> f32 {
+        if self.finished {
+            return delta;
+        }
+
+        if self.start.is_none() {
+            self.start = self.from.or_else(|| items.get(&self.target));
+        }
+        let start = match self.start {
+            Some(start) => start,
+            None => {
+                // the target doesn't exist
+                self.finished = true;
+                return delta;
+            }
+        };
+
+        if self.duration <= 0f32 {
+            self.finished = true;
+            items.set(&self.target, self.to);
+            return delta;
+        }
+
+        self.elapsed += delta;
+        while self.elapsed >= self.duration {
+            self.plays += 1;
+            if self.repeat.is_done(self.plays) {
+                self.finished = true;
+                let leftover = self.elapsed - self.duration;
+                let end = if self.reversed(self.plays - 1) {
+                    start
+                } else {
+                    self.to
+                };
+                items.set(&self.target, end);
+                return leftover;
+            }
+            self.elapsed -= self.duration;
+        }
+
+        let mut t = self.elapsed / self.duration;
+        if self.reversed(self.plays) {
+            t = 1f32 - t;
+        }
+        items.set(&self.target, start.lerp(&self.to, self.easing.ease(t)));
+        0f32
+    }
+
+    /// Restart the tween, keeping the value it started from
+    pub fn reset(&mut self) {
+        self.elapsed = 0f32;
+        self.plays = 0;
+        self.finished = false;
+    }
+
+    /// Whether the play goes back from the end to the start
+    fn reversed(&self, play: u32) -> bool {
+        self.yoyo && play % 2 == 1
+    }
+}
+
+/// Tweens arranged into sequences and parallel groups
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum TweenNode {
+    /// A single tween
+    Tween(Tween),
+    /// Wait for the duration, elapsed
+    Wait(f32, f32),
+    /// Play the nodes one after another, the index of the current node
+    Sequence(Vec<TweenNode>, usize),
+    /// Play the nodes at the same time, finishing when all have
+    Parallel(Vec<TweenNode>),
+    /// Play the node repeatedly, the number of finished plays
+    Looped(Box<TweenNode>, Repeat, u32),
+}
+
+impl From<Tween> for TweenNode {
+    fn from(tween: Tween) -> Self {
+        TweenNode::Tween(tween)
+    }
+}
+
+impl TweenNode {
+    /// Wait for the duration in seconds
+    pub fn wait(duration: f32) -> TweenNode {
+        TweenNode::Wait(duration, 0f32)
+    }
+
+    /// Play the nodes one after another
+    pub fn sequence(nodes: Vec<TweenNode>) -> TweenNode {
+        TweenNode::Sequence(nodes, 0)
+    }
+
+    /// Play the nodes at the same time
+    pub fn parallel(nodes: Vec<TweenNode>) -> TweenNode {
+        TweenNode::Parallel(nodes)
+    }
+
+    /// Play the node repeatedly
+    pub fn looped(node: TweenNode, repeat: Repeat) -> TweenNode {
+        TweenNode::Looped(Box::new(node), repeat, 0)
+    }
+
+    /// Whether the node has finished
+    pub fn is_finished(&self) -> bool {
+        match *self {
+            TweenNode::Tween(ref tween) => tween.is_finished(),
+            TweenNode::Wait(duration, elapsed) => elapsed >= duration,
+            TweenNode::Sequence(ref nodes, current) => current >= nodes.len(),
+            TweenNode::Parallel(ref nodes) => nodes.iter().all(|n| n.is_finished()),
+            TweenNode::Looped(_, repeat, plays) => repeat.is_done(plays),
+        }
+    }
+
+    /// Advance the node, returning the time left over once it finishes
+    pub fn advance<T: Default>(&mut self, delta: f32, items: &mut TweenItems<T>) -> f32 {
+        match *self {
+            TweenNode::Tween(ref mut tween) => tween.advance(delta, items),
+            TweenNode::Wait(duration, ref mut elapsed) => {
+                let left = (duration - *elapsed).max(0f32);
+                *elapsed += delta.min(left);
+                (delta - left).max(0f32)
+            }
+            TweenNode::Sequence(ref mut nodes, ref mut current) => {
+                let mut delta = delta;
+                while *current < nodes.len() {
+                    delta = nodes[*current].advance(delta, items);
+                    if !nodes[*current].is_finished() {
+                        return 0f32;
+                    }
+                    *current += 1;
+                }
+                delta
+            }
+            TweenNode::Parallel(ref mut nodes) => nodes
+                .iter_mut()
+                .filter(|n| !n.is_finished())
+                .map(|n| n.advance(delta, items))
+                .fold(delta, f32::min),
+            TweenNode::Looped(ref mut node, repeat, ref mut plays) => {
+                let mut delta = delta;
+                while !repeat.is_done(*plays) {
+                    let before = delta;
+                    delta = node.advance(delta, items);
+                    if !node.is_finished() {
+                        return 0f32;
+                    }
+                    *plays += 1;
+                    if repeat.is_done(*plays) {
+                        break;
+                    }
+                    node.reset();
+                    // stop a node that takes no time from looping forever
+                    if delta >= before {
+                        return 0f32;
+                    }
+                }
+                delta
+            }
+        }
+    }
+
+    /// Restart the node and all of its children
+    pub fn reset(&mut self) {
+        match *self {
+            TweenNode::Tween(ref mut tween) => tween.reset(),
+            TweenNode::Wait(_, ref mut elapsed) => *elapsed = 0f32,
+            TweenNode::Sequence(ref mut nodes, ref mut current) => {
+                *current = 0;
+                nodes.iter_mut().for_each(|n| n.reset());
+            }
+            TweenNode::Parallel(ref mut nodes) => nodes.iter_mut().for_each(|n| n.reset()),
+            TweenNode::Looped(ref mut node, _, ref mut plays) => {
+                *plays = 0;
+                node.reset();
+            }
+        }
+    }
+}
+
+/// A timer that fires after its duration, optionally repeating
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct Timer {
+    /// The time between firing in seconds
+    pub duration: f32,
+    /// Whether the timer restarts after firing
+    pub repeating: bool,
+    /// Whether the timer is paused
+    pub paused: bool,
+    /// The time since the timer started or last fired
+    #[builder(setter(skip))]
+    #[serde(skip)]
+    elapsed: f32,
+    /// The number of times the timer fired in the last update
+    #[builder(setter(skip))]
+    #[serde(skip)]
+    fired: u32,
+    /// Whether a one-shot timer has fired
+    #[builder(setter(skip))]
+    #[serde(skip)]
+    finished: bool,
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Timer {
+            duration: 1f32,
+            repeating: false,
+            paused: false,
+            elapsed: 0f32,
+            fired: 0,
+            finished: false,
+        }
+    }
+}
+
+impl Timer {
+    /// Create a timer that fires once after the duration
+    pub fn once(duration: f32) -> Timer {
+        Timer {
+            duration,
+            ..Default::default()
+        }
+    }
+
+    /// Create a timer that fires every duration
+    pub fn repeating(duration: f32) -> Timer {
+        Timer {
+            duration,
+            repeating: true,
+            ..Default::default()
+        }
+    }
+
+    /// Advance the timer, returning the number of times it fired
+    pub fn update(&mut self, delta: f32) -> u32 {
+        self.fired = 0;
+        if self.paused || self.finished {
+            return 0;
+        }
+
+        self.elapsed += delta;
+        if self.duration <= 0f32 {
+            // fire once per update rather than endlessly
+            self.fired = 1;
+            self.elapsed = 0f32;
+            self.finished = !self.repeating;
+        } else {
+            while self.elapsed >= self.duration && !self.finished {
+                self.fired += 1;
+                self.elapsed -= self.duration;
+                self.finished = !self.repeating;
+            }
+        }
+        self.fired
+    }
+
+    /// Whether the timer fired in the last update
+    pub fn just_fired(&self) -> bool {
+        self.fired > 0
+    }
+
+    /// Whether a one-shot timer has fired
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The progress towards firing from 0 to 1
+    pub fn progress(&self) -> f32 {
+        if self.finished || self.duration <= 0f32 {
+            1f32
+        } else {
+            self.elapsed / self.duration
+        }
+    }
+
+    /// The time until the timer fires
+    pub fn remaining(&self) -> f32 {
+        if self.finished {
+            0f32
+        } else {
+            (self.duration - self.elapsed).max(0f32)
+        }
+    }
+
+    /// Restart the timer
+    pub fn reset(&mut self) {
+        self.elapsed = 0f32;
+        self.fired = 0;
+        self.finished = false;
+    }
+}
+
+/// The handle to a playing tween
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct TweenId(usize);
+
+/// The handle to a running timer
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+pub struct TimerId(usize);
+
+/// The tweens that finished and timers that fired in an update
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TweenerUpdate {
+    /// The tweens that finished, they are no longer playing
+    pub finished: Vec<TweenId>,
+    /// The timers that fired
+    pub fired: Vec<TimerId>,
+}
+
+/// The playing tweens and running timers
+#[derive(Default)]
+pub struct Tweener {
+    /// The playing tweens
+    tweens: Vec<(TweenId, TweenNode)>,
+    /// The running timers
+    timers: Vec<(TimerId, Timer)>,
+    /// The id given to the next tween or timer
+    next_id: usize,
+}
+
+impl Tweener {
+    /// Create a new tweener with nothing playing
+    pub fn new() -> Tweener {
+        Default::default()
+    }
+
+    /// Start playing a tween or group of tweens
+    pub fn play<N: Into<TweenNode>>(&mut self, node: N) -> TweenId {
+        self.next_id += 1;
+        let id = TweenId(self.next_id);
+        self.tweens.push((id, node.into()));
+        id
+    }
+
+    /// Stop a tween, leaving its target where it is, returning whether it was playing
+    pub fn stop(&mut self, id: TweenId) -> bool {
+        let len = self.tweens.len();
+        self.tweens.retain(|(i, _)| *i != id);
+        self.tweens.len() != len
+    }
+
+    /// Whether the tween is still playing
+    pub fn is_playing(&self, id: TweenId) -> bool {
+        self.tweens.iter().any(|(i, _)| *i == id)
+    }
+
+    /// The number of playing tweens
+    pub fn playing(&self) -> usize {
+        self.tweens.len()
+    }
+
+    /// Start a timer
+    pub fn start_timer(&mut self, timer: Timer) -> TimerId {
+        self.next_id += 1;
+        let id = TimerId(self.next_id);
+        self.timers.push((id, timer));
+        id
+    }
+
+    /// Get a timer, one-shot timers are removed the update after they fire
+    pub fn timer(&self, id: TimerId) -> Option<&Timer> {
+        self.timers.iter().find(|(i, _)| *i == id).map(|(_, t)| t)
+    }
+
+    /// Get a mutable timer
+    pub fn timer_mut(&mut self, id: TimerId) -> Option<&mut Timer> {
+        self.timers
+            .iter_mut()
+            .find(|(i, _)| *i == id)
+            .map(|(_, t)| t)
+    }
+
+    /// Whether the timer fired in the last update
+    pub fn fired(&self, id: TimerId) -> bool {
+        self.timer(id).is_some_and(|t| t.just_fired())
+    }
+
+    /// Cancel a timer, returning whether it was running
+    pub fn cancel_timer(&mut self, id: TimerId) -> bool {
+        let len = self.timers.len();
+        self.timers.retain(|(i, _)| *i != id);
+        self.timers.len() != len
+    }
+
+    /// Advance the tweens and timers by the delta
+    pub fn update<T: Default>(&mut self, delta: f32, items: &mut TweenItems<T>) -> TweenerUpdate {
+        let mut update = TweenerUpdate::default();
+
+        self.timers.retain(|(_, t)| !t.is_finished());
+        for (id, timer) in self.timers.iter_mut() {
+            if timer.update(delta) > 0 {
+                update.fired.push(*id);
+            }
+        }
+
+        for (id, node) in self.tweens.iter_mut() {
+            node.advance(delta, items);
+            if node.is_finished() {
+                update.finished.push(*id);
+            }
+        }
+        self.tweens.retain(|(_, n)| !n.is_finished());
+
+        update
+    }
+}
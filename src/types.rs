@@ -1,3 +1,7 @@
+use lod::Lods;
+use morph::Morphs;
+use skinning::Skin;
+
 /// type definition for a Vector3
 pub type Vector3 = (f32, f32, f32);
 
@@ -20,7 +24,7 @@ pub struct Vertex {
 implement_vertex!(Vertex, position, normal, texture);
 
 /// struct for handling transform data
-#[derive(Builder, Copy, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Builder, Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[builder(default)]
 pub struct Transform {
     /// The position of the transform
@@ -33,6 +37,10 @@ pub struct Transform {
     pub active: bool,
     /// Whether the transform is frustum culled
     pub cull: bool,
+    /// The colour the instance is tinted with
+    pub tint: (f32, f32, f32, f32),
+    /// Custom data passed to the shaders with the instance
+    pub data: [f32; 4],
 }
 
 impl Default for Transform {
@@ -43,6 +51,8 @@ impl Default for Transform {
             scale: (1f32, 1f32, 1f32),
             active: true,
             cull: true,
+            tint: (1f32, 1f32, 1f32, 1f32),
+            data: [0f32; 4],
         }
     }
 }
@@ -61,12 +71,12 @@ pub enum PhysicsType {
 }
 
 /// Default RenderItem utype
-#[derive(Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Default)]
 pub struct DefaultTag;
 
 /// struct for abstracting items to be sent to render
 #[derive(Builder, Clone, Serialize, Deserialize, PartialEq)]
-#[builder(default)]
+#[builder(default, build_fn(validate = "Self::validate"))]
 pub struct RenderItem<T: Default> {
     /// The vertices representing this items mesh
     pub vertices: Vec<Vertex>,
@@ -83,6 +93,14 @@ pub struct RenderItem<T: Default> {
     pub name: String,
     /// Tag Type for grouping similar items
     pub tag: T,
+    /// Any additional tags the item is grouped under
+    pub tags: Vec<T>,
+    /// The skin deforming the vertices by a skeleton
+    pub skin: Option<Skin>,
+    /// The morph targets and their weights for each instance
+    pub morphs: Option<Morphs>,
+    /// The lower detail meshes drawn in place of the vertices further away
+    pub lods: Option<Lods>,
 }
 
 impl<T: Default> Default for RenderItem<T> {
@@ -95,12 +113,75 @@ impl<T: Default> Default for RenderItem<T> {
             physics_type: PhysicsType::None,
             name: "ri".into(),
             tag: Default::default(),
+            tags: Vec::new(),
+            skin: None,
+            morphs: None,
+            lods: None,
         }
     }
 }
 
+impl<T: Clone + Default> RenderItemBuilder<T> {
+    /// Check the skin and morph targets have data for each of the vertices
+    fn validate(&self) -> Result<(), String> {
+        let vertex_count = self.vertices.as_ref().map_or(0, |v| v.len());
+        if let Some(Some(ref skin)) = self.skin {
+            if skin.vertices().len() != vertex_count {
+                return Err(format!(
+                    "The skin has {} vertices but the item has {}",
+                    skin.vertices().len(),
+                    vertex_count
+                ));
+            }
+        }
+        if let Some(Some(ref morphs)) = self.morphs {
+            if !morphs.matches(vertex_count) {
+                return Err(format!(
+                    "The morph targets don't have deltas for the item's {} vertices",
+                    vertex_count
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 unsafe impl<T: Default> Send for RenderItem<T> {}
 
+/// How the colour of a material is combined with the colour behind it
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub enum BlendMode {
+    /// Replaces the colour behind it, drawn front to back
+    #[default]
+    Opaque,
+    /// Blended over the colour behind it by its alpha, drawn back to front
+    Alpha,
+    /// Added to the colour behind it, drawn back to front
+    Additive,
+    /// Multiplies the colour behind it, drawn back to front
+    Multiply,
+}
+
+impl BlendMode {
+    /// Whether the material shows what is behind it and has to be drawn
+    /// after the opaque items
+    pub fn is_transparent(self) -> bool {
+        self != BlendMode::Opaque
+    }
+}
+
+/// Which faces of a material are culled
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub enum CullMode {
+    /// Both sides of the faces are drawn
+    None,
+    /// Faces wound clockwise are culled
+    #[default]
+    Clockwise,
+    /// Faces wound counter clockwise are culled
+    CounterClockwise,
+}
+
 /// Struct for containing material information
 #[derive(Builder, Clone, Serialize, Deserialize, PartialEq)]
 #[builder(default)]
@@ -112,6 +193,17 @@ pub struct Material {
     pub texture_name: Option<String>,
     /// The normal texture that will be used
     pub normal_texture_name: Option<String>,
+    /// How the item is blended with what is behind it
+    pub blend: BlendMode,
+    /// Whether the item writes to the depth buffer, usually off for
+    /// transparent items so they don't hide each other
+    pub depth_write: bool,
+    /// Whether the item is hidden by things in front of it
+    pub depth_test: bool,
+    /// Which faces of the item are culled
+    pub cull: CullMode,
+    /// Whether the item is drawn as the outlines of its triangles
+    pub wireframe: bool,
 }
 
 impl Default for Material {
@@ -120,10 +212,37 @@ impl Default for Material {
             shader_name: "dist".into(),
             texture_name: None,
             normal_texture_name: None,
+            blend: BlendMode::Opaque,
+            depth_write: true,
+            depth_test: true,
+            cull: CullMode::Clockwise,
+            wireframe: false,
         }
     }
 }
 
+/// The space a TextItem is positioned in
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum TextSpace {
+    /// Positioned in clip space over the scene
+    Screen,
+    /// Positioned in world space facing along the z-axis and depth tested
+    World,
+    /// Positioned in world space always facing the camera and depth tested
+    WorldBillboard,
+}
+
+/// How the lines of a TextItem are aligned to its position
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum TextAlign {
+    /// Lines start at the position
+    Left,
+    /// Lines are centered on the position
+    Center,
+    /// Lines end at the position
+    Right,
+}
+
 /// struct for abstacting text items to be rendered
 #[derive(Builder, Clone, Serialize, Deserialize, PartialEq)]
 #[builder(default)]
@@ -142,6 +261,22 @@ pub struct TextItem {
     /// The name of the RenderItem for lookup
     #[builder(setter(into))]
     pub name: String,
+    /// The space the text is positioned in
+    pub space: TextSpace,
+    /// The name of the font the text is drawn with
+    #[builder(setter(into))]
+    pub font: String,
+    /// How the lines are aligned to the position, if None world text is
+    /// centered and screen text starts at the position
+    pub align: Option<TextAlign>,
+    /// The width the text is wrapped at, in the same units as the position
+    pub max_width: Option<f32>,
+    /// The distance between each line relative to the line height
+    pub line_spacing: f32,
+    /// The localization key the text is looked up with, if any
+    pub loc_key: Option<String>,
+    /// The values substituted into the placeholders of the localized text
+    pub loc_args: Vec<(String, String)>,
 }
 
 impl Default for TextItem {
@@ -153,14 +288,222 @@ impl Default for TextItem {
             scale: (1f32, 1f32, 1f32),
             active: true,
             name: "ti".into(),
+            space: TextSpace::Screen,
+            font: "default".into(),
+            align: None,
+            max_width: None,
+            line_spacing: 1f32,
+            loc_key: None,
+            loc_args: Vec::new(),
+        }
+    }
+}
+
+impl TextItem {
+    /// How the lines are aligned to the position, falling back to the
+    /// alignment for the item's space
+    pub fn alignment(&self) -> TextAlign {
+        match (self.align, self.space) {
+            (Some(align), _) => align,
+            (None, TextSpace::Screen) => TextAlign::Left,
+            (None, _) => TextAlign::Center,
         }
     }
 }
 
 unsafe impl Send for TextItem {}
 
+/// struct for animating a sprite through the frames of a sprite sheet
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[builder(default)]
+pub struct SpriteAnimation {
+    /// The texture regions (u, v, width, height) of each frame
+    pub frames: Vec<(f32, f32, f32, f32)>,
+    /// The number of frames shown per second
+    pub fps: f32,
+    /// Whether the animation loops when it reaches the last frame
+    pub looping: bool,
+    /// Whether the animation is currently playing
+    pub playing: bool,
+    /// The time in seconds the animation has been playing
+    pub time: f32,
+}
+
+impl Default for SpriteAnimation {
+    fn default() -> Self {
+        SpriteAnimation {
+            frames: Vec::new(),
+            fps: 12f32,
+            looping: true,
+            playing: true,
+            time: 0f32,
+        }
+    }
+}
+
+impl SpriteAnimation {
+    /// Creates the frames for count cells of a sprite sheet laid out in a
+    /// grid of cols by rows, read left to right and top to bottom
+    pub fn from_grid(cols: u32, rows: u32, count: u32) -> SpriteAnimation {
+        let (w, h) = (1f32 / cols as f32, 1f32 / rows as f32);
+        let frames = (0..count.min(cols * rows))
+            .map(|i| {
+                let (col, row) = ((i % cols) as f32, (i / cols) as f32);
+                // textures are loaded flipped so v starts at the bottom
+                (col * w, 1f32 - (row + 1f32) * h, w, h)
+            })
+            .collect();
+
+        SpriteAnimation {
+            frames,
+            ..Default::default()
+        }
+    }
+
+    /// Advance the animation by delta seconds
+    pub fn update(&mut self, delta: f32) {
+        if self.playing {
+            self.time += delta;
+        }
+    }
+
+    /// Get the index of the current frame
+    pub fn current_frame(&self) -> usize {
+        if self.frames.is_empty() {
+            return 0;
+        }
+
+        let frame = (self.time * self.fps) as usize;
+        if self.looping {
+            frame % self.frames.len()
+        } else {
+            frame.min(self.frames.len() - 1)
+        }
+    }
+
+    /// Whether a non looping animation has reached its last frame
+    pub fn finished(&self) -> bool {
+        !self.looping && (self.time * self.fps) as usize >= self.frames.len()
+    }
+}
+
+/// struct for abstracting 2d sprites to be rendered over the scene
+#[derive(Builder, Clone, Serialize, Deserialize, PartialEq)]
+#[builder(default)]
+pub struct SpriteItem {
+    /// The texture the sprite is drawn with
+    #[builder(setter(into))]
+    pub texture_name: String,
+    /// The region of the texture (u, v, width, height) to draw
+    pub region: (f32, f32, f32, f32),
+    /// The position of the center of the sprite in pixels from the top left of the screen
+    pub pos: (f32, f32),
+    /// The size of the sprite in pixels
+    pub size: (f32, f32),
+    /// The rotation of the sprite in radians
+    pub rotation: f32,
+    /// The scale applied to the size of the sprite
+    pub scale: (f32, f32),
+    /// The colour the sprite is tinted with
+    pub tint: (f32, f32, f32, f32),
+    /// The draw order of the sprite, higher values are drawn on top
+    pub z: i32,
+    /// Whether the sprite is flipped on the x and y axes
+    pub flip: (bool, bool),
+    /// The animation that sets the region of the sprite
+    pub animation: Option<SpriteAnimation>,
+    /// Whether this item is active/should be rendered
+    pub active: bool,
+    /// The name of the SpriteItem for lookup
+    #[builder(setter(into))]
+    pub name: String,
+}
+
+impl Default for SpriteItem {
+    fn default() -> Self {
+        SpriteItem {
+            texture_name: "default".into(),
+            region: (0f32, 0f32, 1f32, 1f32),
+            pos: (0f32, 0f32),
+            size: (64f32, 64f32),
+            rotation: 0f32,
+            scale: (1f32, 1f32),
+            tint: (1f32, 1f32, 1f32, 1f32),
+            z: 0,
+            flip: (false, false),
+            animation: None,
+            active: true,
+            name: "si".into(),
+        }
+    }
+}
+
+impl SpriteItem {
+    /// Get the texture region to draw, taken from the animation if there is one
+    pub fn current_region(&self) -> (f32, f32, f32, f32) {
+        match self.animation {
+            Some(ref anim) if !anim.frames.is_empty() => anim.frames[anim.current_frame()],
+            _ => self.region,
+        }
+    }
+
+    /// Get the two triangles of the sprite in pixel space
+    pub fn vertices(&self) -> Vec<SpriteVertex> {
+        let (u, v, w, h) = self.current_region();
+        let (mut u0, mut u1) = (u, u + w);
+        let (mut v0, mut v1) = (v, v + h);
+        if self.flip.0 {
+            ::std::mem::swap(&mut u0, &mut u1);
+        }
+        if self.flip.1 {
+            ::std::mem::swap(&mut v0, &mut v1);
+        }
+
+        let half_w = self.size.0 * self.scale.0 / 2f32;
+        let half_h = self.size.1 * self.scale.1 / 2f32;
+        let (sin, cos) = (self.rotation.sin(), self.rotation.cos());
+        let color = [self.tint.0, self.tint.1, self.tint.2, self.tint.3];
+
+        let corner = |x: f32, y: f32, tex: [f32; 2]| SpriteVertex {
+            position: [
+                self.pos.0 + x * cos - y * sin,
+                self.pos.1 + x * sin + y * cos,
+            ],
+            texture: tex,
+            color,
+        };
+
+        // screen y points down so the top of the sprite uses v1
+        let top_left = corner(-half_w, -half_h, [u0, v1]);
+        let top_right = corner(half_w, -half_h, [u1, v1]);
+        let bottom_left = corner(-half_w, half_h, [u0, v0]);
+        let bottom_right = corner(half_w, half_h, [u1, v0]);
+
+        vec![
+            top_left,
+            bottom_left,
+            bottom_right,
+            top_left,
+            bottom_right,
+            top_right,
+        ]
+    }
+}
+
+/// struct for the vertices of batched sprites
+#[derive(Copy, Clone)]
+pub struct SpriteVertex {
+    /// The position of the vertex in pixels
+    pub position: [f32; 2],
+    /// The uv/texture coordinates
+    pub texture: [f32; 2],
+    /// The tint of the vertex
+    pub color: [f32; 4],
+}
+implement_vertex!(SpriteVertex, position, texture, color);
+
 /// struct for abstracting the camera state
-#[derive(Builder, Copy, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Builder, Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[builder(default)]
 pub struct Camera {
     /// The position of the camera in 3d space
@@ -178,6 +521,24 @@ impl Default for Camera {
     }
 }
 
+/// struct representing a ray cast into the world
+#[derive(Copy, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct Ray {
+    /// The point the ray starts from
+    pub origin: Vector3,
+    /// The normalised direction of the ray
+    pub dir: Vector3,
+}
+
+impl Default for Ray {
+    fn default() -> Self {
+        Ray {
+            origin: (0f32, 0f32, 0f32),
+            dir: (0f32, 0f32, -1f32),
+        }
+    }
+}
+
 /// struct for shader attributes
 #[derive(Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ShaderIn {
@@ -187,5 +548,16 @@ pub struct ShaderIn {
     pub world_rotation: Quaternion,
     /// The world scale to be passed to the shader
     pub world_scale: Vector3,
+    /// The tint of the instance to be passed to the shader
+    pub world_tint: (f32, f32, f32, f32),
+    /// The custom data of the instance to be passed to the shader
+    pub world_data: [f32; 4],
 }
-implement_vertex!(ShaderIn, world_position, world_rotation, world_scale);
+implement_vertex!(
+    ShaderIn,
+    world_position,
+    world_rotation,
+    world_scale,
+    world_tint,
+    world_data
+);
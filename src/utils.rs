@@ -5,8 +5,9 @@ use std::f32::consts::PI;
 use std::iter::Sum;
 use std::ops::{Add, Mul};
 
-use types::{Camera, MaterialBuilder, Matrix4, Quaternion, Vector3, Vertex};
-use types::{RenderItem, RenderItemBuilder, TransformBuilder};
+use renderer::FIELD_OF_VIEW;
+use types::{Camera, MaterialBuilder, Matrix4, Quaternion, Ray, Vector3, Vertex};
+use types::{RenderItem, RenderItemBuilder, Transform, TransformBuilder};
 
 use input::{Input, Key};
 
@@ -124,6 +125,19 @@ pub fn mul_mat4(a: Matrix4, b: Matrix4) -> Matrix4 {
     new_mat
 }
 
+/// returns the transpose of the matrix
+pub fn transpose_mat4(m: Matrix4) -> Matrix4 {
+    let mut new_mat: Matrix4 = [[0f32; 4]; 4];
+
+    for (i, row) in new_mat.iter_mut().enumerate() {
+        for (j, item) in row.iter_mut().enumerate() {
+            *item = m[j][i];
+        }
+    }
+
+    new_mat
+}
+
 /// returns a euler angle as a quaternion
 pub fn to_quaternion(angle: Vector3) -> Quaternion {
     let (c3, c1, c2) = (
@@ -259,11 +273,7 @@ pub fn handle_fp_inputs(input: &mut Input, cam: &mut Camera) {
 
     // make sure euler_rot always between 0 and 2PI
     fn fix_rot(num: f32) -> f32 {
-        if num < 0f32 {
-            return TWO_PI - num;
-        }
-
-        num % TWO_PI
+        num.rem_euclid(TWO_PI)
     }
 }
 
@@ -330,6 +340,191 @@ pub fn get_frustum_planes(matrix: &Matrix4) -> Vec<(f32, f32, f32, f32)> {
     planes
 }
 
+/// returns the vector rotated by the quaternion, matching the rotation in the default shaders
+pub fn rotate_vec3(v: [f32; 3], q: Quaternion) -> [f32; 3] {
+    let q_xyz = [q.0, q.1, q.2];
+    let c = crossp(q_xyz, v);
+    let temp = [c[0] + q.3 * v[0], c[1] + q.3 * v[1], c[2] + q.3 * v[2]];
+    let c = crossp(q_xyz, temp);
+
+    [v[0] + 2f32 * c[0], v[1] + 2f32 * c[1], v[2] + 2f32 * c[2]]
+}
+
+/// returns a vertex position with the transform's scale, rotation and position applied
+pub fn transform_point(p: [f32; 3], trans: &Transform) -> [f32; 3] {
+    let scaled = [
+        p[0] * trans.scale.0,
+        p[1] * trans.scale.1,
+        p[2] * trans.scale.2,
+    ];
+    let rotated = rotate_vec3(scaled, trans.rot);
+
+    [
+        rotated[0] + trans.pos.0,
+        rotated[1] + trans.pos.1,
+        rotated[2] + trans.pos.2,
+    ]
+}
+
+/// Returns the world space ray under a screen position for the camera, with
+/// the screen position in pixels from the top left like `Input::mouse_pos`
+pub fn unproject(screen_pos: (f32, f32), dimensions: (u32, u32), cam: &Camera) -> Ray {
+    let (width, height) = (dimensions.0 as f32, dimensions.1 as f32);
+    let tan_fov = (FIELD_OF_VIEW * (PI / 360f32)).tan();
+
+    // the direction in view space
+    let ndc_x = (2f32 * screen_pos.0 / width) - 1f32;
+    let ndc_y = 1f32 - (2f32 * screen_pos.1 / height);
+    let view_dir = [ndc_x * tan_fov * (width / height), ndc_y * tan_fov, -1f32];
+
+    // rotate by the inverse of the view matrix rotation
+    let mv_matrix = build_fp_view_matrix(cam);
+    let dir = [
+        dotp(&mv_matrix[0][0..3], &view_dir),
+        dotp(&mv_matrix[1][0..3], &view_dir),
+        dotp(&mv_matrix[2][0..3], &view_dir),
+    ];
+    let len = dotp(&dir, &dir).sqrt();
+
+    Ray {
+        origin: cam.pos,
+        dir: (dir[0] / len, dir[1] / len, dir[2] / len),
+    }
+}
+
+/// Returns the distance along the ray to where it enters the box from min to max
+pub fn ray_aabb_test(ray: &Ray, min: Vector3, max: Vector3) -> Option<f32> {
+    let origin = [ray.origin.0, ray.origin.1, ray.origin.2];
+    let dir = [ray.dir.0, ray.dir.1, ray.dir.2];
+    let (min, max) = ([min.0, min.1, min.2], [max.0, max.1, max.2]);
+
+    let mut t_min = 0f32;
+    let mut t_max = f32::MAX;
+
+    for i in 0..3 {
+        if dir[i].abs() < f32::EPSILON {
+            // parallel to the slab so must start within it
+            if origin[i] < min[i] || origin[i] > max[i] {
+                return None;
+            }
+        } else {
+            let t1 = (min[i] - origin[i]) / dir[i];
+            let t2 = (max[i] - origin[i]) / dir[i];
+
+            t_min = t_min.max(t1.min(t2));
+            t_max = t_max.min(t1.max(t2));
+
+            if t_min > t_max {
+                return None;
+            }
+        }
+    }
+
+    Some(t_min)
+}
+
+/// Returns the distance along the ray to where it hits the bounds of the transform,
+/// using the scale as the half extents like the physics colliders
+pub fn ray_transform_test(ray: &Ray, trans: &Transform) -> Option<f32> {
+    ray_aabb_test(
+        ray,
+        (
+            trans.pos.0 - trans.scale.0,
+            trans.pos.1 - trans.scale.1,
+            trans.pos.2 - trans.scale.2,
+        ),
+        (
+            trans.pos.0 + trans.scale.0,
+            trans.pos.1 + trans.scale.1,
+            trans.pos.2 + trans.scale.2,
+        ),
+    )
+}
+
+/// Returns the distance along the ray to where it hits the triangle p0, p1, p2
+pub fn ray_triangle_test(ray: &Ray, p0: [f32; 3], p1: [f32; 3], p2: [f32; 3]) -> Option<f32> {
+    let origin = [ray.origin.0, ray.origin.1, ray.origin.2];
+    let dir = [ray.dir.0, ray.dir.1, ray.dir.2];
+
+    let edge1 = sub_vec3(p1, p0);
+    let edge2 = sub_vec3(p2, p0);
+    let h = crossp(dir, edge2);
+    let a = dotp(&edge1, &h);
+
+    // ray is parallel to the triangle
+    if a.abs() < f32::EPSILON {
+        return None;
+    }
+
+    let f = 1f32 / a;
+    let s = sub_vec3(origin, p0);
+    let u = f * dotp(&s, &h);
+    if !(0f32..=1f32).contains(&u) {
+        return None;
+    }
+
+    let q = crossp(s, edge1);
+    let v = f * dotp(&dir, &q);
+    if v < 0f32 || u + v > 1f32 {
+        return None;
+    }
+
+    let t = f * dotp(&edge2, &q);
+    if t > f32::EPSILON {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+/// Returns the index of the closest instance of the render item hit by the ray
+/// against its triangles, along with the distance along the ray
+pub fn ray_render_item_test<T: Default>(ray: &Ray, item: &RenderItem<T>) -> Option<(usize, f32)> {
+    // the radius of the mesh used to skip instances the ray misses entirely
+    let mesh_radius = item
+        .vertices
+        .iter()
+        .map(|v| dotp(&v.position, &v.position))
+        .fold(0f32, f32::max)
+        .sqrt();
+    let origin = [ray.origin.0, ray.origin.1, ray.origin.2];
+    let dir = [ray.dir.0, ray.dir.1, ray.dir.2];
+
+    item.instance_transforms
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.active)
+        .filter(|(_, t)| {
+            // bounding sphere test
+            let radius = mesh_radius * t.scale.0.max(t.scale.1.max(t.scale.2));
+            let to_center = sub_vec3([t.pos.0, t.pos.1, t.pos.2], origin);
+            let along = dotp(&to_center, &dir);
+            dotp(&to_center, &to_center) - (along * along) <= radius * radius
+        })
+        .filter_map(|(i, t)| {
+            item.vertices
+                .chunks(3)
+                .filter(|tri| tri.len() == 3)
+                .filter_map(|tri| {
+                    ray_triangle_test(
+                        ray,
+                        transform_point(tri[0].position, t),
+                        transform_point(tri[1].position, t),
+                        transform_point(tri[2].position, t),
+                    )
+                })
+                .fold(None, |closest: Option<f32>, dist| match closest {
+                    Some(c) if c <= dist => Some(c),
+                    _ => Some(dist),
+                })
+                .map(|dist| (i, dist))
+        })
+        .fold(None, |closest, hit| match closest {
+            Some((_, c)) if c <= hit.1 => closest,
+            _ => Some(hit),
+        })
+}
+
 /// Helper function for creating demo's shadertoy style, so you only need to provide a fragment
 /// shader
 pub fn demo(frag_shader: &'static str) {
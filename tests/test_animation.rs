@@ -0,0 +1,121 @@
+extern crate bincode;
+extern crate caper;
+
+use std::collections::HashMap;
+
+use caper::animation::{
+    AnimationClip, AnimationClipBuilder, AnimationPlayer, Animations, Interpolation, Track,
+    TransformTrackBuilder,
+};
+use caper::types::{DefaultTag, RenderItem, RenderItemBuilder, TransformBuilder};
+
+/// A clip moving the cube along x from 0 to 2 over 2 seconds
+fn slide(name: &str, to: f32) -> AnimationClip {
+    AnimationClipBuilder::default()
+        .name(name)
+        .tracks(vec![TransformTrackBuilder::default()
+            .render_item("cube")
+            .pos(Some(Track::new(
+                vec![(0f32, (0f32, 0f32, 0f32)), (2f32, (to, 0f32, 0f32))],
+                Interpolation::Linear,
+            )))
+            .build()
+            .unwrap()])
+        .build()
+        .unwrap()
+}
+
+/// A render item named cube with one instance
+fn cube() -> Vec<RenderItem<DefaultTag>> {
+    vec![RenderItemBuilder::default()
+        .name("cube")
+        .instance_transforms(vec![TransformBuilder::default().build().unwrap()])
+        .build()
+        .unwrap()]
+}
+
+#[test]
+fn track_sample_test() {
+    let keys = vec![
+        (1f32, (1f32, 0f32, 0f32)),
+        (0f32, (0f32, 0f32, 0f32)),
+        (2f32, (3f32, 0f32, 0f32)),
+    ];
+    let step = Track::new(keys.clone(), Interpolation::Step);
+    let linear = Track::new(keys.clone(), Interpolation::Linear);
+    let cubic = Track::new(keys, Interpolation::Cubic);
+
+    assert_eq!(linear.end_time(), 2f32);
+    assert_eq!(step.sample(0.5f32), Some((0f32, 0f32, 0f32)));
+    assert_eq!(linear.sample(0.5f32), Some((0.5f32, 0f32, 0f32)));
+    assert_eq!(linear.sample(1.5f32), Some((2f32, 0f32, 0f32)));
+    // values are held outside of the keyframes
+    assert_eq!(linear.sample(-1f32), Some((0f32, 0f32, 0f32)));
+    assert_eq!(step.sample(5f32), Some((3f32, 0f32, 0f32)));
+    // the cubic passes through the keyframes
+    assert_eq!(cubic.sample(1f32), Some((1f32, 0f32, 0f32)));
+    let x = cubic.sample(1.5f32).unwrap().0;
+    assert!(x > 1f32 && x < 3f32 && x != 2f32);
+
+    let empty: Track<(f32, f32, f32)> = Track::new(vec![], Interpolation::Linear);
+    assert_eq!(empty.sample(0f32), None);
+}
+
+#[test]
+fn player_test() {
+    let mut clips = HashMap::new();
+    clips.insert("slide".to_string(), slide("slide", 2f32));
+    let mut items = cube();
+
+    let mut player = AnimationPlayer::new();
+    player.play("slide", true);
+    player.update(1f32, &clips, &mut items);
+    assert_eq!(items[0].instance_transforms[0].pos.0, 1f32);
+
+    // looping wraps the time
+    player.update(1.5f32, &clips, &mut items);
+    assert_eq!(items[0].instance_transforms[0].pos.0, 0.5f32);
+    assert!(!player.is_finished(&clips));
+
+    // speed scales the delta and clips that don't loop stop at the end
+    player.play("slide", false);
+    player.speed = 2f32;
+    player.update(0.5f32, &clips, &mut items);
+    assert_eq!(items[0].instance_transforms[0].pos.0, 1f32);
+    player.update(5f32, &clips, &mut items);
+    assert_eq!(items[0].instance_transforms[0].pos.0, 2f32);
+    assert!(player.is_finished(&clips));
+}
+
+#[test]
+fn crossfade_test() {
+    let mut animations = Animations::new();
+    animations.add_clip(slide("right", 2f32));
+    animations.add_clip(slide("left", -2f32));
+    let mut items = cube();
+
+    animations.player("cube").play("right", true);
+    animations.update(1f32, &mut items);
+    assert_eq!(items[0].instance_transforms[0].pos.0, 1f32);
+
+    // half way through the fade the clips are blended evenly
+    animations.player("cube").crossfade("left", 1f32, true);
+    animations.update(0.5f32, &mut items);
+    assert!(animations.player("cube").is_fading());
+    let x = items[0].instance_transforms[0].pos.0;
+    assert!((x - (1.5f32 - 0.5f32) / 2f32).abs() < 1e-5, "{}", x);
+
+    animations.update(0.5f32, &mut items);
+    assert!(!animations.player("cube").is_fading());
+    assert_eq!(items[0].instance_transforms[0].pos.0, -1f32);
+    assert_eq!(animations.player("cube").current().unwrap().clip, "left");
+}
+
+#[test]
+fn clip_serde_test() {
+    let clip = slide("slide", 2f32);
+    assert_eq!(clip.duration(), 2f32);
+    let bytes = bincode::serialize(&clip).unwrap();
+    let loaded: AnimationClip = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(loaded, clip);
+}
@@ -0,0 +1,138 @@
+extern crate caper;
+
+use caper::camera::animation::{
+    blend_cameras, CameraPathBuilder, CameraPathPlayer, CameraShake, CameraTransition,
+    PathInterpolation, PathLook,
+};
+use caper::camera::{
+    forward_dir, look_rotation, CameraController, FirstPersonController, FollowControllerBuilder,
+    OrbitControllerBuilder,
+};
+use caper::input::{Input, Key};
+use caper::types::Camera;
+
+fn approx(a: f32, b: f32) -> bool {
+    (a - b).abs() < 0.001
+}
+
+#[test]
+fn first_person_controller_test() {
+    let mut cam = Camera::default();
+    let mut controller = FirstPersonController::default();
+    let mut input = Input::new();
+
+    // walking forward for a second moves move_speed units down -z
+    input.keys_down.push(Key::W);
+    controller.update(&mut cam, &input, 1f32);
+    assert!(approx(cam.pos.2, -controller.move_speed));
+    assert!(approx(cam.pos.0, 0f32));
+
+    // looking up is limited by the pitch limits
+    input.keys_down.clear();
+    input.mouse_delta = (0f32, 10f32);
+    controller.update(&mut cam, &input, 0.016f32);
+    assert!(approx(cam.euler_rot.0, controller.pitch_limits.1));
+}
+
+#[test]
+fn orbit_and_follow_controller_test() {
+    let mut cam = Camera::default();
+    let input = Input::new();
+
+    let mut orbit = OrbitControllerBuilder::default()
+        .target((1f32, 2f32, 3f32))
+        .distance(5f32)
+        .smoothing(0f32)
+        .build()
+        .unwrap();
+    orbit.update(&mut cam, &input, 0.016f32);
+    let offset = (cam.pos.0 - 1f32, cam.pos.1 - 2f32, cam.pos.2 - 3f32);
+    let dist = (offset.0 * offset.0 + offset.1 * offset.1 + offset.2 * offset.2).sqrt();
+    assert!(approx(dist, 5f32));
+
+    // the camera looks at the target
+    let dir = forward_dir(cam.euler_rot.0, cam.euler_rot.1);
+    assert!(approx(dir.0, -offset.0 / dist));
+    assert!(approx(dir.1, -offset.1 / dist));
+    assert!(approx(dir.2, -offset.2 / dist));
+
+    let (pitch, yaw) = look_rotation(dir);
+    assert!(approx(pitch, cam.euler_rot.0) && approx(yaw, cam.euler_rot.1));
+
+    // a wall behind the target pulls the camera in
+    let mut follow = FollowControllerBuilder::default()
+        .pitch(0f32)
+        .obstacles(vec![((-5f32, -5f32, 2f32), (5f32, 5f32, 3f32))])
+        .build()
+        .unwrap();
+    follow.update(&mut cam, &input, 0.016f32);
+    assert!(approx(cam.pos.2, 2f32 - follow.collision_padding));
+}
+
+#[test]
+fn camera_animation_test() {
+    let input = Input::new();
+    let mut cam = Camera::default();
+
+    // the catmull-rom path passes through each point at its key time
+    let path = CameraPathBuilder::default()
+        .points(vec![
+            (0f32, 0f32, 0f32),
+            (0f32, 0f32, -10f32),
+            (10f32, 0f32, -10f32),
+        ])
+        .duration(2f32)
+        .look(PathLook::Target((0f32, 0f32, -20f32)))
+        .build()
+        .unwrap();
+    let mut player = CameraPathPlayer::new(path);
+    player.update(&mut cam, &input, 1f32);
+    assert!(approx(cam.pos.2, -10f32) && approx(cam.pos.0, 0f32));
+    assert!(approx(cam.euler_rot.1, 0f32));
+    player.update(&mut cam, &input, 5f32);
+    assert!(player.finished());
+    assert!(approx(cam.pos.0, 10f32));
+
+    // bezier paths pass through the key points
+    let bezier = CameraPathBuilder::default()
+        .points(vec![
+            (0f32, 0f32, 0f32),
+            (0f32, 5f32, 0f32),
+            (5f32, 5f32, 0f32),
+            (5f32, 0f32, 0f32),
+        ])
+        .interpolation(PathInterpolation::Bezier)
+        .duration(1f32)
+        .build()
+        .unwrap();
+    assert_eq!(bezier.key_count(), 2);
+    assert!(approx(bezier.position_at(1f32).0, 5f32));
+    assert!(approx(bezier.position_at(0.5f32).1, 3.75f32));
+
+    // transitions blend the shortest way round
+    let from = Camera {
+        pos: (0f32, 0f32, 0f32),
+        euler_rot: (0f32, 6f32, 0f32),
+    };
+    let to = Camera {
+        pos: (4f32, 0f32, 0f32),
+        euler_rot: (0f32, 0.5f32, 0f32),
+    };
+    let halfway = blend_cameras(&from, &to, 0.5f32);
+    assert!(approx(halfway.pos.0, 2f32));
+    assert!(halfway.euler_rot.1 > 6f32);
+
+    let mut transition = CameraTransition::new(from, to, 1f32);
+    transition.update(&mut cam, &input, 2f32);
+    assert!(transition.finished());
+    assert!(approx(cam.pos.0, 4f32));
+
+    // shake decays away with no trauma left
+    let mut shake = CameraShake::default();
+    shake.add_trauma(2f32);
+    assert_eq!(shake.trauma, 1f32);
+    shake.update(0.5f32);
+    assert!(approx(shake.shake(), 0.25f32));
+    shake.update(1f32);
+    assert_eq!(shake.apply(&to), to);
+}
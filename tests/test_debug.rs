@@ -0,0 +1,110 @@
+extern crate caper;
+
+use std::convert::TryInto;
+
+use caper::renderer::debug::DebugDraw;
+use caper::utils::{build_persp_proj_mat, get_frustum_planes, mul_mat4};
+
+const RED: (f32, f32, f32, f32) = (1f32, 0f32, 0f32, 1f32);
+
+/// Whether the two positions are within a small distance of each other
+fn close(a: [f32; 3], b: [f32; 3]) -> bool {
+    a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1e-3)
+}
+
+#[test]
+fn duration_test() {
+    let mut debug = DebugDraw::new();
+    debug.line((0f32, 0f32, 0f32), (1f32, 0f32, 0f32), RED, None);
+    debug.line((0f32, 0f32, 0f32), (0f32, 1f32, 0f32), RED, Some(1f32));
+    debug.ray(
+        (0f32, 0f32, 0f32),
+        (0f32, 0f32, 1f32),
+        2f32,
+        RED,
+        Some(0.5f32),
+    );
+    assert_eq!(debug.len(), 3);
+
+    // lines without a duration only last the frame
+    debug.update(0.25f32);
+    assert_eq!(debug.len(), 2);
+
+    debug.update(0.25f32);
+    assert_eq!(debug.len(), 1);
+
+    debug.update(0.5f32);
+    assert!(debug.is_empty());
+
+    debug.axes((0f32, 0f32, 0f32), 1f32, Some(10f32));
+    debug.clear();
+    assert!(debug.is_empty());
+}
+
+#[test]
+fn vertices_test() {
+    let mut debug = DebugDraw::new();
+    debug.ray((1f32, 2f32, 3f32), (0f32, 0f32, 1f32), 2f32, RED, None);
+    debug.on_top = true;
+    debug.aabb(
+        (0f32, 0f32, 0f32),
+        (1f32, 2f32, 3f32),
+        (0f32, 1f32, 0f32, 0.5f32),
+        None,
+    );
+
+    // two vertices per line split by whether they're drawn on top
+    let depth_tested = debug.vertices(false);
+    assert_eq!(depth_tested.len(), 2);
+    assert_eq!(depth_tested[0].position, [1f32, 2f32, 3f32]);
+    assert_eq!(depth_tested[1].position, [1f32, 2f32, 5f32]);
+    assert_eq!(depth_tested[1].color, [1f32, 0f32, 0f32, 1f32]);
+
+    let on_top = debug.vertices(true);
+    assert_eq!(on_top.len(), 24);
+    assert!(on_top.iter().all(|v| v.color == [0f32, 1f32, 0f32, 0.5f32]));
+    assert!(on_top.iter().any(|v| v.position == [1f32, 2f32, 3f32]));
+
+    // three circles for a sphere and two lines per division for a grid
+    debug.clear();
+    debug.sphere((0f32, 0f32, 0f32), 2f32, RED, None);
+    let sphere = debug.vertices(true);
+    assert_eq!(sphere.len(), 16 * 3 * 2);
+    assert!(sphere.iter().all(|v| {
+        let p = v.position;
+        ((p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt() - 2f32).abs() < 1e-4
+    }));
+
+    debug.clear();
+    debug.grid((0f32, 0f32, 0f32), 4f32, 4, RED, None);
+    assert_eq!(debug.len(), 10);
+}
+
+#[test]
+fn frustum_test() {
+    // a camera at the origin looking down -z
+    let identity = [
+        [1f32, 0f32, 0f32, 0f32],
+        [0f32, 1f32, 0f32, 0f32],
+        [0f32, 0f32, 1f32, 0f32],
+        [0f32, 0f32, 0f32, 1f32],
+    ];
+    let projection = build_persp_proj_mat(90f32, 1f32, 1f32, 10f32);
+    let planes = get_frustum_planes(&mul_mat4(projection, identity));
+    let planes: [_; 6] = planes[..].try_into().unwrap();
+
+    let mut debug = DebugDraw::new();
+    debug.frustum(&planes, RED, None);
+    let vertices = debug.vertices(false);
+    assert_eq!(vertices.len(), 24);
+
+    // the corners are on the near and far planes
+    for corner in &[
+        [-1f32, -1f32, -1f32],
+        [1f32, 1f32, -1f32],
+        [-10f32, 10f32, -10f32],
+        [10f32, -10f32, -10f32],
+    ] {
+        assert!(vertices.iter().any(|v| close(v.position, *corner)));
+    }
+}
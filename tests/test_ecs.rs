@@ -0,0 +1,144 @@
+extern crate caper;
+
+use caper::ecs::{physics_body_system, PhysicsBodyBuilder, Schedule, World};
+use caper::types::{DefaultTag, PhysicsType, RenderItem, Transform};
+
+use std::sync::{Arc, Mutex};
+
+/// A user defined component
+#[derive(Clone, PartialEq, Debug)]
+struct Health(i32);
+
+#[test]
+fn entity_component_test() {
+    let mut world = World::new();
+    let a = world.spawn();
+    let b = world.spawn();
+    assert_eq!(world.len(), 2);
+
+    assert_eq!(world.insert(a, Health(10)), Ok(None));
+    assert_eq!(world.insert(a, Health(5)), Ok(Some(Health(10))));
+    assert!(world.insert(b, RenderItem::<DefaultTag>::default()).is_ok());
+    assert!(world.insert(b, Transform::default()).is_ok());
+
+    assert_eq!(world.get::<Health>(a), Some(&Health(5)));
+    assert!(world.has::<Transform>(b));
+    assert!(!world.has::<Health>(b));
+    assert_eq!(world.query::<Health>(), vec![a]);
+
+    world.get_mut::<Health>(a).unwrap().0 -= 1;
+    assert_eq!(world.remove::<Health>(a), Some(Health(4)));
+    assert_eq!(world.get::<Health>(a), None);
+
+    // despawned handles stay invalid when the index is reused
+    assert!(world.despawn(b));
+    assert!(!world.despawn(b));
+    let c = world.spawn();
+    assert_eq!(c.index(), b.index());
+    assert!(!world.is_alive(b));
+    assert!(world.is_alive(c));
+    assert!(!world.has::<Transform>(c));
+    // components for despawned entities are given back
+    assert_eq!(world.insert(b, Health(1)), Err(Health(1)));
+    assert!(world.query::<Health>().is_empty());
+    assert_eq!(world.entities(), vec![a, c]);
+}
+
+#[test]
+fn join_test() {
+    let mut world = World::new();
+    let entities = (0..100)
+        .map(|i| {
+            let e = world.spawn();
+            assert!(world.insert(e, Health(i)).is_ok());
+            if i % 2 == 0 {
+                assert!(world.insert(e, Transform::default()).is_ok());
+            }
+            e
+        })
+        .collect::<Vec<_>>();
+
+    world.join_mut::<Health, Transform, _>(|_, health, transform| {
+        transform.pos.0 = health.0 as f32;
+    });
+    world.par_join_mut::<Transform, Health, _>(|_, transform, health| {
+        health.0 += transform.pos.0 as i32;
+    });
+    world.par_for_each_mut::<Health, _>(|_, health| health.0 *= 2);
+
+    assert_eq!(world.get::<Health>(entities[4]), Some(&Health(16)));
+    assert_eq!(world.get::<Health>(entities[5]), Some(&Health(10)));
+    assert_eq!(world.get::<Transform>(entities[4]).unwrap().pos.0, 4f32);
+
+    let mut total = 0;
+    world.for_each::<Health, _>(|_, health| total += health.0);
+    assert_eq!(
+        total,
+        (0..100)
+            .map(|i| if i % 2 == 0 { i * 4 } else { i * 2 })
+            .sum()
+    );
+
+    let taken = world.take_all::<Transform>();
+    assert_eq!(taken.len(), 50);
+    assert!(world.query::<Transform>().is_empty());
+}
+
+#[test]
+fn schedule_test() {
+    let mut world = World::new();
+    let e = world.spawn();
+    assert!(world.insert(e, Transform::default()).is_ok());
+    assert!(world
+        .insert(
+            e,
+            PhysicsBodyBuilder::default()
+                .velocity((1f32, 0f32, 0f32))
+                .gravity((0f32, 0f32, 0f32))
+                .build()
+                .unwrap(),
+        )
+        .is_ok());
+    let still = world.spawn();
+    assert!(world.insert(still, Transform::default()).is_ok());
+    assert!(world
+        .insert(
+            still,
+            PhysicsBodyBuilder::default()
+                .physics_type(PhysicsType::Static)
+                .velocity((1f32, 0f32, 0f32))
+                .build()
+                .unwrap(),
+        )
+        .is_ok());
+
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let mut schedule = Schedule::new();
+    let (o1, o2, o3) = (order.clone(), order.clone(), order.clone());
+    schedule.add_system("last", 10, move |_: &mut World, _| {
+        o1.lock().unwrap().push(3)
+    });
+    schedule.add_system("first", -1, move |_: &mut World, _| {
+        o2.lock().unwrap().push(1)
+    });
+    schedule.add_system("second", -1, move |_: &mut World, _| {
+        o3.lock().unwrap().push(2)
+    });
+    schedule.add_system("physics", 0, physics_body_system);
+    assert_eq!(
+        schedule.system_names(),
+        vec!["first", "second", "physics", "last"]
+    );
+
+    schedule.run(&mut world, 0.5f32);
+    assert_eq!(*order.lock().unwrap(), vec![1, 2, 3]);
+    assert_eq!(world.get::<Transform>(e).unwrap().pos, (0.5f32, 0f32, 0f32));
+    assert_eq!(
+        world.get::<Transform>(still).unwrap().pos,
+        (0f32, 0f32, 0f32)
+    );
+
+    assert!(schedule.remove_system("first"));
+    assert!(!schedule.remove_system("first"));
+    assert_eq!(schedule.system_names().len(), 3);
+}
@@ -0,0 +1,66 @@
+extern crate caper;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use caper::events::{EngineEvent, EventBus};
+
+/// A user defined event
+#[derive(Clone, PartialEq, Debug)]
+struct Scored(u32);
+
+#[test]
+fn publish_read_drain_test() {
+    let mut bus = EventBus::new();
+    assert!(bus.read::<Scored>().is_empty());
+
+    // published events are only readable after the update
+    bus.publish(Scored(1));
+    bus.publish(Scored(2));
+    bus.publish(EngineEvent::Focused(true));
+    assert!(bus.read::<Scored>().is_empty());
+
+    bus.update();
+    assert_eq!(bus.read::<Scored>(), &[Scored(1), Scored(2)]);
+    assert_eq!(bus.read::<EngineEvent>(), &[EngineEvent::Focused(true)]);
+
+    // events are dropped by the next update
+    bus.publish(Scored(3));
+    bus.update();
+    assert_eq!(bus.read::<Scored>(), &[Scored(3)]);
+    assert!(bus.read::<EngineEvent>().is_empty());
+
+    assert_eq!(bus.drain::<Scored>(), vec![Scored(3)]);
+    assert!(bus.read::<Scored>().is_empty());
+
+    bus.publish(Scored(4));
+    bus.clear();
+    bus.update();
+    assert!(bus.read::<Scored>().is_empty());
+}
+
+#[test]
+fn subscribe_test() {
+    let mut bus = EventBus::new();
+    let total = Rc::new(RefCell::new(0));
+
+    let sub_total = total.clone();
+    let id = bus.subscribe(move |e: &Scored| *sub_total.borrow_mut() += e.0);
+
+    bus.publish(Scored(5));
+    bus.publish(Scored(6));
+    assert_eq!(*total.borrow(), 0);
+    bus.update();
+    assert_eq!(*total.borrow(), 11);
+
+    // events are only delivered once
+    bus.update();
+    assert_eq!(*total.borrow(), 11);
+
+    assert!(bus.unsubscribe(id));
+    assert!(!bus.unsubscribe(id));
+    bus.publish(Scored(7));
+    bus.update();
+    assert_eq!(*total.borrow(), 11);
+    assert_eq!(bus.read::<Scored>(), &[Scored(7)]);
+}
@@ -0,0 +1,87 @@
+extern crate caper;
+
+use caper::index::{select_mut, ItemIndex};
+use caper::types::{RenderItem, RenderItemBuilder};
+
+/// Tags for grouping the test items
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+enum Tag {
+    #[default]
+    None,
+    Enemy,
+    Flying,
+    Boss,
+}
+
+/// A render item with the name and tags
+fn item(name: &str, tag: Tag, tags: Vec<Tag>) -> RenderItem<Tag> {
+    RenderItemBuilder::default()
+        .name(name)
+        .tag(tag)
+        .tags(tags)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn tag_index_test() {
+    let mut items = vec![
+        item("ground", Tag::None, vec![]),
+        item("bat", Tag::Enemy, vec![Tag::Flying]),
+        item("bat", Tag::Enemy, vec![Tag::Flying]),
+        item(
+            "dragon",
+            Tag::Boss,
+            vec![Tag::Enemy, Tag::Flying, Tag::Enemy],
+        ),
+        item("bird", Tag::None, vec![Tag::Flying]),
+    ];
+
+    let mut index = ItemIndex::new();
+    assert!(index.is_dirty());
+    index.ensure_built(&items);
+    assert!(!index.is_dirty());
+
+    assert_eq!(index.with_tag(&Tag::Enemy), &[1, 2, 3]);
+    assert_eq!(index.with_tag(&Tag::Flying), &[1, 2, 3, 4]);
+    assert_eq!(index.with_tag(&Tag::Boss), &[3]);
+    assert_eq!(index.with_name("bat"), &[1, 2]);
+    assert!(index.with_name("cat").is_empty());
+    assert_eq!(
+        index.matching(|t| *t == Tag::Boss || *t == Tag::None),
+        vec![0, 3, 4]
+    );
+    assert_eq!(index.tags().len(), 4);
+
+    // retag the bird as an enemy
+    index.remove_tags(4, &items[4]);
+    items[4].tag = Tag::Enemy;
+    items[4].tags = vec![];
+    index.insert_tags(4, &items[4]);
+    assert_eq!(index.with_tag(&Tag::Enemy), &[1, 2, 3, 4]);
+    assert_eq!(index.with_tag(&Tag::Flying), &[1, 2, 3]);
+    assert!(index.with_tag(&Tag::None).contains(&0));
+
+    // adding items rebuilds the index on next use
+    items.push(item("boss", Tag::Boss, vec![]));
+    index.invalidate();
+    index.ensure_built(&items);
+    assert_eq!(index.with_tag(&Tag::Boss), &[3, 5]);
+
+    let selected = select_mut(&mut items, index.with_tag(&Tag::Boss));
+    assert_eq!(selected.len(), 2);
+    for item in selected {
+        item.active = false;
+    }
+    assert!(!items[3].active && !items[5].active && items[4].active);
+}
+
+#[test]
+fn select_mut_test() {
+    let mut values = (0..10).collect::<Vec<i32>>();
+    for v in select_mut(&mut values, &[0, 3, 4, 9, 12]) {
+        *v *= -1;
+    }
+    assert_eq!(values, vec![0, 1, 2, -3, -4, 5, 6, 7, 8, -9]);
+    assert!(select_mut(&mut values, &[]).is_empty());
+}
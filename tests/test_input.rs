@@ -1 +1,124 @@
+extern crate caper;
 
+use caper::input::actions::{ActionMap, Binding, MouseAxis};
+use caper::input::gamepad::{GamepadAxis, GamepadButton, GamepadEvent, VirtualGamepadBackend};
+use caper::input::record::{InputFrame, InputRecorder, RecorderState};
+use caper::input::{Input, Key};
+
+#[test]
+fn action_map_test() {
+    let mut actions = ActionMap::new();
+    actions.bind_action("jump", Binding::Key(Key::Space));
+    actions.bind_action_with_modifiers("save", Binding::Key(Key::S), vec![Key::LControl]);
+    actions.bind_axis("move_z", Binding::Key(Key::W), 1f32);
+    actions.bind_axis("move_z", Binding::Key(Key::S), -1f32);
+    actions.bind_axis("look_x", Binding::MouseAxis(MouseAxis::X), 2f32);
+    actions.set_axis_dead_zone("look_x", 0.1f32);
+
+    let mut input = Input::new();
+    input.keys_down.push(Key::Space);
+    input.keys_down.push(Key::W);
+    input.mouse_delta = (0.05f32, 0f32);
+    actions.update(&input);
+
+    assert!(actions.pressed("jump"));
+    assert!(actions.just_pressed("jump"));
+    assert!(!actions.pressed("save"));
+    assert_eq!(actions.axis_value("move_z"), 1f32);
+    assert_eq!(actions.axis_value("look_x"), 0f32);
+
+    input.keys_down = vec![Key::LControl, Key::S];
+    input.mouse_delta = (0.25f32, 0f32);
+    actions.update(&input);
+
+    assert!(!actions.pressed("jump"));
+    assert!(actions.just_released("jump"));
+    assert!(actions.just_pressed("save"));
+    assert_eq!(actions.axis_value("move_z"), -1f32);
+    assert_eq!(actions.axis_value("look_x"), 0.5f32);
+    assert_eq!(actions.axis_value("unbound"), 0f32);
+}
+
+#[test]
+fn virtual_gamepad_test() {
+    let backend = VirtualGamepadBackend::new();
+    let mut input = Input::new();
+    input.gamepads.set_backend(Box::new(backend.clone()));
+
+    let mut actions = ActionMap::new();
+    actions.bind_action("jump", Binding::GamepadButton(GamepadButton::South));
+    actions.bind_axis(
+        "move_x",
+        Binding::GamepadAxis(GamepadAxis::LeftStickX),
+        1f32,
+    );
+    actions.set_axis_dead_zone("move_x", 0.2f32);
+
+    backend.connect(0, "pad");
+    backend.connect(1, "other pad");
+    backend.press(1, GamepadButton::South);
+    backend.move_axis(0, GamepadAxis::LeftStickX, -0.75f32);
+    input.gamepads.update();
+    actions.update(&input);
+
+    assert_eq!(input.gamepads.connected().count(), 2);
+    assert_eq!(
+        input.gamepads.events[0],
+        GamepadEvent::Connected(0, "pad".into())
+    );
+    assert!(input
+        .gamepads
+        .get(1)
+        .unwrap()
+        .buttons_pressed
+        .contains(&GamepadButton::South));
+    assert!(actions.just_pressed("jump"));
+    assert_eq!(actions.axis_value("move_x"), -0.75f32);
+
+    backend.move_axis(0, GamepadAxis::LeftStickX, 0.1f32);
+    backend.disconnect(1);
+    input.gamepads.update();
+    actions.update(&input);
+
+    assert_eq!(input.gamepads.connected().count(), 1);
+    assert!(input.gamepads.get(1).unwrap().buttons_pressed.is_empty());
+    assert!(actions.just_released("jump"));
+    assert_eq!(actions.axis_value("move_x"), 0f32);
+}
+
+#[test]
+fn record_replay_test() {
+    let mut recorder = InputRecorder::new();
+    let mut input = Input::new();
+
+    recorder.start_recording();
+    input.keys_down.push(Key::W);
+    input.mouse_pos = (10f32, 20f32);
+    recorder.record_frame(&input, 0.016f32);
+    input.keys_down.clear();
+    input.characters_down.push('a');
+    recorder.record_frame(&input, 0.02f32);
+    let recording = recorder.stop();
+    assert_eq!(recording.frames.len(), 2);
+
+    let mut replayed = Input::new();
+    recorder.start_replay(recording.clone());
+    assert_eq!(recorder.state(), RecorderState::Replaying);
+
+    let frame = recorder.next_frame().unwrap();
+    frame.apply(&mut replayed);
+    assert_eq!(frame.delta, 0.016f32);
+    assert_eq!(replayed.keys_down, vec![Key::W]);
+    assert_eq!(replayed.mouse_pos, (10f32, 20f32));
+
+    let frame = recorder.next_frame().unwrap();
+    frame.apply(&mut replayed);
+    assert_eq!(
+        InputFrame::capture(&replayed, frame.delta),
+        recording.frames[1]
+    );
+
+    // the recorder goes back to the live input once the replay has finished
+    assert!(recorder.next_frame().is_none());
+    assert_eq!(recorder.state(), RecorderState::Idle);
+}
@@ -0,0 +1,47 @@
+extern crate caper;
+
+use caper::localization::{LocaleTable, Localization};
+use caper::types::TextItemBuilder;
+
+#[test]
+fn translate_test() {
+    let mut loc = Localization::default();
+    loc.add_table(
+        LocaleTable::parse(
+            "en",
+            "# english\ngreeting = Hello {name}!\napples.one = {count} apple\napples.other = {count} apples",
+        )
+        .unwrap(),
+    );
+    loc.add_table(LocaleTable::parse("fr", "greeting = Bonjour {name} !").unwrap());
+
+    let args = vec![("name".to_string(), "Sam".to_string())];
+    assert_eq!(loc.translate("greeting", &args), "Hello Sam!");
+
+    let one = vec![("count".to_string(), "1".to_string())];
+    let many = vec![("count".to_string(), "3".to_string())];
+    assert_eq!(loc.translate("apples", &one), "1 apple");
+    assert_eq!(loc.translate("apples", &many), "3 apples");
+
+    // switching locale updates text items and falls back for missing keys
+    loc.set_locale("fr");
+    let mut text_items = vec![
+        TextItemBuilder::default()
+            .loc_key(Some("greeting".into()))
+            .loc_args(args)
+            .build()
+            .unwrap(),
+        TextItemBuilder::default()
+            .loc_key(Some("apples".into()))
+            .loc_args(many)
+            .build()
+            .unwrap(),
+    ];
+    loc.localize_text_items(&mut text_items);
+    assert_eq!(text_items[0].text, "Bonjour Sam !");
+    assert_eq!(text_items[1].text, "3 apples");
+
+    // missing keys show the key
+    assert_eq!(loc.translate("missing", &[]), "missing");
+    assert!(LocaleTable::parse("en", "no equals").is_err());
+}
@@ -0,0 +1,115 @@
+extern crate caper;
+
+use caper::lod::{screen_size, LodLevel, LodMetric, Lods, LodsBuilder};
+use caper::mesh::{gen_cube, gen_sphere, simplify};
+use caper::renderer::queue::{build_queue, QueueItem};
+use caper::types::{DefaultTag, RenderItemBuilder, TransformBuilder};
+
+/// Lods with a level starting at each of the thresholds
+fn lods(thresholds: &[f32], metric: LodMetric, fade: f32) -> Lods {
+    LodsBuilder::default()
+        .levels(
+            thresholds
+                .iter()
+                .map(|t| LodLevel {
+                    vertices: gen_cube(),
+                    threshold: *t,
+                })
+                .collect(),
+        )
+        .metric(metric)
+        .fade(fade)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn lod_select_test() {
+    let by_dist = lods(&[10f32, 20f32], LodMetric::Distance, 2f32);
+    assert_eq!(by_dist.select(5f32, 1f32), (0, 0f32));
+    assert_eq!(by_dist.select(9f32, 1f32), (0, 0.5f32));
+    assert_eq!(by_dist.select(15f32, 1f32), (1, 0f32));
+    assert_eq!(by_dist.select(25f32, 1f32), (2, 0f32));
+
+    // smaller on screen with distance
+    assert!((screen_size(10f32, 1f32) - 0.1732f32).abs() < 1e-4);
+    let by_size = lods(&[0.5f32, 0.1f32], LodMetric::ScreenSize, 0f32);
+    assert_eq!(by_size.select(1f32, 1f32), (0, 0f32));
+    assert_eq!(by_size.select(10f32, 1f32), (1, 0f32));
+    assert_eq!(by_size.select(100f32, 1f32), (2, 0f32));
+
+    // level 0 is the item's own vertices
+    let base = gen_sphere();
+    assert_eq!(by_size.vertices(&base, 0).len(), base.len());
+    assert_eq!(by_size.vertices(&base, 2).len(), gen_cube().len());
+}
+
+#[test]
+fn simplify_test() {
+    // every corner of the cube is in its own cell
+    let cube = gen_cube();
+    let kept = simplify(&cube, 2);
+    assert_eq!(kept.len(), cube.len());
+    assert_eq!(kept[0].position, cube[0].position);
+
+    // everything collapses into one cell
+    assert!(simplify(&cube, 1).is_empty());
+
+    let sphere = gen_sphere();
+    let simplified = simplify(&sphere, 3);
+    assert!(!simplified.is_empty());
+    assert!(simplified.len() < sphere.len());
+    assert_eq!(simplified.len() % 3, 0);
+
+    let generated = Lods::generate(&sphere, &[(10f32, 3), (20f32, 1)], LodMetric::Distance);
+    assert_eq!(generated.levels[0].vertices.len(), simplified.len());
+    assert_eq!(generated.levels[1].threshold, 20f32);
+}
+
+#[test]
+fn lod_queue_test() {
+    let render_items = vec![RenderItemBuilder::<DefaultTag>::default()
+        .lods(Some(lods(&[10f32, 20f32], LodMetric::Distance, 2f32)))
+        .instance_transforms(
+            [-5f32, -9f32, -15f32, -25f32]
+                .iter()
+                .map(|z| {
+                    TransformBuilder::default()
+                        .pos((0f32, 0f32, *z))
+                        .build()
+                        .unwrap()
+                })
+                .collect(),
+        )
+        .build()
+        .unwrap()];
+
+    // a batch for each level with the instance cross-fading drawn last at
+    // level 0 fading out then level 1 fading in
+    let queue = build_queue(&render_items, (0f32, 0f32, 0f32), &[]);
+    let batches = queue
+        .iter()
+        .map(|q| (q.level, q.instances.clone(), q.fading))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        batches,
+        vec![
+            (0, vec![0], false),
+            (1, vec![2], false),
+            (2, vec![3], false),
+            (0, vec![1], true),
+            (1, vec![1], true),
+        ]
+    );
+    assert_eq!(queue[3].fades, vec![0.5f32]);
+    assert_eq!(
+        queue[4],
+        QueueItem {
+            item: 0,
+            level: 1,
+            instances: vec![1],
+            fades: vec![0.5f32],
+            fading: true,
+        }
+    );
+}
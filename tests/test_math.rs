@@ -0,0 +1,151 @@
+extern crate caper;
+extern crate proptest;
+
+use caper::math::{Mat4, Quat, Vec3};
+use caper::types::Vector3;
+use caper::utils::rotate_vec3;
+
+use proptest::prelude::*;
+
+use std::f32::consts::PI;
+
+/// Whether the two values are within the tolerance
+fn approx(a: f32, b: f32, tol: f32) -> bool {
+    (a - b).abs() <= tol
+}
+
+/// Whether the two vectors are within the tolerance on each axis
+fn approx_vec(a: Vec3, b: Vec3, tol: f32) -> bool {
+    approx(a.x, b.x, tol) && approx(a.y, b.y, tol) && approx(a.z, b.z, tol)
+}
+
+/// Whether the two matrices are within the tolerance for each element
+fn approx_mat(a: Mat4, b: Mat4, tol: f32) -> bool {
+    (0..16).all(|i| approx(a.cols[i / 4][i % 4], b.cols[i / 4][i % 4], tol))
+}
+
+/// A vector with each component between -100 and 100
+fn vec3() -> impl Strategy<Value = Vec3> {
+    (-100f32..100f32, -100f32..100f32, -100f32..100f32).prop_map(|(x, y, z)| Vec3::new(x, y, z))
+}
+
+/// A rotation around a random axis
+fn quat() -> impl Strategy<Value = Quat> {
+    (vec3(), -PI..PI).prop_filter_map("zero length axis", |(axis, angle)| {
+        if axis.length() > 0.01f32 {
+            Some(Quat::from_axis_angle(axis, angle))
+        } else {
+            None
+        }
+    })
+}
+
+/// A scale with each component between 0.5 and 2
+fn scale() -> impl Strategy<Value = Vec3> {
+    (0.5f32..2f32, 0.5f32..2f32, 0.5f32..2f32).prop_map(|(x, y, z)| Vec3::new(x, y, z))
+}
+
+proptest! {
+    #[test]
+    fn tuple_round_trip_test(x in -100f32..100f32, y in -100f32..100f32, z in -100f32..100f32) {
+        let t: Vector3 = (x, y, z);
+        let v: Vec3 = t.into();
+        let back: Vector3 = v.into();
+        prop_assert_eq!(t, back);
+    }
+
+    #[test]
+    fn normalize_length_test(v in vec3()) {
+        prop_assume!(v.length() > 0.001f32);
+        prop_assert!(approx(v.normalize().length(), 1f32, 0.0001f32));
+    }
+
+    #[test]
+    fn cross_perpendicular_test(a in vec3(), b in vec3()) {
+        let c = a.cross(b);
+        let tol = 0.001f32 * a.length() * b.length() * (a.length() + b.length() + 1f32);
+        prop_assert!(approx(c.dot(a), 0f32, tol));
+        prop_assert!(approx(c.dot(b), 0f32, tol));
+    }
+
+    #[test]
+    fn quat_inverse_test(q in quat()) {
+        let i = q * q.inverse();
+        prop_assert!(approx(i.x, 0f32, 0.0001f32));
+        prop_assert!(approx(i.y, 0f32, 0.0001f32));
+        prop_assert!(approx(i.z, 0f32, 0.0001f32));
+        prop_assert!(approx(i.w, 1f32, 0.0001f32));
+    }
+
+    #[test]
+    fn quat_rotate_test(q in quat(), v in vec3()) {
+        let r = q * v;
+        prop_assert!(approx(r.length(), v.length(), 0.001f32 * (v.length() + 1f32)));
+
+        // matches the tuple based rotation
+        let old: Vec3 = rotate_vec3(v.into(), q.into()).into();
+        prop_assert!(approx_vec(r, old, 0.001f32 * (v.length() + 1f32)));
+
+        // matches the rotation matrix
+        let m = Mat4::from_quat(q).transform_vector(v);
+        prop_assert!(approx_vec(r, m, 0.001f32 * (v.length() + 1f32)));
+    }
+
+    #[test]
+    fn axis_angle_round_trip_test(axis in vec3(), angle in 0.01f32..3.1f32) {
+        prop_assume!(axis.length() > 0.01f32);
+        let (out_axis, out_angle) = Quat::from_axis_angle(axis, angle).to_axis_angle();
+        prop_assert!(approx(out_angle, angle, 0.001f32));
+        prop_assert!(approx_vec(out_axis, axis.normalize(), 0.001f32));
+    }
+
+    #[test]
+    fn slerp_test(a in quat(), b in quat(), t in 0f32..1f32) {
+        let start = a.slerp(b, 0f32);
+        let end = a.slerp(b, 1f32);
+        // q and -q are the same rotation
+        prop_assert!(approx(start.dot(a).abs(), 1f32, 0.0001f32));
+        prop_assert!(approx(end.dot(b).abs(), 1f32, 0.0001f32));
+        prop_assert!(approx(a.slerp(b, t).length(), 1f32, 0.0001f32));
+    }
+
+    #[test]
+    fn mat_inverse_test(t in vec3(), r in quat(), s in scale()) {
+        let m = Mat4::from_trs(t, r, s);
+        let inv = m.inverse().unwrap();
+        prop_assert!(approx_mat(inv * m, Mat4::identity(), 0.001f32));
+        prop_assert!(approx_mat(m * inv, Mat4::identity(), 0.001f32));
+    }
+
+    #[test]
+    fn mat_transpose_test(t in vec3(), r in quat(), s in scale()) {
+        let m = Mat4::from_trs(t, r, s);
+        prop_assert_eq!(m.transpose().transpose(), m);
+        prop_assert!(approx(m.transpose().determinant(), m.determinant(), 0.001f32));
+    }
+
+    #[test]
+    fn transform_point_test(t in vec3(), r in quat(), s in scale(), p in vec3()) {
+        let m = Mat4::from_trs(t, r, s);
+        let expected = r * (p * s) + t;
+        prop_assert!(approx_vec(m.transform_point(p), expected, 0.01f32));
+        let back = m.inverse().unwrap().transform_point(m.transform_point(p));
+        prop_assert!(approx_vec(back, p, 0.01f32));
+    }
+}
+
+#[test]
+fn look_at_test() {
+    let eye = Vec3::new(0f32, 0f32, 5f32);
+    let view = Mat4::look_at(eye, Vec3::default(), Vec3::new(0f32, 1f32, 0f32));
+
+    // the target ends up straight ahead down -z
+    let target = view.transform_point(Vec3::default());
+    assert!(approx_vec(target, Vec3::new(0f32, 0f32, -5f32), 0.0001f32));
+
+    let rot = Quat::look_rotation(-eye, Vec3::new(0f32, 1f32, 0f32));
+    let forward = rot * Vec3::new(0f32, 0f32, -1f32);
+    assert!(approx_vec(forward, Vec3::new(0f32, 0f32, -1f32), 0.0001f32));
+
+    assert!(Mat4::from_scale(Vec3::default()).inverse().is_none());
+}
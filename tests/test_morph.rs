@@ -0,0 +1,148 @@
+extern crate caper;
+
+use caper::morph::{MorphTarget, Morphs, MorphsBuilder};
+use caper::renderer::lighting::DirectionalLight;
+use caper::renderer::posteffect::PostShaderOptions;
+use caper::tween::{Easing, Tween, TweenItems, TweenTarget, TweenValue};
+use caper::types::{DefaultTag, RenderItem, RenderItemBuilder, TextItem, Vertex};
+
+/// A single vertex at the position
+fn vertex(x: f32, y: f32, z: f32) -> Vertex {
+    Vertex {
+        position: [x, y, z],
+        normal: [0f32, 1f32, 0f32],
+        texture: [0f32, 0f32],
+    }
+}
+
+/// Two vertices with a target that raises them and one that widens them
+fn morphs() -> (Vec<Vertex>, Morphs) {
+    let base = vec![vertex(-1f32, 0f32, 0f32), vertex(1f32, 0f32, 0f32)];
+    let raise = MorphTarget::from_vertices(
+        "raise",
+        &base,
+        &[vertex(-1f32, 1f32, 0f32), vertex(1f32, 1f32, 0f32)],
+    )
+    .unwrap();
+    let widen = MorphTarget::from_vertices(
+        "widen",
+        &base,
+        &[vertex(-2f32, 0f32, 0f32), vertex(2f32, 0f32, 0f32)],
+    )
+    .unwrap();
+
+    let morphs = MorphsBuilder::default()
+        .targets(vec![raise, widen])
+        .build()
+        .unwrap();
+    (base, morphs)
+}
+
+#[test]
+fn from_vertices_test() {
+    let (base, morphs) = morphs();
+    assert_eq!(morphs.targets()[0].deltas[1].position, [0f32, 1f32, 0f32]);
+    assert_eq!(morphs.targets()[1].deltas[0].position, [-1f32, 0f32, 0f32]);
+    assert_eq!(morphs.targets()[1].deltas[0].normal, [0f32, 0f32, 0f32]);
+    assert!(morphs.matches(2));
+    assert!(!morphs.matches(3));
+
+    // the target must have a vertex for each of the base's
+    assert!(MorphTarget::from_vertices("bad", &base, &base[..1]).is_err());
+}
+
+#[test]
+fn weights_test() {
+    let (base, mut morphs) = morphs();
+
+    // missing weights are 0
+    assert_eq!(morphs.weight(3, 0), 0f32);
+    assert_eq!(morphs.instance_weights(1), vec![0f32, 0f32]);
+
+    assert!(morphs.set_weight(1, 1, 0.5f32));
+    assert!(morphs.set_weight_by_name(1, "raise", 1f32));
+    assert!(!morphs.set_weight(0, 2, 1f32));
+    assert!(!morphs.set_weight_by_name(0, "frown", 1f32));
+    assert_eq!(morphs.instance_weights(0), vec![0f32, 0f32]);
+    assert_eq!(morphs.instance_weights(1), vec![1f32, 0.5f32]);
+
+    // the weights blend the targets per instance
+    assert_eq!(morphs.apply(&base, 0)[1].position, base[1].position);
+    let blended = morphs.apply(&base, 1);
+    assert_eq!(blended[0].position, [-1.5f32, 1f32, 0f32]);
+    assert_eq!(blended[1].position, [1.5f32, 1f32, 0f32]);
+
+    // a position and normal texel per vertex of each target
+    let texels = morphs.delta_texels();
+    assert_eq!(texels.len(), 8);
+    assert_eq!(texels[2], (0f32, 1f32, 0f32, 0f32));
+    assert_eq!(texels[4], (-1f32, 0f32, 0f32, 0f32));
+}
+
+#[test]
+fn tween_weight_test() {
+    let (_, morphs) = morphs();
+    let mut render_items = vec![RenderItem::<DefaultTag> {
+        morphs: Some(morphs),
+        ..Default::default()
+    }];
+    let mut text_items: Vec<TextItem> = Vec::new();
+    let mut cams = Vec::new();
+    let mut lights = vec![DirectionalLight::default()];
+    let mut options = PostShaderOptions::default();
+    let mut items = TweenItems {
+        render_items: &mut render_items,
+        text_items: &mut text_items,
+        cams: &mut cams,
+        lights: &mut lights,
+        post_shader_options: &mut options,
+        lights_changed: false,
+    };
+
+    let target = TweenTarget::MorphWeight(0, 0, 1);
+    assert_eq!(items.get(&target), Some(TweenValue::Float(0f32)));
+    assert_eq!(items.get(&TweenTarget::MorphWeight(0, 0, 2)), None);
+
+    let mut tween = Tween::new(target, TweenValue::Float(1f32), 1f32, Easing::Linear);
+    tween.advance(0.25f32, &mut items);
+    assert_eq!(items.get(&target), Some(TweenValue::Float(0.25f32)));
+    tween.advance(1f32, &mut items);
+    assert!(tween.is_finished());
+    assert_eq!(
+        items.render_items[0].morphs.as_ref().unwrap().weight(0, 1),
+        1f32
+    );
+}
+
+#[test]
+fn morphs_validation_test() {
+    let (base, morphs) = morphs();
+
+    // the targets must all have a delta for the same vertices
+    let short = MorphTarget::from_vertices("short", &base[..1], &base[..1]).unwrap();
+    let mut targets = morphs.targets().to_vec();
+    targets.push(short);
+    assert!(MorphsBuilder::default().targets(targets).build().is_err());
+
+    // the targets need a delta for each of the item's vertices
+    assert!(RenderItemBuilder::<DefaultTag>::default()
+        .vertices(base[..1].to_vec())
+        .morphs(Some(morphs.clone()))
+        .build()
+        .is_err());
+    assert!(RenderItemBuilder::<DefaultTag>::default()
+        .vertices(base)
+        .morphs(Some(morphs))
+        .build()
+        .is_ok());
+}
+
+#[test]
+fn morphs_id_test() {
+    // clones share the cached deltas of their targets, new morphs don't
+    let (_, first) = morphs();
+    let (_, second) = morphs();
+    assert_eq!(first.clone().id(), first.id());
+    assert_ne!(second.id(), first.id());
+    assert_ne!(Morphs::default().id(), Morphs::default().id());
+}
@@ -0,0 +1,113 @@
+extern crate caper;
+
+use caper::particles::{ParticleEmitter, ParticleEmitterBuilder};
+
+/// An emitter without spread so the particles are predictable
+fn steady_emitter(spawn_rate: f32, max_particles: usize) -> ParticleEmitter {
+    ParticleEmitterBuilder::default()
+        .pos((1f32, 0f32, 0f32))
+        .spawn_rate(spawn_rate)
+        .max_particles(max_particles)
+        .lifetime(1f32)
+        .velocity((0f32, 2f32, 0f32))
+        .velocity_spread((0f32, 0f32, 0f32))
+        .gravity((0f32, -1f32, 0f32))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn update_test() {
+    let mut emitter = steady_emitter(10f32, 100);
+
+    // the fraction of a particle is carried over to the next update
+    emitter.update(0.15f32);
+    assert_eq!(emitter.particles.len(), 1);
+    emitter.update(0.05f32);
+    assert_eq!(emitter.particles.len(), 2);
+
+    // particles are moved by their velocity after gravity is applied
+    let particle = emitter.particles[0];
+    assert_eq!(particle.pos.0, 1f32);
+    assert!((particle.pos.1 - 0.0975f32).abs() < 1e-5);
+    assert_eq!(emitter.particles[1].pos, (1f32, 0f32, 0f32));
+    assert!((particle.velocity.1 - 1.95f32).abs() < 1e-5);
+    assert!((particle.age - 0.05f32).abs() < 1e-5);
+    assert!((particle.life() - 0.05f32).abs() < 1e-5);
+
+    // dead particles are removed
+    emitter.emitting = false;
+    emitter.update(0.97f32);
+    assert_eq!(emitter.particles.len(), 1);
+    emitter.update(0.1f32);
+    assert!(emitter.particles.is_empty());
+
+    // no more than the maximum are alive at once
+    let mut capped = steady_emitter(100f32, 5);
+    capped.update(0.5f32);
+    assert_eq!(capped.particles.len(), 5);
+}
+
+#[test]
+fn burst_test() {
+    let mut emitter = steady_emitter(0f32, 8);
+    emitter.burst(5);
+    assert_eq!(emitter.particles.len(), 5);
+    assert!(emitter.particles.iter().all(|p| p.pos == emitter.pos));
+
+    // bursts are capped at the maximum too
+    emitter.burst(5);
+    assert_eq!(emitter.particles.len(), 8);
+
+    // the spread randomises the velocity the same way for the same seed
+    let mut spread = emitter.clone();
+    spread.velocity_spread = (1f32, 1f32, 1f32);
+    spread.particles.clear();
+    let mut same = spread.clone();
+    spread.burst(2);
+    same.burst(2);
+    assert_eq!(spread.particles, same.particles);
+    assert_ne!(spread.particles[0].velocity, spread.particles[1].velocity);
+}
+
+#[test]
+fn color_at_test() {
+    let emitter = ParticleEmitterBuilder::default()
+        .start_color((1f32, 0f32, 0f32, 1f32))
+        .end_color((0f32, 0f32, 1f32, 0f32))
+        .start_size(1f32)
+        .end_size(3f32)
+        .build()
+        .unwrap();
+
+    assert_eq!(emitter.color_at(0f32), (1f32, 0f32, 0f32, 1f32));
+    assert_eq!(emitter.color_at(0.5f32), (0.5f32, 0f32, 0.5f32, 0.5f32));
+    assert_eq!(emitter.color_at(1f32), (0f32, 0f32, 1f32, 0f32));
+    assert_eq!(emitter.size_at(0.25f32), 1.5f32);
+}
+
+#[test]
+fn speed_over_life_test() {
+    let mut emitter = ParticleEmitterBuilder::default()
+        .lifetime(1f32)
+        .velocity((0f32, 1f32, 0f32))
+        .velocity_spread((0f32, 0f32, 0f32))
+        .start_speed(2f32)
+        .end_speed(0f32)
+        .emitting(false)
+        .build()
+        .unwrap();
+    assert_eq!(emitter.speed_at(0f32), 2f32);
+    assert_eq!(emitter.speed_at(0.25f32), 1.5f32);
+    assert_eq!(emitter.speed_at(1f32), 0f32);
+
+    // particles move at the speed for their life
+    emitter.burst(1);
+    emitter.update(0.5f32);
+    assert_eq!(emitter.particles[0].pos, (0f32, 1f32, 0f32));
+    emitter.update(0.25f32);
+    assert_eq!(emitter.particles[0].pos, (0f32, 1.25f32, 0f32));
+
+    // the velocity itself is left alone
+    assert_eq!(emitter.particles[0].velocity, (0f32, 1f32, 0f32));
+}
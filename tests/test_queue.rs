@@ -0,0 +1,86 @@
+extern crate caper;
+
+use caper::renderer::queue::{build_queue, QueueItem};
+use caper::types::{
+    BlendMode, DefaultTag, MaterialBuilder, RenderItem, RenderItemBuilder, TransformBuilder,
+    Vector3,
+};
+
+/// A render item with the blend mode and an instance at each of the positions
+fn item(blend: BlendMode, positions: &[Vector3]) -> RenderItem<DefaultTag> {
+    RenderItemBuilder::default()
+        .material(MaterialBuilder::default().blend(blend).build().unwrap())
+        .instance_transforms(
+            positions
+                .iter()
+                .map(|p| TransformBuilder::default().pos(*p).build().unwrap())
+                .collect(),
+        )
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn queue_order_test() {
+    let mut render_items = vec![
+        item(
+            BlendMode::Alpha,
+            &[(0f32, 0f32, -2f32), (0f32, 0f32, -6f32)],
+        ),
+        item(
+            BlendMode::Opaque,
+            &[(0f32, 0f32, -8f32), (0f32, 0f32, -3f32)],
+        ),
+        item(BlendMode::Additive, &[(0f32, 0f32, -4f32)]),
+        item(BlendMode::Opaque, &[(0f32, 0f32, -1f32)]),
+        item(BlendMode::Opaque, &[]),
+        item(BlendMode::Opaque, &[(0f32, 0f32, -1f32)]),
+    ];
+    render_items[5].instance_transforms[0].active = false;
+
+    // no frustum planes so nothing is culled
+    let queue = build_queue(&render_items, (0f32, 0f32, 0f32), &[]);
+
+    // opaque front to back then transparent back to front, items without
+    // visible instances are skipped
+    assert_eq!(
+        queue,
+        vec![
+            QueueItem {
+                item: 3,
+                level: 0,
+                instances: vec![0],
+                fades: vec![1f32],
+                fading: false,
+            },
+            QueueItem {
+                item: 1,
+                level: 0,
+                instances: vec![1, 0],
+                fades: vec![1f32, 1f32],
+                fading: false,
+            },
+            QueueItem {
+                item: 0,
+                level: 0,
+                instances: vec![1, 0],
+                fades: vec![1f32, 1f32],
+                fading: false,
+            },
+            QueueItem {
+                item: 2,
+                level: 0,
+                instances: vec![0],
+                fades: vec![1f32],
+                fading: false,
+            },
+        ]
+    );
+
+    // the order follows the camera
+    let queue = build_queue(&render_items, (0f32, 0f32, -10f32), &[]);
+    let order = queue.iter().map(|q| q.item).collect::<Vec<_>>();
+    assert_eq!(order, vec![1, 3, 0, 2]);
+    assert_eq!(queue[0].instances, vec![0, 1]);
+    assert_eq!(queue[2].instances, vec![0, 1]);
+}
@@ -0,0 +1,268 @@
+extern crate caper;
+
+use caper::lighting::DirectionalLightBuilder;
+use caper::math::{Quat, Vec3};
+use caper::scene::{camera_euler, camera_rotation, SceneGraph, SceneItems, SceneNode};
+use caper::types::{
+    Camera, DefaultTag, RenderItem, RenderItemBuilder, TextItemBuilder, Transform,
+    TransformBuilder, Vector3,
+};
+
+use std::f32::consts::FRAC_PI_2;
+
+/// Whether the two vectors are within a small tolerance on each axis
+fn approx(a: Vector3, b: Vector3) -> bool {
+    (a.0 - b.0).abs() < 0.0001 && (a.1 - b.1).abs() < 0.0001 && (a.2 - b.2).abs() < 0.0001
+}
+
+/// A render item with an instance at each of the positions
+fn item(positions: &[Vector3]) -> RenderItem<DefaultTag> {
+    RenderItemBuilder::default()
+        .instance_transforms(
+            positions
+                .iter()
+                .map(|p| TransformBuilder::default().pos(*p).build().unwrap())
+                .collect(),
+        )
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn instance_hierarchy_test() {
+    let mut render_items = vec![item(&[(1f32, 0f32, 0f32), (0f32, 0f32, 0f32)])];
+    let parent = SceneNode::Instance(0, 0);
+    let child = SceneNode::Instance(0, 1);
+
+    let mut scene = SceneGraph::new();
+    let local = TransformBuilder::default()
+        .pos((0f32, 0f32, -2f32))
+        .build()
+        .unwrap();
+    scene.set_parent(child, parent, local).unwrap();
+    assert_eq!(scene.parent(child), Some(parent));
+    assert_eq!(scene.children(parent), vec![child]);
+
+    // the child follows the parent as it moves and rotates
+    render_items[0].instance_transforms[0].rot =
+        Quat::from_axis_angle(Vec3::new(0f32, 1f32, 0f32), FRAC_PI_2).into();
+    render_items[0].instance_transforms[0].scale = (2f32, 2f32, 2f32);
+    // the tint of the child is its own
+    render_items[0].instance_transforms[1].tint = (1f32, 0f32, 0f32, 1f32);
+    {
+        let mut items = SceneItems {
+            render_items: &mut render_items,
+            text_items: &mut [],
+            cams: &mut [],
+            lights: &mut [],
+        };
+        assert!(!scene.update(&mut items));
+    }
+    let world = render_items[0].instance_transforms[1];
+    assert!(approx(world.pos, (-3f32, 0f32, 0f32)));
+    assert!(approx(world.scale, (2f32, 2f32, 2f32)));
+    assert_eq!(world.tint, (1f32, 0f32, 0f32, 1f32));
+
+    // deactivating the parent deactivates the child
+    render_items[0].instance_transforms[0].active = false;
+    let mut items = SceneItems {
+        render_items: &mut render_items,
+        text_items: &mut [],
+        cams: &mut [],
+        lights: &mut [],
+    };
+    scene.update(&mut items);
+    assert!(!items.render_items[0].instance_transforms[1].active);
+
+    // a parent can't be attached to its own child
+    assert!(scene
+        .set_parent(parent, child, Transform::default())
+        .is_err());
+    assert!(scene
+        .set_parent(child, child, Transform::default())
+        .is_err());
+}
+
+#[test]
+fn reparent_keeps_world_test() {
+    let mut render_items = vec![item(&[
+        (5f32, 0f32, 0f32),
+        (0f32, 3f32, 0f32),
+        (1f32, 1f32, 1f32),
+    ])];
+    render_items[0].instance_transforms[1].rot =
+        Quat::from_axis_angle(Vec3::new(1f32, 0f32, 0f32), 0.5f32).into();
+    let mut text_items = vec![TextItemBuilder::default()
+        .pos((1f32, 2f32, 0f32))
+        .build()
+        .unwrap()];
+
+    let (a, b) = (SceneNode::Instance(0, 0), SceneNode::Instance(0, 1));
+    let child = SceneNode::Instance(0, 2);
+    let text = SceneNode::Text(0);
+
+    let mut scene = SceneGraph::new();
+    let mut items = SceneItems {
+        render_items: &mut render_items,
+        text_items: &mut text_items,
+        cams: &mut [],
+        lights: &mut [],
+    };
+
+    // attaching doesn't move the child
+    scene.attach(child, a, &items).unwrap();
+    scene.attach(text, child, &items).unwrap();
+    scene.update(&mut items);
+    assert!(approx(
+        items.render_items[0].instance_transforms[2].pos,
+        (1f32, 1f32, 1f32)
+    ));
+    assert!(approx(items.text_items[0].pos, (1f32, 2f32, 0f32)));
+
+    // moving to another parent doesn't move the child
+    scene.attach(child, b, &items).unwrap();
+    scene.update(&mut items);
+    assert_eq!(scene.parent(child), Some(b));
+    assert!(approx(
+        items.render_items[0].instance_transforms[2].pos,
+        (1f32, 1f32, 1f32)
+    ));
+
+    // the text follows when the new parent moves
+    items.render_items[0].instance_transforms[1].pos = (0f32, 4f32, 0f32);
+    scene.update(&mut items);
+    assert!(approx(items.text_items[0].pos, (1f32, 3f32, 0f32)));
+
+    // detaching leaves the child where it is
+    let link = scene.detach(child, &mut items).unwrap();
+    assert_eq!(link.parent, b);
+    assert_eq!(scene.parent(child), None);
+    assert!(approx(
+        items.render_items[0].instance_transforms[2].pos,
+        (1f32, 2f32, 1f32)
+    ));
+
+    assert!(scene
+        .attach(child, SceneNode::Instance(3, 0), &items)
+        .is_err());
+}
+
+#[test]
+fn camera_and_light_test() {
+    let cam = Camera {
+        pos: (0f32, 0f32, 0f32),
+        euler_rot: (0.3f32, 1.2f32, 0.1f32),
+    };
+    let (pitch, yaw, roll) = camera_euler(camera_rotation(&cam));
+    assert!(approx((pitch, yaw, roll), cam.euler_rot));
+
+    let mut render_items = vec![item(&[(0f32, 0f32, 0f32)])];
+    let mut cams = vec![Camera::default()];
+    let mut lights = vec![DirectionalLightBuilder::default()
+        .dir((0f32, 0f32, -2f32))
+        .build()
+        .unwrap()];
+
+    let parent = SceneNode::Instance(0, 0);
+    let mut scene = SceneGraph::new();
+    {
+        let items = SceneItems {
+            render_items: &mut render_items,
+            text_items: &mut [],
+            cams: &mut cams,
+            lights: &mut lights,
+        };
+        scene.attach(SceneNode::Camera(0), parent, &items).unwrap();
+        scene.attach(SceneNode::Light(0), parent, &items).unwrap();
+    }
+
+    // turn the parent to the left
+    render_items[0].instance_transforms[0].pos = (0f32, 1f32, 0f32);
+    render_items[0].instance_transforms[0].rot =
+        Quat::from_axis_angle(Vec3::new(0f32, 1f32, 0f32), FRAC_PI_2).into();
+    let mut items = SceneItems {
+        render_items: &mut render_items,
+        text_items: &mut [],
+        cams: &mut cams,
+        lights: &mut lights,
+    };
+    assert!(scene.update(&mut items));
+
+    assert!(approx(items.cams[0].pos, (0f32, 1f32, 0f32)));
+    assert!(approx(items.cams[0].euler_rot, (0f32, FRAC_PI_2, 0f32)));
+    assert!(approx(items.lights[0].dir, (-2f32, 0f32, 0f32)));
+}
+
+#[test]
+fn remove_test() {
+    let mut render_items = vec![
+        item(&[(1f32, 0f32, 0f32)]),
+        item(&[(5f32, 0f32, 0f32)]),
+        item(&[(0f32, 1f32, 0f32), (0f32, 2f32, 0f32)]),
+    ];
+    let mut text_items = vec![
+        TextItemBuilder::default().build().unwrap(),
+        TextItemBuilder::default().build().unwrap(),
+    ];
+
+    let mut scene = SceneGraph::new();
+    {
+        let items = SceneItems {
+            render_items: &mut render_items,
+            text_items: &mut text_items,
+            cams: &mut [],
+            lights: &mut [],
+        };
+        scene
+            .attach(SceneNode::Instance(2, 0), SceneNode::Instance(0, 0), &items)
+            .unwrap();
+        scene
+            .attach(SceneNode::Instance(2, 1), SceneNode::Instance(1, 0), &items)
+            .unwrap();
+        scene
+            .attach(SceneNode::Text(1), SceneNode::Instance(2, 1), &items)
+            .unwrap();
+    }
+
+    // removing the parent's item drops the link rather than moving it onto the next item
+    render_items.remove(0);
+    scene.remove_render_item(0);
+    assert_eq!(scene.parent(SceneNode::Instance(1, 0)), None);
+    assert_eq!(
+        scene.parent(SceneNode::Instance(1, 1)),
+        Some(SceneNode::Instance(0, 0))
+    );
+    assert_eq!(
+        scene.parent(SceneNode::Text(1)),
+        Some(SceneNode::Instance(1, 1))
+    );
+
+    render_items[0].instance_transforms[0].pos = (6f32, 0f32, 0f32);
+    let mut items = SceneItems {
+        render_items: &mut render_items,
+        text_items: &mut text_items,
+        cams: &mut [],
+        lights: &mut [],
+    };
+    scene.update(&mut items);
+    assert!(approx(
+        items.render_items[1].instance_transforms[0].pos,
+        (0f32, 1f32, 0f32)
+    ));
+    assert!(approx(
+        items.render_items[1].instance_transforms[1].pos,
+        (1f32, 2f32, 0f32)
+    ));
+
+    // removing an earlier text item moves the link down with the item
+    scene.remove(SceneNode::Text(0));
+    assert_eq!(
+        scene.parent(SceneNode::Text(0)),
+        Some(SceneNode::Instance(1, 1))
+    );
+
+    // removing an instance drops its children
+    scene.remove(SceneNode::Instance(1, 1));
+    assert!(scene.children(SceneNode::Instance(1, 1)).is_empty());
+    assert_eq!(scene.parent(SceneNode::Text(0)), None);
+}
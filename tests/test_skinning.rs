@@ -0,0 +1,149 @@
+extern crate caper;
+
+use std::collections::HashMap;
+
+use caper::animation::{AnimationClipBuilder, Interpolation, JointTrackBuilder, Track};
+use caper::math::{Mat4, Quat, Vec3};
+use caper::mesh::gen_quad;
+use caper::skinning::{JointBuilder, Skeleton, Skin, SkinVertex};
+use caper::types::{DefaultTag, RenderItemBuilder, TransformBuilder};
+
+/// A two joint arm, the elbow one unit above the shoulder
+fn arm() -> Skeleton {
+    Skeleton::from_bind_pose(vec![
+        JointBuilder::default().name("shoulder").build().unwrap(),
+        JointBuilder::default()
+            .name("elbow")
+            .parent(Some(0))
+            .bind(
+                TransformBuilder::default()
+                    .pos((0f32, 1f32, 0f32))
+                    .rot((0f32, 0f32, 0f32, 1f32))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    ])
+    .unwrap()
+}
+
+/// Assert that the vectors are near each other
+fn assert_near(a: Vec3, b: Vec3) {
+    assert!((a - b).length() < 1e-4, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn skin_vertex_test() {
+    // the lightest influence is dropped
+    let vertex = SkinVertex::new(&[
+        (0, 1f32),
+        (1, 4f32),
+        (2, 0f32),
+        (3, 2f32),
+        (4, 0.5f32),
+        (5, 1f32),
+    ]);
+    assert_eq!(vertex.joints[0], 1);
+    assert_eq!(vertex.weights.iter().sum::<f32>(), 1f32);
+    assert_eq!(vertex.weights[0], 0.5f32);
+    assert!(!vertex.joints.contains(&4));
+
+    assert_eq!(SkinVertex::new(&[]), SkinVertex::default());
+}
+
+#[test]
+fn skeleton_test() {
+    let bad = Skeleton::new(vec![JointBuilder::default()
+        .name("root")
+        .parent(Some(0))
+        .build()
+        .unwrap()]);
+    assert!(bad.is_err());
+
+    let skeleton = arm();
+    assert_eq!(skeleton.len(), 2);
+    assert_eq!(skeleton.joint_index("elbow"), Some(1));
+    assert_eq!(skeleton.joint_index("knee"), None);
+
+    // in the bind pose the joints don't move the vertices
+    for m in skeleton.bind_pose().joint_matrices(&skeleton) {
+        let p = Mat4::from(m).transform_point(Vec3::new(0.5f32, 2f32, -1f32));
+        assert_near(p, Vec3::new(0.5f32, 2f32, -1f32));
+    }
+
+    // rotating the shoulder swings the elbow around it
+    let mut pose = skeleton.bind_pose();
+    pose.locals[0].rot =
+        Quat::from_axis_angle(Vec3::new(0f32, 0f32, 1f32), 90f32.to_radians()).into();
+    let matrices = pose.joint_matrices(&skeleton);
+    let hand = Mat4::from(matrices[1]).transform_point(Vec3::new(0f32, 2f32, 0f32));
+    assert_near(hand, Vec3::new(-2f32, 0f32, 0f32));
+
+    // blending half way back to the bind pose
+    pose.blend(&skeleton.bind_pose(), 0.5f32);
+    let angle = Quat::from(pose.locals[0].rot).to_axis_angle().1;
+    assert!((angle - 45f32.to_radians()).abs() < 1e-4);
+}
+
+#[test]
+fn skin_player_test() {
+    let bend = AnimationClipBuilder::default()
+        .name("bend")
+        .joints(vec![JointTrackBuilder::default()
+            .joint("elbow")
+            .rot(Some(Track::new(
+                vec![
+                    (0f32, (0f32, 0f32, 0f32, 1f32)),
+                    (
+                        1f32,
+                        Quat::from_axis_angle(Vec3::new(0f32, 0f32, 1f32), 90f32.to_radians())
+                            .into(),
+                    ),
+                ],
+                Interpolation::Linear,
+            )))
+            .build()
+            .unwrap()])
+        .build()
+        .unwrap();
+    assert_eq!(bend.duration(), 1f32);
+    let mut clips = HashMap::new();
+    clips.insert("bend".to_string(), bend);
+
+    let mut skin = Skin::new(
+        arm(),
+        vec![SkinVertex::new(&[(1, 1f32)]), SkinVertex::new(&[(0, 1f32)])],
+    )
+    .unwrap();
+    assert_eq!(skin.joint_matrices().len(), 2);
+
+    skin.player.play("bend", false);
+    skin.update(1f32, &clips);
+
+    // the forearm is bent, the upper arm stays where it is
+    let matrices = skin.joint_matrices();
+    let hand = Mat4::from(matrices[1]).transform_point(Vec3::new(0f32, 2f32, 0f32));
+    assert_near(hand, Vec3::new(-1f32, 1f32, 0f32));
+    let upper = Mat4::from(matrices[0]).transform_point(Vec3::new(0f32, 0.5f32, 0f32));
+    assert_near(upper, Vec3::new(0f32, 0.5f32, 0f32));
+}
+
+#[test]
+fn skin_validation_test() {
+    // the vertex is influenced by a joint the skeleton doesn't have
+    assert!(Skin::new(arm(), vec![SkinVertex::new(&[(2, 1f32)])]).is_err());
+
+    // the skin needs a vertex for each of the item's
+    let skin = Skin::new(arm(), vec![SkinVertex::new(&[(0, 1f32)]); 3]).unwrap();
+    assert!(RenderItemBuilder::<DefaultTag>::default()
+        .vertices(gen_quad())
+        .skin(Some(skin.clone()))
+        .build()
+        .is_err());
+    assert!(RenderItemBuilder::<DefaultTag>::default()
+        .vertices(gen_quad()[..3].to_vec())
+        .skin(Some(skin))
+        .build()
+        .is_ok());
+}
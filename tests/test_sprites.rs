@@ -0,0 +1,92 @@
+extern crate caper;
+
+use caper::types::{SpriteAnimation, SpriteAnimationBuilder, SpriteItemBuilder};
+
+#[test]
+fn current_frame_test() {
+    let mut looping = SpriteAnimationBuilder::default()
+        .frames(vec![(0f32, 0f32, 1f32, 1f32); 4])
+        .fps(10f32)
+        .build()
+        .unwrap();
+    assert_eq!(looping.current_frame(), 0);
+    looping.update(0.25f32);
+    assert_eq!(looping.current_frame(), 2);
+
+    // wraps around to the first frame
+    looping.update(0.2f32);
+    assert_eq!(looping.current_frame(), 0);
+    assert!(!looping.finished());
+
+    // a paused animation stays on its frame
+    looping.playing = false;
+    looping.update(1f32);
+    assert_eq!(looping.current_frame(), 0);
+
+    // stops on the last frame
+    let mut once = SpriteAnimation {
+        looping: false,
+        playing: true,
+        time: 0f32,
+        ..looping.clone()
+    };
+    once.update(0.35f32);
+    assert!(!once.finished());
+    assert_eq!(once.current_frame(), 3);
+    once.update(1f32);
+    assert!(once.finished());
+    assert_eq!(once.current_frame(), 3);
+
+    assert_eq!(SpriteAnimation::default().current_frame(), 0);
+}
+
+#[test]
+fn from_grid_test() {
+    let anim = SpriteAnimation::from_grid(4, 2, 6);
+    assert_eq!(anim.frames.len(), 6);
+
+    // read left to right from the top row, v starts at the bottom
+    assert_eq!(anim.frames[0], (0f32, 0.5f32, 0.25f32, 0.5f32));
+    assert_eq!(anim.frames[3], (0.75f32, 0.5f32, 0.25f32, 0.5f32));
+    assert_eq!(anim.frames[4], (0f32, 0f32, 0.25f32, 0.5f32));
+
+    // no more frames than cells
+    assert_eq!(SpriteAnimation::from_grid(2, 2, 10).frames.len(), 4);
+}
+
+#[test]
+fn vertices_test() {
+    let mut sprite = SpriteItemBuilder::default()
+        .pos((100f32, 50f32))
+        .size((20f32, 10f32))
+        .region((0f32, 0f32, 0.5f32, 0.25f32))
+        .build()
+        .unwrap();
+
+    // two triangles, the top of the sprite uses the top of the region
+    let vertices = sprite.vertices();
+    assert_eq!(vertices.len(), 6);
+    assert_eq!(vertices[0].position, [90f32, 45f32]);
+    assert_eq!(vertices[0].texture, [0f32, 0.25f32]);
+    assert_eq!(vertices[2].position, [110f32, 55f32]);
+    assert_eq!(vertices[2].texture, [0.5f32, 0f32]);
+
+    // flipping swaps the texture coordinates but not the positions
+    sprite.flip = (true, false);
+    let flipped = sprite.vertices();
+    assert_eq!(flipped[0].position, [90f32, 45f32]);
+    assert_eq!(flipped[0].texture, [0.5f32, 0.25f32]);
+    assert_eq!(flipped[2].texture, [0f32, 0f32]);
+
+    sprite.flip = (false, true);
+    let flipped = sprite.vertices();
+    assert_eq!(flipped[0].texture, [0f32, 0f32]);
+    assert_eq!(flipped[2].texture, [0.5f32, 0.25f32]);
+
+    // the animation's frame replaces the region
+    sprite.flip = (false, false);
+    sprite.animation = Some(SpriteAnimation::from_grid(2, 1, 2));
+    sprite.animation.as_mut().unwrap().update(0.1f32);
+    assert_eq!(sprite.current_region(), (0.5f32, 0f32, 0.5f32, 1f32));
+    assert_eq!(sprite.vertices()[0].texture, [0.5f32, 1f32]);
+}
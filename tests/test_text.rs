@@ -0,0 +1,109 @@
+extern crate caper;
+
+use caper::renderer::font::{line_offset, world_text_matrix, wrap_text};
+use caper::types::{Camera, Matrix4, TextAlign, TextItemBuilder, TextSpace};
+use caper::utils::build_fp_view_matrix;
+
+/// The direction in view space of the world space direction
+fn view_dir(modelview: Matrix4, dir: [f32; 4]) -> [f32; 3] {
+    let axis = |r: usize| (0..3).map(|i| modelview[i][r] * dir[i]).sum::<f32>();
+    [axis(0), axis(1), axis(2)]
+}
+
+/// Whether the two directions are within a small distance of each other
+fn close(a: [f32; 3], b: [f32; 3]) -> bool {
+    a.iter().zip(b.iter()).all(|(a, b)| (a - b).abs() < 1e-5)
+}
+
+#[test]
+fn world_text_matrix_test() {
+    let mut text_item = TextItemBuilder::default()
+        .text("hi")
+        .pos((1f32, 2f32, 3f32))
+        .scale((2f32, 3f32, 1f32))
+        .space(TextSpace::World)
+        .build()
+        .unwrap();
+    let modelview = build_fp_view_matrix(&Camera {
+        pos: (0f32, 0f32, 5f32),
+        euler_rot: (0.3f32, 1.2f32, 0f32),
+    });
+
+    // world text faces along z whatever the camera, offset in scaled text units
+    let matrix = world_text_matrix(&text_item, modelview, (0.5f32, -1f32));
+    assert_eq!(matrix[0], [2f32, 0f32, 0f32, 0f32]);
+    assert_eq!(matrix[1], [0f32, 3f32, 0f32, 0f32]);
+    assert_eq!(matrix[2], [0f32, 0f32, 1f32, 0f32]);
+    assert_eq!(matrix[3], [2f32, -1f32, 3f32, 1f32]);
+
+    // billboards are laid out along the camera's right and up
+    text_item.space = TextSpace::WorldBillboard;
+    let matrix = world_text_matrix(&text_item, modelview, (0f32, 0f32));
+    assert!(close(view_dir(modelview, matrix[0]), [2f32, 0f32, 0f32]));
+    assert!(close(view_dir(modelview, matrix[1]), [0f32, 3f32, 0f32]));
+    assert!(close(view_dir(modelview, matrix[2]), [0f32, 0f32, 1f32]));
+    assert_eq!(matrix[3], [1f32, 2f32, 3f32, 1f32]);
+
+    // so the offset moves across the screen rather than along world x
+    let offset = world_text_matrix(&text_item, modelview, (1f32, 0f32))[3];
+    let moved = [offset[0] - 1f32, offset[1] - 2f32, offset[2] - 3f32, 0f32];
+    assert!(close(view_dir(modelview, moved), [2f32, 0f32, 0f32]));
+}
+
+/// Measures text as one unit per character
+fn chars(text: &str) -> f32 {
+    text.chars().count() as f32
+}
+
+#[test]
+fn wrap_text_test() {
+    // without a width only new lines split the text
+    assert_eq!(
+        wrap_text("the quick\nbrown fox", None, chars),
+        vec!["the quick", "brown fox"]
+    );
+
+    // words are moved to the next line once it's too wide
+    assert_eq!(
+        wrap_text("the quick brown fox jumps", Some(10f32), chars),
+        vec!["the quick", "brown fox", "jumps"]
+    );
+
+    // words wider than the width get a line of their own
+    assert_eq!(
+        wrap_text("a tremendously big word", Some(5f32), chars),
+        vec!["a", "tremendously", "big", "word"]
+    );
+
+    // empty lines are kept
+    assert_eq!(
+        wrap_text("one\n\ntwo", Some(10f32), chars),
+        vec!["one", "", "two"]
+    );
+}
+
+#[test]
+fn alignment_test() {
+    // world text is centered unless it's aligned otherwise
+    let mut text_item = TextItemBuilder::default().build().unwrap();
+    assert_eq!(text_item.alignment(), TextAlign::Left);
+    text_item.space = TextSpace::World;
+    assert_eq!(text_item.alignment(), TextAlign::Center);
+    text_item.space = TextSpace::WorldBillboard;
+    assert_eq!(text_item.alignment(), TextAlign::Center);
+    text_item.align = Some(TextAlign::Right);
+    assert_eq!(text_item.alignment(), TextAlign::Right);
+}
+
+#[test]
+fn line_offset_test() {
+    assert_eq!(line_offset(TextAlign::Left, 4f32, 0, 1f32), (0f32, 0f32));
+    assert_eq!(
+        line_offset(TextAlign::Center, 4f32, 1, 1f32),
+        (-2f32, -1f32)
+    );
+    assert_eq!(
+        line_offset(TextAlign::Right, 4f32, 2, 1.5f32),
+        (-4f32, -3f32)
+    );
+}
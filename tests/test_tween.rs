@@ -0,0 +1,223 @@
+extern crate caper;
+
+use caper::renderer::lighting::DirectionalLight;
+use caper::renderer::posteffect::PostShaderOptions;
+use caper::tween::{
+    Easing, PostShaderField, Repeat, Timer, Tween, TweenBuilder, TweenItems, TweenNode,
+    TweenTarget, TweenValue, Tweener,
+};
+use caper::types::{Camera, DefaultTag, RenderItem, TextItem};
+
+/// Assert that the camera position is near the expected value
+fn assert_pos(cams: &[Camera], x: f32) {
+    assert!(
+        (cams[0].pos.0 - x).abs() < 1e-4,
+        "{} != {}",
+        cams[0].pos.0,
+        x
+    );
+}
+
+#[test]
+fn easing_test() {
+    let easings = [
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::SineIn,
+        Easing::SineOut,
+        Easing::SineInOut,
+        Easing::ExpoIn,
+        Easing::ExpoOut,
+        Easing::ExpoInOut,
+        Easing::BackIn,
+        Easing::BackOut,
+        Easing::BackInOut,
+        Easing::ElasticIn,
+        Easing::ElasticOut,
+        Easing::BounceIn,
+        Easing::BounceOut,
+        Easing::BounceInOut,
+    ];
+    for easing in easings.iter() {
+        assert!(easing.ease(0f32).abs() < 1e-3, "{:?}", easing);
+        assert!((easing.ease(1f32) - 1f32).abs() < 1e-3, "{:?}", easing);
+        assert_eq!(easing.ease(2f32), easing.ease(1f32));
+    }
+    assert_eq!(Easing::Linear.ease(0.25f32), 0.25f32);
+    assert_eq!(Easing::QuadIn.ease(0.5f32), 0.25f32);
+    assert!(Easing::BackIn.ease(0.2f32) < 0f32);
+    assert!(Easing::BackOut.ease(0.8f32) > 1f32);
+}
+
+#[test]
+fn tween_sequence_test() {
+    let mut render_items: Vec<RenderItem<DefaultTag>> = Vec::new();
+    let mut text_items: Vec<TextItem> = Vec::new();
+    let mut cams = vec![Camera {
+        pos: (0f32, 0f32, 0f32),
+        euler_rot: (0f32, 0f32, 0f32),
+    }];
+    let mut lights = vec![DirectionalLight::default()];
+    let mut options = PostShaderOptions::default();
+
+    let mut tweener = Tweener::new();
+    let id = tweener.play(TweenNode::sequence(vec![
+        Tween::new(
+            TweenTarget::CameraPos(0),
+            TweenValue::Vec3((2f32, 0f32, 0f32)),
+            1f32,
+            Easing::Linear,
+        )
+        .into(),
+        TweenNode::wait(0.5f32),
+        TweenNode::parallel(vec![
+            Tween::new(
+                TweenTarget::CameraPos(0),
+                TweenValue::Vec3((0f32, 0f32, 0f32)),
+                1f32,
+                Easing::Linear,
+            )
+            .into(),
+            Tween::new(
+                TweenTarget::PostShader(PostShaderField::Noise),
+                TweenValue::Float(1f32),
+                2f32,
+                Easing::Linear,
+            )
+            .into(),
+        ]),
+    ]));
+    let missing = tweener.play(Tween::new(
+        TweenTarget::TextPos(3),
+        TweenValue::Vec3((0f32, 0f32, 0f32)),
+        1f32,
+        Easing::Linear,
+    ));
+
+    let mut step = |tweener: &mut Tweener, delta: f32| {
+        let mut items = TweenItems {
+            render_items: &mut render_items,
+            text_items: &mut text_items,
+            cams: &mut cams,
+            lights: &mut lights,
+            post_shader_options: &mut options,
+            lights_changed: false,
+        };
+        let update = tweener.update(delta, &mut items);
+        (update, items.cams[0], items.post_shader_options.noise)
+    };
+
+    // tweens of items that don't exist finish straight away
+    let (update, cam, _) = step(&mut tweener, 0.5f32);
+    assert_eq!(update.finished, vec![missing]);
+    assert_pos(&[cam], 1f32);
+
+    // the leftover time carries into the wait
+    let (_, cam, _) = step(&mut tweener, 0.75f32);
+    assert_pos(&[cam], 2f32);
+    let (_, cam, noise) = step(&mut tweener, 0.75f32);
+    assert_pos(&[cam], 1f32);
+    assert!((noise - 0.25f32).abs() < 1e-4);
+
+    // the parallel group waits for the longest tween
+    let (update, cam, _) = step(&mut tweener, 1f32);
+    assert_pos(&[cam], 0f32);
+    assert!(update.finished.is_empty());
+    assert!(tweener.is_playing(id));
+    let (update, _, noise) = step(&mut tweener, 1f32);
+    assert_eq!(update.finished, vec![id]);
+    assert_eq!(noise, 1f32);
+    assert_eq!(tweener.playing(), 0);
+}
+
+#[test]
+fn tween_yoyo_test() {
+    let mut render_items: Vec<RenderItem<DefaultTag>> = Vec::new();
+    let mut text_items: Vec<TextItem> = Vec::new();
+    let mut cams: Vec<Camera> = Vec::new();
+    let mut lights = vec![DirectionalLight::default()];
+    let mut options = PostShaderOptions::default();
+    let mut items = TweenItems {
+        render_items: &mut render_items,
+        text_items: &mut text_items,
+        cams: &mut cams,
+        lights: &mut lights,
+        post_shader_options: &mut options,
+        lights_changed: false,
+    };
+
+    let mut tween = TweenBuilder::default()
+        .target(TweenTarget::LightDir(0))
+        .from(Some(TweenValue::Vec3((0f32, 0f32, 0f32))))
+        .to(TweenValue::Vec3((1f32, 0f32, 0f32)))
+        .duration(1f32)
+        .repeat(Repeat::Times(3))
+        .yoyo(true)
+        .build()
+        .unwrap();
+
+    assert_eq!(tween.advance(0.25f32, &mut items), 0f32);
+    assert_eq!(items.lights[0].dir.0, 0.25f32);
+    assert!(items.lights_changed);
+
+    // the second play goes back to the start
+    tween.advance(1f32, &mut items);
+    assert_eq!(items.lights[0].dir.0, 0.75f32);
+
+    // the third play ends at the end
+    let leftover = tween.advance(2f32, &mut items);
+    assert!(tween.is_finished());
+    assert_eq!(leftover, 0.25f32);
+    assert_eq!(items.lights[0].dir.0, 1f32);
+
+    // looping a group resets the tween back to its start
+    tween.reset();
+    let mut looped = TweenNode::looped(tween.into(), Repeat::Forever);
+    looped.advance(3.5f32, &mut items);
+    assert!(!looped.is_finished());
+    assert_eq!(items.lights[0].dir.0, 0.5f32);
+}
+
+#[test]
+fn timer_test() {
+    let mut once = Timer::once(1f32);
+    assert_eq!(once.update(0.5f32), 0);
+    assert_eq!(once.remaining(), 0.5f32);
+    assert_eq!(once.update(2f32), 1);
+    assert!(once.just_fired() && once.is_finished());
+    assert_eq!(once.update(2f32), 0);
+
+    let mut repeating = Timer::repeating(0.5f32);
+    assert_eq!(repeating.update(1.25f32), 2);
+    assert_eq!(repeating.progress(), 0.5f32);
+    repeating.paused = true;
+    assert_eq!(repeating.update(1f32), 0);
+
+    let mut tweener = Tweener::new();
+    let id = tweener.start_timer(Timer::once(1f32));
+    let mut render_items: Vec<RenderItem<DefaultTag>> = Vec::new();
+    let mut text_items: Vec<TextItem> = Vec::new();
+    let mut cams: Vec<Camera> = Vec::new();
+    let mut lights: Vec<DirectionalLight> = Vec::new();
+    let mut options = PostShaderOptions::default();
+    let mut items = TweenItems {
+        render_items: &mut render_items,
+        text_items: &mut text_items,
+        cams: &mut cams,
+        lights: &mut lights,
+        post_shader_options: &mut options,
+        lights_changed: false,
+    };
+
+    assert!(tweener.update(0.5f32, &mut items).fired.is_empty());
+    assert_eq!(tweener.update(0.5f32, &mut items).fired, vec![id]);
+    assert!(tweener.fired(id));
+    // one-shot timers are removed the update after they fire
+    tweener.update(0.5f32, &mut items);
+    assert!(tweener.timer(id).is_none());
+}
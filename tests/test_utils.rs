@@ -1 +1,134 @@
+extern crate caper;
 
+use std::f32::consts::FRAC_PI_2;
+
+use caper::types::{Camera, Ray, TransformBuilder};
+use caper::utils::{
+    build_fp_view_matrix, ray_aabb_test, ray_transform_test, ray_triangle_test, unproject,
+};
+
+#[test]
+fn unproject_center_test() {
+    let cam = Camera::default();
+    let ray = unproject((400f32, 300f32), (800, 600), &cam);
+
+    assert!(ray.dir.0.abs() < 0.0001);
+    assert!(ray.dir.1.abs() < 0.0001);
+    assert!((ray.dir.2 + 1f32).abs() < 0.0001);
+}
+
+#[test]
+fn ray_intersection_test() {
+    let ray = Ray::default();
+
+    // box in front of the ray
+    let hit = ray_aabb_test(&ray, (-1f32, -1f32, -6f32), (1f32, 1f32, -4f32));
+    assert_eq!(hit, Some(4f32));
+
+    // box behind the ray
+    let miss = ray_aabb_test(&ray, (-1f32, -1f32, 4f32), (1f32, 1f32, 6f32));
+    assert_eq!(miss, None);
+
+    let trans = TransformBuilder::default()
+        .pos((0f32, 0f32, -5f32))
+        .build()
+        .unwrap();
+    assert_eq!(ray_transform_test(&ray, &trans), Some(4f32));
+
+    let tri_hit = ray_triangle_test(
+        &ray,
+        [-1f32, -1f32, -2f32],
+        [1f32, -1f32, -2f32],
+        [0f32, 1f32, -2f32],
+    );
+    assert_eq!(tri_hit, Some(2f32));
+}
+
+#[test]
+fn unproject_moved_camera_test() {
+    // a translated camera casts from its position in the same direction
+    let cam = Camera {
+        pos: (3f32, -2f32, 7f32),
+        ..Default::default()
+    };
+    let ray = unproject((400f32, 300f32), (800, 600), &cam);
+    assert_eq!(ray.origin, (3f32, -2f32, 7f32));
+    assert!((ray.dir.2 + 1f32).abs() < 0.0001);
+
+    // the top left of the screen is up and to the left
+    let ray = unproject((0f32, 0f32), (800, 600), &cam);
+    assert!(ray.dir.0 < 0f32 && ray.dir.1 > 0f32 && ray.dir.2 < 0f32);
+    assert!((ray.dir.0 / ray.dir.1 + 800f32 / 600f32).abs() < 0.0001);
+
+    // a camera turned a quarter turn looks along x
+    let cam = Camera {
+        pos: (1f32, 2f32, 3f32),
+        euler_rot: (0f32, FRAC_PI_2, 0f32),
+    };
+    let ray = unproject((400f32, 300f32), (800, 600), &cam);
+    assert_eq!(ray.origin, (1f32, 2f32, 3f32));
+    assert!((ray.dir.0.abs() - 1f32).abs() < 0.0001);
+    assert!(ray.dir.1.abs() < 0.0001);
+    assert!(ray.dir.2.abs() < 0.0001);
+
+    // which is the camera's forward in view space
+    let view = build_fp_view_matrix(&cam);
+    let dir = [ray.dir.0, ray.dir.1, ray.dir.2];
+    let view_dir = (0..3)
+        .map(|r| (0..3).map(|i| view[i][r] * dir[i]).sum::<f32>())
+        .collect::<Vec<_>>();
+    assert!(view_dir[0].abs() < 0.0001);
+    assert!(view_dir[1].abs() < 0.0001);
+    assert!((view_dir[2] + 1f32).abs() < 0.0001);
+
+    // looking down picks things below the camera
+    let cam = Camera {
+        euler_rot: (-FRAC_PI_2, 0f32, 0f32),
+        ..Default::default()
+    };
+    let ray = unproject((400f32, 300f32), (800, 600), &cam);
+    let floor = ray_aabb_test(&ray, (-1f32, -6f32, -1f32), (1f32, -5f32, 1f32));
+    let ceiling = ray_aabb_test(&ray, (-1f32, 5f32, -1f32), (1f32, 6f32, 1f32));
+    assert!((floor.unwrap() - 5f32).abs() < 0.0001);
+    assert_eq!(ceiling, None);
+}
+
+#[test]
+fn ray_miss_test() {
+    let ray = Ray::default();
+
+    // box to the side of the ray
+    let beside = ray_aabb_test(&ray, (2f32, -1f32, -6f32), (4f32, 1f32, -4f32));
+    assert_eq!(beside, None);
+
+    // parallel to a slab the ray starts outside of
+    let parallel = ray_aabb_test(&ray, (-1f32, 1f32, -6f32), (1f32, 3f32, -4f32));
+    assert_eq!(parallel, None);
+
+    // triangle off to the side
+    let tri_beside = ray_triangle_test(
+        &ray,
+        [2f32, -1f32, -2f32],
+        [4f32, -1f32, -2f32],
+        [3f32, 1f32, -2f32],
+    );
+    assert_eq!(tri_beside, None);
+
+    // triangle behind the ray
+    let tri_behind = ray_triangle_test(
+        &ray,
+        [-1f32, -1f32, 2f32],
+        [1f32, -1f32, 2f32],
+        [0f32, 1f32, 2f32],
+    );
+    assert_eq!(tri_behind, None);
+
+    // triangle edge on to the ray
+    let tri_edge_on = ray_triangle_test(
+        &ray,
+        [0f32, -1f32, -1f32],
+        [0f32, -1f32, -3f32],
+        [0f32, 1f32, -2f32],
+    );
+    assert_eq!(tri_edge_on, None);
+}